@@ -0,0 +1,591 @@
+use crate::{
+    arweave::{
+        manifest::{PathManifest, MANIFEST_CONTENT_TYPE},
+        ArweaveTag,
+    },
+    blog_client::{BlogClient, POST_INDEX_PATH, POST_MEDIA_PATH},
+    media::PostManifest,
+    storage::{content_type_for_path, ContentId},
+    types::{BlogClientError, Result},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Archive schema version written by this client
+pub const ARCHIVE_VERSION: u32 = 1;
+/// Manifest path of the provenance record added to imported posts
+pub const POST_IMPORT_PATH: &str = "import.json";
+
+/// The `Blog` account as exported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedBlog {
+    pub pubkey: String,
+    pub authority: String,
+    pub title: String,
+    pub description: String,
+    pub post_count: u64,
+    pub created_at: i64,
+}
+
+/// A file from a post's path manifest, with its bytes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedFile {
+    pub path: String,
+    /// Id the file had in the source store
+    pub id: String,
+    pub content_type: String,
+    #[serde(serialize_with = "serialize_base64", deserialize_with = "deserialize_base64")]
+    pub data: Vec<u8>,
+}
+
+/// A `BlogPost` account as exported, with every file of its content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedPost {
+    pub pubkey: String,
+    pub author: String,
+    pub title: String,
+    pub content: String,
+    pub arweave_hash: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub files: Vec<ArchivedFile>,
+}
+
+/// A self-contained copy of a blog: its accounts plus the bodies and media
+/// they point at, so it can be restored without the source cluster or store
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlogArchive {
+    pub version: u32,
+    pub exported_at: i64,
+    pub program_id: String,
+    pub blog: ArchivedBlog,
+    /// Oldest first, the order they are recreated in
+    pub posts: Vec<ArchivedPost>,
+}
+
+impl BlogArchive {
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let archive: Self = serde_json::from_slice(data).map_err(|e| BlogClientError {
+            message: format!("Failed to parse blog archive: {}", e),
+        })?;
+        if archive.version > ARCHIVE_VERSION {
+            return Err(BlogClientError {
+                message: format!(
+                    "Blog archive version {} is newer than supported version {}",
+                    archive.version, ARCHIVE_VERSION
+                ),
+            });
+        }
+        Ok(archive)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec_pretty(self).map_err(|e| BlogClientError {
+            message: format!("Failed to serialize blog archive: {}", e),
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path).map_err(|e| BlogClientError {
+            message: format!("Failed to read {}: {}", path.display(), e),
+        })?;
+        Self::from_bytes(&data)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_bytes()?).map_err(|e| BlogClientError {
+            message: format!("Failed to write {}: {}", path.display(), e),
+        })
+    }
+}
+
+/// Where an imported post came from, stored as `import.json` in its manifest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportRecord {
+    pub source_program: String,
+    pub source_blog: String,
+    pub source_post: String,
+    pub source_author: String,
+    pub source_content_id: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// New pubkeys for an imported blog and its posts
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportReport {
+    pub blog: Pubkey,
+    /// Source post pubkey to the post recreated from it
+    pub posts: BTreeMap<String, Pubkey>,
+}
+
+/// Progress of an `import_blog`, saved next to the archive so an
+/// interrupted import continues instead of starting a second copy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportProgress {
+    pub source_blog: String,
+    /// The new blog, recorded before its transaction is sent
+    pub blog: Option<String>,
+    /// Source post pubkey to the post created from it
+    pub posts: BTreeMap<String, String>,
+    /// Source and new post whose transaction was sent but not yet confirmed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_post: Option<(String, String)>,
+}
+
+impl ImportProgress {
+    /// Progress at `path`, or a fresh one for `archive` if there is none
+    pub fn load(path: &Path, archive: &BlogArchive) -> Result<Self> {
+        let progress: Self = match std::fs::read(path) {
+            Ok(data) => serde_json::from_slice(&data).map_err(|e| BlogClientError {
+                message: format!("Failed to parse {}: {}", path.display(), e),
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self {
+                    source_blog: archive.blog.pubkey.clone(),
+                    blog: None,
+                    posts: BTreeMap::new(),
+                    pending_post: None,
+                })
+            }
+            Err(e) => {
+                return Err(BlogClientError {
+                    message: format!("Failed to read {}: {}", path.display(), e),
+                })
+            }
+        };
+        if progress.source_blog != archive.blog.pubkey {
+            return Err(BlogClientError {
+                message: format!(
+                    "{} records an import of blog {}, not {}",
+                    path.display(),
+                    progress.source_blog,
+                    archive.blog.pubkey
+                ),
+            });
+        }
+        Ok(progress)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let data = serde_json::to_vec_pretty(self).map_err(|e| BlogClientError {
+            message: format!("Failed to serialize import progress: {}", e),
+        })?;
+        // Write then rename so an interrupted import never truncates it
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, data)
+            .and_then(|_| std::fs::rename(&temp_path, path))
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to save {}: {}", path.display(), e),
+            })
+    }
+}
+
+fn parse_pubkey(value: &str) -> Result<Pubkey> {
+    Pubkey::from_str(value).map_err(|e| BlogClientError {
+        message: format!("Invalid pubkey {}: {}", value, e),
+    })
+}
+
+fn serialize_base64<S: Serializer>(data: &[u8], serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&STANDARD.encode(data))
+}
+
+fn deserialize_base64<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Vec<u8>, D::Error> {
+    let encoded = String::deserialize(deserializer)?;
+    STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+}
+
+impl BlogClient {
+    /// Read a blog, all of its posts and every file in their manifests into
+    /// a `BlogArchive`
+    pub async fn export_blog(&self, blog_pubkey: Pubkey) -> Result<BlogArchive> {
+        let blog = self.get_blog(blog_pubkey).await?;
+        let mut posts = self.get_posts_by_blog(blog_pubkey).await?;
+        posts.sort_by_key(|post| (post.created_at, post.pubkey));
+
+        let mut archived_posts = Vec::with_capacity(posts.len());
+        for post in posts {
+            let files = self.export_post_files(post.pubkey, &post.arweave_hash).await?;
+            archived_posts.push(ArchivedPost {
+                pubkey: post.pubkey.to_string(),
+                author: post.author.to_string(),
+                title: post.title,
+                content: post.content,
+                arweave_hash: post.arweave_hash,
+                created_at: post.created_at,
+                updated_at: post.updated_at,
+                files,
+            });
+        }
+
+        let exported_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default();
+
+        Ok(BlogArchive {
+            version: ARCHIVE_VERSION,
+            exported_at,
+            program_id: self.program_id().to_string(),
+            blog: ArchivedBlog {
+                pubkey: blog.pubkey.to_string(),
+                authority: blog.authority.to_string(),
+                title: blog.title,
+                description: blog.description,
+                post_count: blog.post_count,
+                created_at: blog.created_at,
+            },
+            posts: archived_posts,
+        })
+    }
+
+    /// Every file in the manifest of the post content at `arweave_hash`.
+    /// Posts stored before path manifests export as the equivalent
+    /// `index.md` and `data.json` files.
+    async fn export_post_files(&self, post_pubkey: Pubkey, arweave_hash: &str) -> Result<Vec<ArchivedFile>> {
+        let manifest = self.get_post_manifest(arweave_hash).await?;
+        let mut files = Vec::new();
+        for (path, id) in manifest.paths() {
+            let data = self
+                .content_store()
+                .get(&ContentId::from(id))
+                .await
+                .map_err(|e| BlogClientError {
+                    message: format!("Failed to export {} of post {}: {}", path, post_pubkey, e.message),
+                })?;
+            files.push(ArchivedFile {
+                path: path.to_string(),
+                id: id.to_string(),
+                content_type: content_type_for_path(Path::new(path)).to_string(),
+                data,
+            });
+        }
+        Ok(files)
+    }
+
+    /// Recreate `archive` on the cluster and content store this client
+    /// points at, as a new blog owned by `authority`. Every file is
+    /// re-uploaded and media manifests are rewritten to the new ids. The
+    /// program stamps new timestamps, so each post's original pubkeys and
+    /// times are kept in an `import.json` file in its manifest.
+    ///
+    /// Progress is saved to `progress_path` before and after every
+    /// transaction. Running the import again with the same file continues
+    /// into the same blog and skips posts already created; a transaction
+    /// that was sent when the import stopped is checked on chain first.
+    pub async fn import_blog(
+        &self,
+        authority: &Keypair,
+        archive: &BlogArchive,
+        progress_path: &Path,
+    ) -> Result<ImportReport> {
+        let mut progress = ImportProgress::load(progress_path, archive)?;
+
+        let existing_blog = progress.blog.as_deref().map(parse_pubkey).transpose()?;
+        let blog_pubkey = match existing_blog {
+            Some(blog) if self.account_exists(&blog)? => blog,
+            _ => {
+                let blog_keypair = Keypair::new();
+                progress.blog = Some(blog_keypair.pubkey().to_string());
+                progress.save(progress_path)?;
+                self.initialize_blog_account(
+                    authority,
+                    &blog_keypair,
+                    archive.blog.title.clone(),
+                    archive.blog.description.clone(),
+                )
+                .await?;
+                blog_keypair.pubkey()
+            }
+        };
+
+        if let Some((source, post)) = progress.pending_post.take() {
+            if self.account_exists(&parse_pubkey(&post)?)? {
+                progress.posts.insert(source, post);
+            }
+            progress.save(progress_path)?;
+        }
+
+        for post in &archive.posts {
+            if progress.posts.contains_key(&post.pubkey) {
+                continue;
+            }
+            let post_keypair = Keypair::new();
+            let instruction = self
+                .import_post(authority, blog_pubkey, &post_keypair, archive, post)
+                .await?;
+
+            progress.pending_post = Some((post.pubkey.clone(), post_keypair.pubkey().to_string()));
+            progress.save(progress_path)?;
+            self.send_instructions(&[instruction], authority, &[authority, &post_keypair])
+                .await?;
+            progress.pending_post = None;
+            progress.posts.insert(post.pubkey.clone(), post_keypair.pubkey().to_string());
+            progress.save(progress_path)?;
+        }
+
+        let posts = progress
+            .posts
+            .iter()
+            .map(|(source, post)| Ok((source.clone(), parse_pubkey(post)?)))
+            .collect::<Result<_>>()?;
+        Ok(ImportReport {
+            blog: blog_pubkey,
+            posts,
+        })
+    }
+
+    fn account_exists(&self, pubkey: &Pubkey) -> Result<bool> {
+        self.rpc_client
+            .get_account_with_commitment(pubkey, self.rpc_client.commitment())
+            .map(|response| response.value.is_some())
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to look up account {}: {}", pubkey, e),
+            })
+    }
+
+    /// Upload `post`'s files and return the instruction creating it at
+    /// `post_keypair`
+    async fn import_post(
+        &self,
+        author: &Keypair,
+        blog_pubkey: Pubkey,
+        post_keypair: &Keypair,
+        archive: &BlogArchive,
+        post: &ArchivedPost,
+    ) -> Result<Instruction> {
+        let post_pubkey = post_keypair.pubkey();
+        let tags = [
+            ArweaveTag::new("Blog", blog_pubkey.to_string()),
+            ArweaveTag::new("Post", post_pubkey.to_string()),
+            ArweaveTag::new("Post-Title", post.title.clone()),
+            ArweaveTag::new("Original-Post", post.pubkey.clone()),
+        ];
+
+        let mut manifest = PathManifest::new().with_index(POST_INDEX_PATH);
+        let mut new_ids: BTreeMap<&str, ContentId> = BTreeMap::new();
+        for file in post.files.iter().filter(|file| file.path != POST_MEDIA_PATH) {
+            let id = self
+                .content_store()
+                .put_signed(&file.data, &file.content_type, &tags, author)
+                .await?;
+            manifest.insert(&file.path, id.0.clone())?;
+            new_ids.insert(&file.id, id);
+        }
+
+        // The media manifest names its variants by id, so it is rewritten
+        // once they have been re-uploaded
+        if let Some(file) = post.files.iter().find(|file| file.path == POST_MEDIA_PATH) {
+            let data = match PostManifest::from_bytes(&file.data) {
+                Some(mut media) => {
+                    let remap = |id: &mut ContentId| {
+                        if let Some(new_id) = new_ids.get(id.as_str()) {
+                            *id = new_id.clone();
+                        }
+                    };
+                    if let Some(content) = media.content.as_mut() {
+                        remap(content);
+                    }
+                    for variant in media.media.iter_mut().flat_map(|asset| asset.variants.iter_mut()) {
+                        remap(&mut variant.id);
+                    }
+                    media.to_bytes()?
+                }
+                None => file.data.clone(),
+            };
+            let id = self
+                .content_store()
+                .put_signed(&data, &file.content_type, &tags, author)
+                .await?;
+            manifest.insert(POST_MEDIA_PATH, id.0)?;
+        }
+
+        let record = ImportRecord {
+            source_program: archive.program_id.clone(),
+            source_blog: archive.blog.pubkey.clone(),
+            source_post: post.pubkey.clone(),
+            source_author: post.author.clone(),
+            source_content_id: post.arweave_hash.clone(),
+            created_at: post.created_at,
+            updated_at: post.updated_at,
+        };
+        let record = serde_json::to_vec(&record).map_err(|e| BlogClientError {
+            message: format!("Failed to serialize import record: {}", e),
+        })?;
+        let id = self
+            .content_store()
+            .put_signed(&record, "application/json", &tags, author)
+            .await?;
+        manifest.insert(POST_IMPORT_PATH, id.0)?;
+
+        let arweave_hash = self
+            .content_store()
+            .put_signed(&manifest.to_bytes()?, MANIFEST_CONTENT_TYPE, &tags, author)
+            .await?;
+
+        self.create_post_instruction(
+            author.pubkey(),
+            post_pubkey,
+            blog_pubkey,
+            post.title.clone(),
+            post.content.clone(),
+            arweave_hash.0,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::LocalStore;
+    use borsh::BorshDeserialize;
+    use image::{DynamicImage, ImageFormat, RgbImage};
+    use solana_blog_program::instruction::BlogInstruction;
+
+    fn archive(blog: &str) -> BlogArchive {
+        BlogArchive {
+            version: ARCHIVE_VERSION,
+            exported_at: 0,
+            program_id: Pubkey::new_unique().to_string(),
+            blog: ArchivedBlog {
+                pubkey: blog.to_string(),
+                authority: Pubkey::new_unique().to_string(),
+                title: "Blog".to_string(),
+                description: String::new(),
+                post_count: 0,
+                created_at: 0,
+            },
+            posts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn import_progress_belongs_to_one_source_blog() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blog.import.json");
+        let source = archive("source-blog");
+
+        let mut progress = ImportProgress::load(&path, &source).unwrap();
+        assert_eq!(progress.blog, None);
+        assert!(progress.posts.is_empty());
+
+        progress.blog = Some(Pubkey::new_unique().to_string());
+        progress.pending_post = Some(("source-post".to_string(), Pubkey::new_unique().to_string()));
+        progress.save(&path).unwrap();
+        assert_eq!(ImportProgress::load(&path, &source).unwrap(), progress);
+
+        let error = ImportProgress::load(&path, &archive("other-blog")).unwrap_err();
+        assert_eq!(
+            error.message,
+            format!("{} records an import of blog source-blog, not other-blog", path.display())
+        );
+    }
+
+    fn client(store: &tempfile::TempDir) -> BlogClient {
+        BlogClient::new("http://127.0.0.1:1", &solana_blog_program::id().to_string())
+            .unwrap()
+            .with_content_store(LocalStore::new(store.path()))
+    }
+
+    fn png() -> Vec<u8> {
+        let mut data = std::io::Cursor::new(Vec::new());
+        DynamicImage::ImageRgb8(RgbImage::new(64, 48))
+            .write_to(&mut data, ImageFormat::Png)
+            .unwrap();
+        data.into_inner()
+    }
+
+    /// Export the post at `arweave_hash` from `source`, import it into
+    /// `target` and return the imported post's manifest
+    async fn round_trip(source: &BlogClient, target: &BlogClient, arweave_hash: &str) -> PathManifest {
+        let author = Keypair::new();
+        let pubkey = Pubkey::new_unique();
+        let post = ArchivedPost {
+            pubkey: pubkey.to_string(),
+            author: author.pubkey().to_string(),
+            title: "Post".to_string(),
+            content: "Summary".to_string(),
+            arweave_hash: arweave_hash.to_string(),
+            created_at: 1,
+            updated_at: 2,
+            files: source.export_post_files(pubkey, arweave_hash).await.unwrap(),
+        };
+        let mut archive = archive("source-blog");
+        archive.posts.push(post.clone());
+        // Through JSON, as `blogctl export` writes it
+        let archive = BlogArchive::from_bytes(&archive.to_bytes().unwrap()).unwrap();
+
+        let instruction = target
+            .import_post(&author, Pubkey::new_unique(), &Keypair::new(), &archive, &archive.posts[0])
+            .await
+            .unwrap();
+        let arweave_hash = match BlogInstruction::try_from_slice(&instruction.data).unwrap() {
+            BlogInstruction::CreatePost { arweave_hash, .. } => arweave_hash,
+            instruction => panic!("unexpected instruction: {:?}", instruction),
+        };
+        target.get_post_manifest(&arweave_hash).await.unwrap()
+    }
+
+    async fn file(client: &BlogClient, manifest: &PathManifest, path: &str) -> Vec<u8> {
+        let id = manifest.get(path).unwrap();
+        client.content_store().get(&ContentId::from(id)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn exported_posts_import_into_another_store() {
+        let (source_store, target_store) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (source, target) = (client(&source_store), client(&target_store));
+        let author = Keypair::new();
+
+        let image = png();
+        let posted = source
+            .upload_post_files(&author, &[], PathManifest::new(), Some("Body"), Some((&image, "Alt")), &[])
+            .await
+            .unwrap();
+        let imported = round_trip(&source, &target, posted.as_str()).await;
+        let original = source.get_post_manifest(posted.as_str()).await.unwrap();
+
+        let paths = |manifest: &PathManifest| manifest.paths().map(|(path, _)| path.to_string()).collect::<Vec<_>>();
+        let mut expected = paths(&original);
+        expected.push(POST_IMPORT_PATH.to_string());
+        expected.sort();
+        assert_eq!(paths(&imported), expected);
+        for path in paths(&original).iter().filter(|path| path.as_str() != POST_MEDIA_PATH) {
+            assert_eq!(file(&target, &imported, path).await, file(&source, &original, path).await);
+        }
+
+        // The media list names the re-uploaded body and variants
+        let media = PostManifest::from_bytes(&file(&target, &imported, POST_MEDIA_PATH).await).unwrap();
+        assert_eq!(media.content.as_ref().map(ContentId::as_str), imported.get(POST_INDEX_PATH));
+        for variant in media.media.iter().flat_map(|asset| &asset.variants) {
+            target.content_store().get(&variant.id).await.unwrap();
+        }
+
+        let record: ImportRecord = serde_json::from_slice(&file(&target, &imported, POST_IMPORT_PATH).await).unwrap();
+        assert_eq!(record.source_content_id, posted.as_str());
+        assert_eq!((record.created_at, record.updated_at), (1, 2));
+    }
+
+    #[tokio::test]
+    async fn posts_from_before_path_manifests_export_their_body() {
+        let (source_store, target_store) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (source, target) = (client(&source_store), client(&target_store));
+
+        let body = source.content_store().put(b"Bare body", "text/markdown", &[]).await.unwrap();
+        let imported = round_trip(&source, &target, body.as_str()).await;
+        assert_eq!(file(&target, &imported, POST_INDEX_PATH).await, b"Bare body");
+        assert_eq!(imported.get(POST_MEDIA_PATH), None);
+    }
+}
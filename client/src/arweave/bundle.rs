@@ -0,0 +1,280 @@
+//! ANS-104 data items signed with an ed25519 (Solana) keypair
+
+use super::{
+    b64url_encode,
+    transaction::{deep_hash, DeepHashItem},
+    wallet, ArweaveTag,
+};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use solana_sdk::signature::{Keypair, Signature, Signer};
+
+/// ANS-104 signature type for Arweave RSA keys
+pub const ARWEAVE_SIGNATURE_TYPE: u16 = 1;
+/// ANS-104 signature type for ed25519 keys, which bundlers accept on their
+/// `/tx/solana` endpoint
+pub const ED25519_SIGNATURE_TYPE: u16 = 2;
+const ED25519_PUBLIC_KEY_LENGTH: usize = 32;
+const ANCHOR_LENGTH: usize = 32;
+
+/// A single ANS-104 data item, ready to be posted to a bundler
+#[derive(Debug, Clone)]
+pub struct DataItem {
+    signature_type: u16,
+    owner: Vec<u8>,
+    anchor: Vec<u8>,
+    tags: Vec<ArweaveTag>,
+    data: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+impl DataItem {
+    /// Build an unsigned item for `owner`. A random anchor keeps ids unique
+    /// when the same bytes are uploaded twice.
+    pub fn new(owner: &[u8], data: &[u8], tags: &[ArweaveTag]) -> Self {
+        let mut anchor = vec![0u8; ANCHOR_LENGTH];
+        rand::thread_rng().fill_bytes(&mut anchor);
+
+        Self {
+            signature_type: ED25519_SIGNATURE_TYPE,
+            owner: owner.to_vec(),
+            anchor,
+            tags: tags.to_vec(),
+            data: data.to_vec(),
+            signature: Vec::new(),
+        }
+    }
+
+    /// Rebuild a signed item from its header fields, as a gateway reports
+    /// them, and its data. The signature type follows from the owner key.
+    pub fn from_parts(owner: &[u8], anchor: &[u8], tags: &[ArweaveTag], data: &[u8], signature: &[u8]) -> Self {
+        let signature_type = if owner.len() == ED25519_PUBLIC_KEY_LENGTH {
+            ED25519_SIGNATURE_TYPE
+        } else {
+            ARWEAVE_SIGNATURE_TYPE
+        };
+        Self {
+            signature_type,
+            owner: owner.to_vec(),
+            anchor: anchor.to_vec(),
+            tags: tags.to_vec(),
+            data: data.to_vec(),
+            signature: signature.to_vec(),
+        }
+    }
+
+    /// The message that gets signed
+    pub fn signature_data(&self) -> [u8; 48] {
+        deep_hash(&DeepHashItem::List(vec![
+            DeepHashItem::Blob(b"dataitem".to_vec()),
+            DeepHashItem::Blob(b"1".to_vec()),
+            DeepHashItem::Blob(self.signature_type.to_string().into_bytes()),
+            DeepHashItem::Blob(self.owner.clone()),
+            DeepHashItem::Blob(Vec::new()), // target
+            DeepHashItem::Blob(self.anchor.clone()),
+            DeepHashItem::Blob(encode_tags(&self.tags)),
+            DeepHashItem::Blob(self.data.clone()),
+        ]))
+    }
+
+    pub fn sign(&mut self, signer: &Keypair) {
+        self.signature_type = ED25519_SIGNATURE_TYPE;
+        self.owner = signer.pubkey().to_bytes().to_vec();
+        self.signature = signer.sign_message(&self.signature_data()).as_ref().to_vec();
+    }
+
+    pub fn owner(&self) -> &[u8] {
+        &self.owner
+    }
+
+    pub fn anchor(&self) -> &[u8] {
+        &self.anchor
+    }
+
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+
+    /// Item id: base64url SHA-256 of the signature. Empty until signed.
+    pub fn id(&self) -> String {
+        if self.signature.is_empty() {
+            return String::new();
+        }
+        b64url_encode(&Sha256::digest(&self.signature))
+    }
+
+    /// Whether the signature is valid for the owner key over this item
+    pub fn verify(&self) -> bool {
+        let message = self.signature_data();
+        match self.signature_type {
+            ED25519_SIGNATURE_TYPE => Signature::try_from(self.signature.as_slice())
+                .is_ok_and(|signature| signature.verify(&self.owner, &message)),
+            _ => wallet::verify_signature(&self.owner, &message, &self.signature),
+        }
+    }
+
+    /// Binary layout from the ANS-104 spec
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let tags = encode_tags(&self.tags);
+        let mut bytes = Vec::with_capacity(
+            2 + self.signature.len() + self.owner.len() + 2 + self.anchor.len() + 16 + tags.len() + self.data.len(),
+        );
+
+        bytes.extend_from_slice(&self.signature_type.to_le_bytes());
+        bytes.extend_from_slice(&self.signature);
+        bytes.extend_from_slice(&self.owner);
+        bytes.push(0); // no target
+        if self.anchor.is_empty() {
+            bytes.push(0);
+        } else {
+            bytes.push(1);
+            bytes.extend_from_slice(&self.anchor);
+        }
+        bytes.extend_from_slice(&(self.tags.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&(tags.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&tags);
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+}
+
+/// Avro encoding of the tag array (`{name: bytes, value: bytes}` records),
+/// or nothing when there are no tags
+fn encode_tags(tags: &[ArweaveTag]) -> Vec<u8> {
+    if tags.is_empty() {
+        return Vec::new();
+    }
+
+    let mut bytes = Vec::new();
+    write_avro_long(&mut bytes, tags.len() as i64);
+    for tag in tags {
+        write_avro_bytes(&mut bytes, tag.name.as_bytes());
+        write_avro_bytes(&mut bytes, tag.value.as_bytes());
+    }
+    write_avro_long(&mut bytes, 0);
+    bytes
+}
+
+fn write_avro_bytes(out: &mut Vec<u8>, data: &[u8]) {
+    write_avro_long(out, data.len() as i64);
+    out.extend_from_slice(data);
+}
+
+fn write_avro_long(out: &mut Vec<u8>, value: i64) {
+    let mut zigzag = ((value << 1) ^ (value >> 63)) as u64;
+    loop {
+        let byte = (zigzag & 0x7f) as u8;
+        zigzag >>= 7;
+        if zigzag == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arweave::{
+        mock_gateway::{GatewayState, MockGateway},
+        ArweaveClient,
+    };
+    use solana_sdk::signature::keypair_from_seed;
+
+    // Built independently from the ANS-104 spec, for the seed and anchor below
+    const ITEM_HEX: &str = "0200073fc5853c629274ae5a84dd25db4e811c2bd91425bf56f1546f47c77fc75deaa8aa3d3f1e7e7aae9f8995e1cefd44be2393b6744f805f26fea526dfedbda9098a88e3dd7409f195fd52db2d3cba5d72ca6709bf1d94121bf3748801b40f6f5c0001090909090909090909090909090909090909090909090909090909090909090902000000000000002f000000000000000418436f6e74656e742d5479706514746578742f706c61696e104170702d4e616d6516536f6c616e612d426c6f670068656c6c6f2062756e646c657273";
+    const ITEM_ID: &str = "hyzotLSfEKLw_q08vcDOtCDvthZt2eloJ9JM8PACLqI";
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    fn signed_item() -> (Keypair, DataItem) {
+        let signer = keypair_from_seed(&[1u8; 32]).unwrap();
+        let tags = [
+            ArweaveTag::new("Content-Type", "text/plain"),
+            ArweaveTag::new("App-Name", "Solana-Blog"),
+        ];
+        let mut item = DataItem::new(&[], b"hello bundlers", &tags);
+        item.anchor = vec![9u8; ANCHOR_LENGTH];
+        item.sign(&signer);
+        (signer, item)
+    }
+
+    #[test]
+    fn avro_tags_match_the_spec_encoding() {
+        assert_eq!(encode_tags(&[]), Vec::<u8>::new());
+        // One block of one record, then the empty block that ends the array
+        assert_eq!(encode_tags(&[ArweaveTag::new("a", "b")]), [0x02, 0x02, b'a', 0x02, b'b', 0x00]);
+
+        let mut long = Vec::new();
+        for value in [0, -1, 1, 63, 64, -65] {
+            write_avro_long(&mut long, value);
+        }
+        assert_eq!(long, [0x00, 0x01, 0x02, 0x7e, 0x80, 0x01, 0x81, 0x01]);
+    }
+
+    #[test]
+    fn data_item_bytes_match_known_vector() {
+        let (signer, item) = signed_item();
+        let bytes = item.to_bytes();
+
+        assert_eq!(hex(&bytes), ITEM_HEX);
+        assert_eq!(item.id(), ITEM_ID);
+
+        // Field offsets: signature type, signature, owner, target and anchor flags
+        assert_eq!(&bytes[..2], &[2, 0]);
+        assert_eq!(&bytes[66..98], signer.pubkey().as_ref());
+        assert_eq!(bytes[98], 0);
+        assert_eq!(bytes[99], 1);
+        assert_eq!(&bytes[100..132], &[9u8; 32]);
+    }
+
+    #[test]
+    fn signature_verifies_with_the_owner_key() {
+        let (signer, item) = signed_item();
+        let signature = Signature::try_from(item.signature.as_slice()).unwrap();
+        assert!(signature.verify(signer.pubkey().as_ref(), &item.signature_data()));
+
+        let mut tampered = item.clone();
+        tampered.data = b"hello bundler!".to_vec();
+        assert!(!signature.verify(signer.pubkey().as_ref(), &tampered.signature_data()));
+    }
+
+    #[test]
+    fn items_rebuilt_from_their_header_verify() {
+        let (_, item) = signed_item();
+        let rebuilt = DataItem::from_parts(&item.owner, &item.anchor, &item.tags, &item.data, &item.signature);
+        assert!(rebuilt.verify());
+        assert_eq!(rebuilt.id(), ITEM_ID);
+
+        let forged = DataItem::from_parts(&item.owner, &item.anchor, &item.tags, b"forged", &item.signature);
+        assert!(!forged.verify());
+    }
+
+    #[test]
+    fn unsigned_items_have_no_id() {
+        let item = DataItem::new(&[0u8; 32], b"data", &[]);
+        assert_eq!(item.id(), "");
+        assert_ne!(item.anchor, DataItem::new(&[0u8; 32], b"data", &[]).anchor);
+    }
+
+    #[tokio::test]
+    async fn uploads_need_a_signer() {
+        let gateway = MockGateway::start(GatewayState::default()).await;
+        let client = ArweaveClient::new().with_bundler_url(gateway.url.clone());
+
+        let error = client.upload_data(b"hello", "text/plain").await.unwrap_err();
+        assert!(error.message.contains("No Arweave wallet or bundle signer"), "{}", error.message);
+        assert!(gateway.state.lock().unwrap().data_items.is_empty());
+
+        let client = client.with_bundle_signer(keypair_from_seed(&[1u8; 32]).unwrap());
+        assert_eq!(client.upload_data(b"hello", "text/plain").await.unwrap(), "item-1");
+
+        let state = gateway.state.lock().unwrap();
+        let posted = &state.data_items[0];
+        assert_eq!(&posted[..2], &[2, 0]);
+        assert!(posted.ends_with(b"hello"));
+    }
+}
@@ -0,0 +1,281 @@
+use crate::types::{BlogClientError, Result};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::SystemTime,
+};
+
+/// Snapshot of cache activity since it was opened
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub insertions: u64,
+    pub evictions: u64,
+    pub entries: usize,
+    pub total_bytes: u64,
+    pub max_bytes: u64,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    size: u64,
+    last_access: SystemTime,
+}
+
+#[derive(Debug, Default)]
+struct Index {
+    entries: HashMap<String, Entry>,
+    total_bytes: u64,
+}
+
+/// On-disk cache of Arweave content keyed by transaction id. Arweave data is
+/// immutable, so entries never go stale; they are only evicted, least
+/// recently used first, to stay under `max_bytes`. File modification times
+/// record last access so the LRU order survives restarts.
+#[derive(Debug)]
+pub struct ContentCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    index: Mutex<Index>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    insertions: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl ContentCache {
+    /// Open (or create) a cache in `dir`, indexing whatever is already there
+    pub fn open(dir: impl Into<PathBuf>, max_bytes: u64) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|e| BlogClientError {
+            message: format!("Failed to create cache directory {}: {}", dir.display(), e),
+        })?;
+
+        let mut index = Index::default();
+        let entries = std::fs::read_dir(&dir).map_err(|e| BlogClientError {
+            message: format!("Failed to read cache directory {}: {}", dir.display(), e),
+        })?;
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else { continue };
+            let Some(key) = entry.file_name().to_str().map(str::to_string) else { continue };
+            if !metadata.is_file() || !is_valid_key(&key) {
+                continue;
+            }
+            index.total_bytes += metadata.len();
+            index.entries.insert(
+                key,
+                Entry {
+                    size: metadata.len(),
+                    last_access: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                },
+            );
+        }
+
+        let cache = Self {
+            dir,
+            max_bytes,
+            index: Mutex::new(index),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            insertions: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        };
+        for key in cache.evict_to(max_bytes) {
+            let _ = std::fs::remove_file(cache.dir.join(key));
+        }
+        Ok(cache)
+    }
+
+    /// Platform cache directory, e.g. `~/.cache/solana-blog/arweave` on Linux
+    pub fn default_dir() -> PathBuf {
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+            .unwrap_or_else(std::env::temp_dir);
+        base.join("solana-blog").join("arweave")
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub async fn get(&self, key: &str) -> Option<Vec<u8>> {
+        if !is_valid_key(key) || !self.index.lock().unwrap().entries.contains_key(key) {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+
+        let path = self.dir.join(key);
+        match tokio::fs::read(&path).await {
+            Ok(data) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                self.touch(key, path).await;
+                Some(data)
+            }
+            Err(_) => {
+                // Removed behind our back; forget it
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                let mut index = self.index.lock().unwrap();
+                if let Some(entry) = index.entries.remove(key) {
+                    index.total_bytes -= entry.size;
+                }
+                None
+            }
+        }
+    }
+
+    /// Store `data` under `key`. Objects larger than the whole cache are skipped.
+    pub async fn put(&self, key: &str, data: &[u8]) -> Result<()> {
+        let size = data.len() as u64;
+        if !is_valid_key(key) || size > self.max_bytes {
+            return Ok(());
+        }
+
+        // Write to a temporary name first so readers never see partial files
+        let path = self.dir.join(key);
+        let temp_path = self.dir.join(format!(".{}.tmp", key));
+        tokio::fs::write(&temp_path, data).await.map_err(|e| BlogClientError {
+            message: format!("Failed to write cache entry {}: {}", key, e),
+        })?;
+        tokio::fs::rename(&temp_path, &path).await.map_err(|e| BlogClientError {
+            message: format!("Failed to write cache entry {}: {}", key, e),
+        })?;
+
+        {
+            let mut index = self.index.lock().unwrap();
+            let previous = index.entries.insert(
+                key.to_string(),
+                Entry {
+                    size,
+                    last_access: SystemTime::now(),
+                },
+            );
+            index.total_bytes += size;
+            if let Some(previous) = previous {
+                index.total_bytes -= previous.size;
+            }
+        }
+        self.insertions.fetch_add(1, Ordering::Relaxed);
+        for key in self.evict_to(self.max_bytes) {
+            let _ = tokio::fs::remove_file(self.dir.join(key)).await;
+        }
+        Ok(())
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let index = self.index.lock().unwrap();
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            insertions: self.insertions.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            entries: index.entries.len(),
+            total_bytes: index.total_bytes,
+            max_bytes: self.max_bytes,
+        }
+    }
+
+    async fn touch(&self, key: &str, path: PathBuf) {
+        let now = SystemTime::now();
+        if let Some(entry) = self.index.lock().unwrap().entries.get_mut(key) {
+            entry.last_access = now;
+        }
+        // tokio has no async way to set a modification time
+        let _ = tokio::task::spawn_blocking(move || {
+            if let Ok(file) = std::fs::File::options().write(true).open(path) {
+                let _ = file.set_modified(now);
+            }
+        })
+        .await;
+    }
+
+    /// Drop the least recently used entries from the index until it fits in
+    /// `max_bytes`, returning their keys for the caller to delete
+    fn evict_to(&self, max_bytes: u64) -> Vec<String> {
+        let mut index = self.index.lock().unwrap();
+        let mut evicted = Vec::new();
+        if index.total_bytes <= max_bytes {
+            return evicted;
+        }
+
+        let mut by_age: Vec<(SystemTime, String)> = index
+            .entries
+            .iter()
+            .map(|(key, entry)| (entry.last_access, key.clone()))
+            .collect();
+        by_age.sort();
+
+        for (_, key) in by_age {
+            if index.total_bytes <= max_bytes {
+                break;
+            }
+            if let Some(entry) = index.entries.remove(&key) {
+                index.total_bytes -= entry.size;
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+                evicted.push(key);
+            }
+        }
+        evicted
+    }
+}
+
+/// Keys are transaction ids; only base64url characters are allowed so keys
+/// stay inside the cache directory
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn evicts_least_recently_used_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ContentCache::open(dir.path(), 10).unwrap();
+
+        cache.put("a", b"aaaa").await.unwrap();
+        cache.put("b", b"bbbb").await.unwrap();
+        assert_eq!(cache.get("a").await.unwrap(), b"aaaa");
+        cache.put("c", b"cccc").await.unwrap();
+
+        assert_eq!(cache.get("b").await, None);
+        assert!(!dir.path().join("b").exists());
+        assert_eq!(cache.get("a").await.unwrap(), b"aaaa");
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.total_bytes, stats.evictions), (2, 8, 1));
+        assert_eq!((stats.hits, stats.misses), (2, 1));
+    }
+
+    #[tokio::test]
+    async fn reopening_keeps_entries_and_refuses_bad_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ContentCache::open(dir.path(), 100).unwrap();
+        cache.put("id_1", b"data").await.unwrap();
+        cache.put("../escape", b"data").await.unwrap();
+        cache.put("too-big", &[0; 101]).await.unwrap();
+        drop(cache);
+
+        let cache = ContentCache::open(dir.path(), 100).unwrap();
+        assert_eq!(cache.get("id_1").await.unwrap(), b"data");
+        assert_eq!(cache.get("../escape").await, None);
+        assert_eq!(cache.get("too-big").await, None);
+        assert!(!dir.path().parent().unwrap().join("escape").exists());
+    }
+}
@@ -0,0 +1,417 @@
+use super::{b64url_decode, bundle::DataItem, transaction::ArweaveTransaction, ArweaveTag};
+use crate::types::{BlogClientError, Result};
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Public ar.io gateways tried after the primary gateway
+pub const DEFAULT_FALLBACK_GATEWAYS: &[&str] = &["https://ar-io.net", "https://permagate.io"];
+
+/// A gateway is skipped for this long after `MAX_CONSECUTIVE_FAILURES`
+const COOLDOWN: Duration = Duration::from_secs(60);
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+
+/// Header fields of a bundled data item, as gateway GraphQL reports them
+const DATA_ITEM_QUERY: &str =
+    "query($id: ID!) { transaction(id: $id) { signature anchor recipient owner { key } tags { name value } } }";
+
+/// How fetched bytes are checked before being returned
+#[derive(Debug, Clone, PartialEq)]
+pub enum Integrity {
+    /// Trust the gateway
+    None,
+    /// Check the bytes against the signed header of the transaction or
+    /// bundled data item: its id must be the hash of the signature, the
+    /// signature must verify with the header's owner, and the signed data
+    /// root or deep hash must match the bytes. Since the id pins the
+    /// signature, a gateway cannot forge a header that passes. Reads
+    /// `/raw/{id}`, the bytes exactly as uploaded.
+    Signed,
+    /// Compare the SHA-256 of the bytes with a digest recorded elsewhere
+    Sha256([u8; 32]),
+}
+
+#[derive(Deserialize)]
+struct GraphQlResponse {
+    data: Option<GraphQlData>,
+}
+
+#[derive(Deserialize)]
+struct GraphQlData {
+    transaction: Option<DataItemHeader>,
+}
+
+#[derive(Deserialize)]
+struct DataItemHeader {
+    signature: String,
+    #[serde(default)]
+    anchor: String,
+    #[serde(default)]
+    recipient: String,
+    owner: DataItemOwner,
+    tags: Vec<ArweaveTag>,
+}
+
+#[derive(Deserialize)]
+struct DataItemOwner {
+    key: String,
+}
+
+/// Rolling health record for one gateway
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GatewayHealth {
+    pub successes: u64,
+    pub failures: u64,
+    pub consecutive_failures: u32,
+    /// Exponential moving average of successful request latency
+    pub average_latency: Option<Duration>,
+    pub last_error: Option<String>,
+    last_failure: Option<Instant>,
+}
+
+impl GatewayHealth {
+    fn is_cooling_down(&self) -> bool {
+        self.consecutive_failures >= MAX_CONSECUTIVE_FAILURES
+            && self.last_failure.is_some_and(|at| at.elapsed() < COOLDOWN)
+    }
+}
+
+/// Ordered set of read gateways with shared health tracking. Clones share
+/// the same health records.
+#[derive(Debug, Clone)]
+pub struct GatewayPool {
+    gateways: Vec<String>,
+    timeout: Duration,
+    health: Arc<Mutex<HashMap<String, GatewayHealth>>>,
+}
+
+impl GatewayPool {
+    pub fn new(gateways: Vec<String>) -> Self {
+        Self {
+            gateways: gateways
+                .into_iter()
+                .map(|url| url.trim_end_matches('/').to_string())
+                .collect(),
+            timeout: Duration::from_secs(10),
+            health: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn gateways(&self) -> &[String] {
+        &self.gateways
+    }
+
+    pub fn health(&self) -> HashMap<String, GatewayHealth> {
+        self.health.lock().unwrap().clone()
+    }
+
+    /// Gateways in the order they should be tried: those not cooling down
+    /// first, then by fewest recent failures and lowest latency. Gateways in
+    /// cooldown are still tried last rather than not at all.
+    fn ordered(&self) -> Vec<String> {
+        let health = self.health.lock().unwrap();
+        let mut gateways: Vec<(usize, &String)> = self.gateways.iter().enumerate().collect();
+        gateways.sort_by_key(|(position, url)| {
+            let record = health.get(*url).cloned().unwrap_or_default();
+            (
+                record.is_cooling_down(),
+                record.consecutive_failures,
+                record.average_latency.unwrap_or(Duration::ZERO),
+                *position,
+            )
+        });
+        gateways.into_iter().map(|(_, url)| url.clone()).collect()
+    }
+
+    fn record_success(&self, gateway: &str, latency: Duration) {
+        let mut health = self.health.lock().unwrap();
+        let record = health.entry(gateway.to_string()).or_default();
+        record.successes += 1;
+        record.consecutive_failures = 0;
+        record.average_latency = Some(match record.average_latency {
+            Some(average) => (average * 4 + latency) / 5,
+            None => latency,
+        });
+    }
+
+    fn record_failure(&self, gateway: &str, error: &str) {
+        let mut health = self.health.lock().unwrap();
+        let record = health.entry(gateway.to_string()).or_default();
+        record.failures += 1;
+        record.consecutive_failures += 1;
+        record.last_error = Some(error.to_string());
+        record.last_failure = Some(Instant::now());
+    }
+
+    /// Fetch `tx_id` from the first gateway that returns data passing the
+    /// `integrity` check, falling back through the pool on any failure
+    pub async fn fetch(&self, client: &Client, tx_id: &str, integrity: &Integrity) -> Result<Vec<u8>> {
+        let mut errors = Vec::new();
+
+        for gateway in self.ordered() {
+            let started = Instant::now();
+            match self.fetch_from(client, &gateway, tx_id, integrity).await {
+                Ok(data) => {
+                    self.record_success(&gateway, started.elapsed());
+                    return Ok(data);
+                }
+                Err(e) => {
+                    self.record_failure(&gateway, &e.message);
+                    errors.push(format!("{}: {}", gateway, e.message));
+                }
+            }
+        }
+
+        Err(BlogClientError {
+            message: format!("Failed to fetch {} from every gateway ({})", tx_id, errors.join("; ")),
+        })
+    }
+
+    async fn fetch_from(
+        &self,
+        client: &Client,
+        gateway: &str,
+        tx_id: &str,
+        integrity: &Integrity,
+    ) -> Result<Vec<u8>> {
+        match integrity {
+            Integrity::None => self.get_bytes(client, &format!("{}/{}", gateway, tx_id)).await,
+            Integrity::Sha256(expected) => {
+                let data = self.get_bytes(client, &format!("{}/{}", gateway, tx_id)).await?;
+                let digest: [u8; 32] = Sha256::digest(&data).into();
+                if digest != *expected {
+                    return Err(BlogClientError {
+                        message: "content does not match the expected SHA-256 digest".to_string(),
+                    });
+                }
+                Ok(data)
+            }
+            Integrity::Signed => {
+                let data = self.get_bytes(client, &format!("{}/raw/{}", gateway, tx_id)).await?;
+                self.verify_signed(client, gateway, tx_id, &data).await?;
+                Ok(data)
+            }
+        }
+    }
+
+    /// Check `data` against the signed header of `tx_id`: a layer-1
+    /// transaction from `/tx/{id}`, or failing that a bundled data item
+    /// from GraphQL
+    async fn verify_signed(&self, client: &Client, gateway: &str, tx_id: &str, data: &[u8]) -> Result<()> {
+        let response = client
+            .get(format!("{}/tx/{}", gateway, tx_id))
+            .timeout(self.timeout)
+            .send()
+            .await
+            .map_err(|e| BlogClientError {
+                message: format!("header request failed: {}", e),
+            })?;
+
+        if response.status().is_success() {
+            let header: ArweaveTransaction = response.json().await.map_err(|e| BlogClientError {
+                message: format!("invalid transaction header: {}", e),
+            })?;
+            return header.verify(tx_id, data);
+        }
+        if response.status() != StatusCode::NOT_FOUND {
+            return Err(BlogClientError {
+                message: format!("header request failed with status {}", response.status()),
+            });
+        }
+
+        let header = self.data_item_header(client, gateway, tx_id).await?;
+        if !header.recipient.is_empty() {
+            return Err(BlogClientError {
+                message: format!("data item {} has a target, which is not supported", tx_id),
+            });
+        }
+        let signature = b64url_decode(&header.signature)?;
+        let item = DataItem::from_parts(
+            &b64url_decode(&header.owner.key)?,
+            &b64url_decode(&header.anchor)?,
+            &header.tags,
+            data,
+            &signature,
+        );
+        if item.id() != tx_id || !item.verify() {
+            return Err(BlogClientError {
+                message: format!("data item {} does not match its signed header", tx_id),
+            });
+        }
+        Ok(())
+    }
+
+    async fn data_item_header(&self, client: &Client, gateway: &str, tx_id: &str) -> Result<DataItemHeader> {
+        let response = client
+            .post(format!("{}/graphql", gateway))
+            .timeout(self.timeout)
+            .json(&serde_json::json!({ "query": DATA_ITEM_QUERY, "variables": { "id": tx_id } }))
+            .send()
+            .await
+            .map_err(|e| BlogClientError {
+                message: format!("GraphQL request failed: {}", e),
+            })?;
+
+        if !response.status().is_success() {
+            return Err(BlogClientError {
+                message: format!("GraphQL request failed with status {}", response.status()),
+            });
+        }
+
+        let body: GraphQlResponse = response.json().await.map_err(|e| BlogClientError {
+            message: format!("invalid GraphQL response: {}", e),
+        })?;
+        body.data
+            .and_then(|data| data.transaction)
+            .ok_or_else(|| BlogClientError {
+                message: format!("no signed header found for {}", tx_id),
+            })
+    }
+
+    async fn get_bytes(&self, client: &Client, url: &str) -> Result<Vec<u8>> {
+        let response = client
+            .get(url)
+            .timeout(self.timeout)
+            .send()
+            .await
+            .map_err(|e| BlogClientError {
+                message: format!("request failed: {}", e),
+            })?;
+
+        if !response.status().is_success() {
+            return Err(BlogClientError {
+                message: format!("status {}", response.status()),
+            });
+        }
+
+        response
+            .bytes()
+            .await
+            .map(|data| data.to_vec())
+            .map_err(|e| BlogClientError {
+                message: format!("failed to read response: {}", e),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arweave::{
+        b64url_encode,
+        mock_gateway::{GatewayState, MockGateway},
+        wallet::ArweaveWallet,
+    };
+    use solana_sdk::signature::keypair_from_seed;
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    /// Header of a layer-1 transaction for `data`, as `/tx/{id}` serves it
+    fn transaction(data: &[u8]) -> (String, serde_json::Value) {
+        let wallet = ArweaveWallet::from_jwk(include_str!("../../testdata/arweave-wallet.json")).unwrap();
+        let mut tx = ArweaveTransaction::new(data, wallet.owner(), b64url_encode(&[7; 48]), 1000, &[]);
+        tx.sign(&wallet).unwrap();
+        (tx.id.clone(), serde_json::to_value(&tx).unwrap())
+    }
+
+    /// Header of a bundled data item for `data`, as GraphQL reports it
+    fn data_item(data: &[u8]) -> (String, serde_json::Value) {
+        let signer = keypair_from_seed(&[1u8; 32]).unwrap();
+        let tags = [ArweaveTag::new("Content-Type", "text/markdown")];
+        let mut item = DataItem::new(&[], data, &tags);
+        item.sign(&signer);
+        let header = serde_json::json!({
+            "signature": b64url_encode(item.signature()),
+            "anchor": b64url_encode(item.anchor()),
+            "recipient": "",
+            "owner": { "key": b64url_encode(item.owner()) },
+            "tags": tags,
+        });
+        (item.id(), header)
+    }
+
+    fn serve_transaction(gateway: &MockGateway, (id, header): &(String, serde_json::Value), served: &[u8]) {
+        let mut state = gateway.state.lock().unwrap();
+        state.headers.insert(id.clone(), header.clone());
+        state.data.insert(id.clone(), served.to_vec());
+    }
+
+    fn serve_data_item(gateway: &MockGateway, (id, header): &(String, serde_json::Value), served: &[u8]) {
+        let mut state = gateway.state.lock().unwrap();
+        state.data_item_headers.insert(id.clone(), header.clone());
+        state.data.insert(id.clone(), served.to_vec());
+    }
+
+    fn pool(gateways: &[&MockGateway]) -> GatewayPool {
+        GatewayPool::new(gateways.iter().map(|gateway| gateway.url.clone()).collect())
+    }
+
+    #[tokio::test]
+    async fn signed_reads_accept_data_matching_the_header() {
+        let gateway = MockGateway::start(GatewayState::default()).await;
+        let tx_data = data(300 * 1024);
+        let tx = transaction(&tx_data);
+        let item = data_item(b"# Hello");
+        serve_transaction(&gateway, &tx, &tx_data);
+        serve_data_item(&gateway, &item, b"# Hello");
+
+        let pool = pool(&[&gateway]);
+        let client = Client::new();
+        assert_eq!(pool.fetch(&client, &tx.0, &Integrity::Signed).await.unwrap(), tx_data);
+        assert_eq!(pool.fetch(&client, &item.0, &Integrity::Signed).await.unwrap(), b"# Hello");
+    }
+
+    #[tokio::test]
+    async fn signed_reads_skip_gateways_serving_other_data() {
+        let bad = MockGateway::start(GatewayState::default()).await;
+        let good = MockGateway::start(GatewayState::default()).await;
+        let tx = transaction(b"original");
+        let item = data_item(b"# Hello");
+        serve_transaction(&bad, &tx, b"tampered");
+        serve_transaction(&good, &tx, b"original");
+        serve_data_item(&bad, &item, b"# Hacked");
+        serve_data_item(&good, &item, b"# Hello");
+
+        let pool = pool(&[&bad, &good]);
+        let client = Client::new();
+        assert_eq!(pool.fetch(&client, &tx.0, &Integrity::Signed).await.unwrap(), b"original");
+        assert_eq!(pool.fetch(&client, &item.0, &Integrity::Signed).await.unwrap(), b"# Hello");
+        // After its first failure the bad gateway is tried last
+        assert_eq!(pool.health()[&bad.url].failures, 1);
+
+        // Only trusting the gateway returns what it serves
+        let pool = GatewayPool::new(vec![bad.url.clone()]);
+        assert_eq!(pool.fetch(&client, &item.0, &Integrity::None).await.unwrap(), b"# Hacked");
+        assert!(pool.fetch(&client, &item.0, &Integrity::Signed).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn signed_reads_reject_headers_for_another_id() {
+        let gateway = MockGateway::start(GatewayState::default()).await;
+        // The gateway serves another transaction's header and data as "forged"
+        let (_, header) = transaction(b"original");
+        serve_transaction(&gateway, &("forged".to_string(), header), b"original");
+
+        let error = pool(&[&gateway])
+            .fetch(&Client::new(), "forged", &Integrity::Signed)
+            .await
+            .unwrap_err();
+        assert!(error.message.contains("does not match its id"), "{}", error.message);
+    }
+}
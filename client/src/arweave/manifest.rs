@@ -0,0 +1,153 @@
+use crate::types::{BlogClientError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Content type gateways recognize as a path manifest
+pub const MANIFEST_CONTENT_TYPE: &str = "application/x.arweave-manifest+json";
+const MANIFEST_KIND: &str = "arweave/paths";
+const MANIFEST_VERSION: &str = "0.2.0";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestIndex {
+    pub path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestPath {
+    pub id: String,
+}
+
+/// An `arweave/paths` manifest mapping relative paths to transaction ids.
+/// Gateways serve `{manifest_id}/{path}` from the mapped transaction and
+/// `{manifest_id}` itself from the index path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathManifest {
+    manifest: String,
+    version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    index: Option<ManifestIndex>,
+    paths: BTreeMap<String, ManifestPath>,
+}
+
+impl Default for PathManifest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PathManifest {
+    pub fn new() -> Self {
+        Self {
+            manifest: MANIFEST_KIND.to_string(),
+            version: MANIFEST_VERSION.to_string(),
+            index: None,
+            paths: BTreeMap::new(),
+        }
+    }
+
+    /// Serve `path` when the manifest id is requested without a path
+    pub fn with_index(mut self, path: impl Into<String>) -> Self {
+        self.index = Some(ManifestIndex { path: path.into() });
+        self
+    }
+
+    /// Map `path` to `id`, replacing any existing mapping
+    pub fn insert(&mut self, path: &str, id: impl Into<String>) -> Result<()> {
+        let path = normalize_path(path).ok_or_else(|| BlogClientError {
+            message: format!("Invalid manifest path: {}", path),
+        })?;
+        self.paths.insert(path, ManifestPath { id: id.into() });
+        Ok(())
+    }
+
+    pub fn remove(&mut self, path: &str) -> Option<String> {
+        normalize_path(path)
+            .and_then(|path| self.paths.remove(&path))
+            .map(|entry| entry.id)
+    }
+
+    pub fn get(&self, path: &str) -> Option<&str> {
+        let path = normalize_path(path)?;
+        self.paths.get(&path).map(|entry| entry.id.as_str())
+    }
+
+    pub fn index_path(&self) -> Option<&str> {
+        self.index.as_ref().map(|index| index.path.as_str())
+    }
+
+    /// Transaction id served for the bare manifest id
+    pub fn index_id(&self) -> Option<&str> {
+        self.index_path().and_then(|path| self.get(path))
+    }
+
+    /// Paths and ids in path order
+    pub fn paths(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.paths
+            .iter()
+            .map(|(path, entry)| (path.as_str(), entry.id.as_str()))
+    }
+
+    /// Resolve a link found in the document at `from` (such as
+    /// `../images/a.png` inside `posts/index.md`) to a transaction id
+    pub fn resolve(&self, from: &str, link: &str) -> Option<&str> {
+        let link = link.split(['#', '?']).next().unwrap_or_default();
+        let joined = match link.strip_prefix('/') {
+            Some(absolute) => absolute.to_string(),
+            None => match from.rfind('/') {
+                Some(slash) => format!("{}/{}", &from[..slash], link),
+                None => link.to_string(),
+            },
+        };
+        self.get(&joined)
+    }
+
+    /// Parse `data` as a path manifest, returning `None` for anything else
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        serde_json::from_slice::<Self>(data)
+            .ok()
+            .filter(|manifest| manifest.manifest == MANIFEST_KIND)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(|e| BlogClientError {
+            message: format!("Failed to serialize path manifest: {}", e),
+        })
+    }
+}
+
+/// Collapse `.` and `..` segments and empty components. Returns `None` for
+/// paths that climb above the manifest root or are empty.
+pub fn normalize_path(path: &str) -> Option<String> {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            segment => segments.push(segment),
+        }
+    }
+    if segments.is_empty() {
+        None
+    } else {
+        Some(segments.join("/"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_are_normalized_within_the_root() {
+        assert_eq!(normalize_path("images/photo.png").as_deref(), Some("images/photo.png"));
+        assert_eq!(normalize_path("./images//photo.png").as_deref(), Some("images/photo.png"));
+        assert_eq!(normalize_path("/images/../files/a.txt").as_deref(), Some("files/a.txt"));
+        assert_eq!(normalize_path("a/b/../../c").as_deref(), Some("c"));
+        assert_eq!(normalize_path("../secret"), None);
+        assert_eq!(normalize_path("a/../.."), None);
+        assert_eq!(normalize_path("./"), None);
+        assert_eq!(normalize_path(""), None);
+    }
+}
@@ -0,0 +1,139 @@
+//! In-process Arweave gateway and bundler for tests. It records what
+//! clients post and serves whatever data the test puts in it.
+
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+#[derive(Default)]
+pub(crate) struct GatewayState {
+    pub price: u64,
+    pub balance: u64,
+    pub anchor: String,
+    /// Transaction headers posted to `/tx`, in order
+    pub transactions: Vec<serde_json::Value>,
+    /// Chunks posted to `/chunk`, in order
+    pub chunks: Vec<serde_json::Value>,
+    /// Reject chunks once this many have been accepted
+    pub chunk_limit: Option<usize>,
+    /// Data items posted to the bundler, in order
+    pub data_items: Vec<Vec<u8>>,
+    /// Lamports the bundler charges per item; zero means free
+    pub bundler_price: u64,
+    /// Lamports funded with the bundler, whoever asks
+    pub bundler_balance: u64,
+    /// Addresses whose bundler balance was queried, in order
+    pub balance_queries: Vec<String>,
+    /// Bytes served at `/{id}` and `/raw/{id}`
+    pub data: HashMap<String, Vec<u8>>,
+    /// JSON served at `/tx/{id}`
+    pub headers: HashMap<String, serde_json::Value>,
+    /// Data item headers returned by `/graphql` for `transaction(id:)`
+    pub data_item_headers: HashMap<String, serde_json::Value>,
+}
+
+pub(crate) struct MockGateway {
+    pub url: String,
+    pub state: Arc<Mutex<GatewayState>>,
+    server: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for MockGateway {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+type Shared = State<Arc<Mutex<GatewayState>>>;
+
+impl MockGateway {
+    pub async fn start(state: GatewayState) -> Self {
+        let state = Arc::new(Mutex::new(state));
+        let app = Router::new()
+            .route("/price/:bytes", get(|State(state): Shared| async move { state.lock().unwrap().price.to_string() }))
+            .route(
+                "/wallet/:address/balance",
+                get(|State(state): Shared| async move { state.lock().unwrap().balance.to_string() }),
+            )
+            .route("/tx_anchor", get(|State(state): Shared| async move { state.lock().unwrap().anchor.clone() }))
+            .route(
+                "/tx",
+                post(|State(state): Shared, Json(tx): Json<serde_json::Value>| async move {
+                    state.lock().unwrap().transactions.push(tx);
+                    "OK"
+                }),
+            )
+            .route(
+                "/chunk",
+                post(|State(state): Shared, Json(chunk): Json<serde_json::Value>| async move {
+                    let mut state = state.lock().unwrap();
+                    if state.chunk_limit.is_some_and(|limit| state.chunks.len() >= limit) {
+                        return StatusCode::SERVICE_UNAVAILABLE;
+                    }
+                    state.chunks.push(chunk);
+                    StatusCode::OK
+                }),
+            )
+            .route(
+                "/price/solana/:bytes",
+                get(|State(state): Shared| async move { state.lock().unwrap().bundler_price.to_string() }),
+            )
+            .route(
+                "/account/balance/solana",
+                get(
+                    |State(state): Shared, Query(query): Query<HashMap<String, String>>| async move {
+                        let mut state = state.lock().unwrap();
+                        state.balance_queries.push(query.get("address").cloned().unwrap_or_default());
+                        Json(serde_json::json!({ "balance": state.bundler_balance.to_string() }))
+                    },
+                ),
+            )
+            .route(
+                "/tx/solana",
+                post(|State(state): Shared, body: Bytes| async move {
+                    let mut state = state.lock().unwrap();
+                    state.data_items.push(body.to_vec());
+                    Json(serde_json::json!({ "id": format!("item-{}", state.data_items.len()) }))
+                }),
+            )
+            .route(
+                "/tx/:id",
+                get(|State(state): Shared, Path(id): Path<String>| async move {
+                    match state.lock().unwrap().headers.get(&id) {
+                        Some(header) => Ok(Json(header.clone())),
+                        None => Err(StatusCode::NOT_FOUND),
+                    }
+                }),
+            )
+            .route(
+                "/graphql",
+                post(|State(state): Shared, Json(query): Json<serde_json::Value>| async move {
+                    let id = query["variables"]["id"].as_str().unwrap_or_default().to_string();
+                    let header = state.lock().unwrap().data_item_headers.get(&id).cloned();
+                    Json(serde_json::json!({ "data": { "transaction": header } }))
+                }),
+            )
+            .route("/raw/:id", get(serve_data))
+            .route("/:id", get(serve_data))
+            .with_state(state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        Self { url, state, server }
+    }
+}
+
+async fn serve_data(State(state): Shared, Path(id): Path<String>) -> Result<Vec<u8>, StatusCode> {
+    state.lock().unwrap().data.get(&id).cloned().ok_or(StatusCode::NOT_FOUND)
+}
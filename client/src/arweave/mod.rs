@@ -0,0 +1,874 @@
+pub mod bundle;
+pub mod cache;
+pub mod gateway;
+pub mod manifest;
+#[cfg(test)]
+pub(crate) mod mock_gateway;
+pub mod transaction;
+pub mod upload;
+pub mod wallet;
+
+pub use solana_blog_types::arweave::merkle;
+
+use reqwest::{Client, StatusCode};
+use crate::types::{Result, BlogClientError};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use sha2::{Digest, Sha256};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use bundle::DataItem;
+use cache::{CacheStats, ContentCache};
+use gateway::{GatewayPool, Integrity, DEFAULT_FALLBACK_GATEWAYS};
+use manifest::{PathManifest, MANIFEST_CONTENT_TYPE};
+use transaction::ArweaveTransaction;
+use upload::UploadProgress;
+use wallet::ArweaveWallet;
+
+/// How often `/tx/{id}/status` is polled while waiting for confirmation
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+pub const DEFAULT_BUNDLER_URL: &str = "https://node1.bundlr.network";
+pub const DEFAULT_APP_NAME: &str = "Solana-Blog";
+
+pub use solana_blog_types::WINSTON_PER_AR;
+
+pub fn winston_to_ar(winston: u64) -> f64 {
+    winston as f64 / WINSTON_PER_AR as f64
+}
+
+/// Nearest winston amount to `ar`; negative amounts become zero
+pub fn ar_to_winston(ar: f64) -> u64 {
+    (ar * WINSTON_PER_AR as f64).round().max(0.0) as u64
+}
+
+/// Exact decimal AR amount, e.g. `0.000123` for 123000000 winston
+pub fn format_ar(winston: u64) -> String {
+    let whole = winston / WINSTON_PER_AR;
+    let fraction = winston % WINSTON_PER_AR;
+    if fraction == 0 {
+        return whole.to_string();
+    }
+    let fraction = format!("{:012}", fraction);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+/// Price of an upload next to the balance of the wallet paying for it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadQuote {
+    pub bytes: usize,
+    pub winston: u64,
+    pub balance_winston: u64,
+}
+
+impl UploadQuote {
+    pub fn is_affordable(&self) -> bool {
+        self.balance_winston >= self.winston
+    }
+
+    /// Winston missing to cover the upload, zero when affordable
+    pub fn shortfall(&self) -> u64 {
+        self.winston.saturating_sub(self.balance_winston)
+    }
+}
+
+/// What an upload costs, in the unit of whoever pays for it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UploadPrice {
+    /// Paid by the configured wallet as the transaction reward
+    Winston(u64),
+    /// Charged by the bundler against the signer's funded balance
+    Lamports(u64),
+}
+
+#[derive(Debug, Clone)]
+pub struct ArweaveClient {
+    client: Client,
+    gateway_url: String,
+    fallback_gateways: Vec<String>,
+    gateway_pool: GatewayPool,
+    bundler_url: String,
+    app_name: String,
+    wallet: Option<Arc<ArweaveWallet>>,
+    bundle_signer: Option<BundleSigner>,
+    cache: Option<Arc<ContentCache>>,
+    resume_dir: Option<PathBuf>,
+    progress: Option<ProgressReporter>,
+}
+
+/// Solana keypair used to sign data items when no per-upload signer is given
+#[derive(Clone)]
+struct BundleSigner(Arc<Keypair>);
+
+impl std::fmt::Debug for BundleSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("BundleSigner").field(&self.0.pubkey()).finish()
+    }
+}
+
+/// Called with the progress of every upload signed by the JWK wallet
+#[derive(Clone)]
+struct ProgressReporter(Arc<dyn Fn(UploadProgress) + Send + Sync>);
+
+impl std::fmt::Debug for ProgressReporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProgressReporter")
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArweaveResponse {
+    id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArweaveTag {
+    pub name: String,
+    pub value: String,
+}
+
+impl ArweaveTag {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+/// Confirmation state reported by `/tx/{id}/status`
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransactionStatus {
+    /// Unknown to the gateway (not yet propagated, or dropped)
+    NotFound,
+    /// Accepted into the mempool but not yet mined
+    Pending,
+    Confirmed {
+        block_height: u64,
+        block_indep_hash: String,
+        number_of_confirmations: u64,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct StatusResponse {
+    block_height: u64,
+    block_indep_hash: String,
+    number_of_confirmations: u64,
+}
+
+pub(crate) fn b64url_encode(data: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(data)
+}
+
+pub(crate) fn b64url_decode(data: &str) -> Result<Vec<u8>> {
+    URL_SAFE_NO_PAD
+        .decode(data.trim_end_matches('='))
+        .map_err(|e| BlogClientError {
+            message: format!("Invalid base64url data: {}", e),
+        })
+}
+
+impl ArweaveClient {
+    pub fn new() -> Self {
+        let gateway_url = "https://arweave.net".to_string();
+        let fallback_gateways: Vec<String> = DEFAULT_FALLBACK_GATEWAYS
+            .iter()
+            .map(|url| url.to_string())
+            .collect();
+
+        Self {
+            client: Client::new(),
+            gateway_pool: Self::build_pool(&gateway_url, &fallback_gateways, None),
+            gateway_url,
+            fallback_gateways,
+            bundler_url: DEFAULT_BUNDLER_URL.to_string(),
+            app_name: DEFAULT_APP_NAME.to_string(),
+            wallet: None,
+            bundle_signer: None,
+            cache: None,
+            resume_dir: None,
+            progress: None,
+        }
+    }
+
+    /// Sign uploads natively with the wallet in `jwk_json`
+    pub fn with_wallet_key(self, jwk_json: &str) -> Result<Self> {
+        Ok(self.with_wallet(ArweaveWallet::from_jwk(jwk_json)?))
+    }
+
+    pub fn with_wallet(mut self, wallet: ArweaveWallet) -> Self {
+        self.wallet = Some(Arc::new(wallet));
+        self
+    }
+
+    fn build_pool(gateway_url: &str, fallback_gateways: &[String], previous: Option<&GatewayPool>) -> GatewayPool {
+        let mut gateways = vec![gateway_url.to_string()];
+        gateways.extend(fallback_gateways.iter().filter(|url| *url != gateway_url).cloned());
+        let pool = GatewayPool::new(gateways);
+        match previous {
+            Some(previous) => pool.with_timeout(previous.timeout()),
+            None => pool,
+        }
+    }
+
+    /// Point at a different gateway, e.g. `http://localhost:1984` for arlocal.
+    /// Uploads go here and reads try it first.
+    pub fn with_gateway_url(mut self, gateway_url: String) -> Self {
+        self.gateway_pool = Self::build_pool(&gateway_url, &self.fallback_gateways, Some(&self.gateway_pool));
+        self.gateway_url = gateway_url;
+        self
+    }
+
+    /// Gateways reads fall back to, in order, after the primary gateway.
+    /// Pass an empty list to read only from the primary gateway.
+    pub fn with_fallback_gateways(mut self, fallback_gateways: Vec<String>) -> Self {
+        self.gateway_pool = Self::build_pool(&self.gateway_url, &fallback_gateways, Some(&self.gateway_pool));
+        self.fallback_gateways = fallback_gateways;
+        self
+    }
+
+    /// Per-gateway timeout for reads
+    pub fn with_gateway_timeout(mut self, timeout: Duration) -> Self {
+        self.gateway_pool = self.gateway_pool.with_timeout(timeout);
+        self
+    }
+
+    /// Bundler that receives ANS-104 data items, e.g. an Irys node
+    pub fn with_bundler_url(mut self, bundler_url: String) -> Self {
+        self.bundler_url = bundler_url;
+        self
+    }
+
+    /// Value of the `App-Name` tag attached to every upload
+    pub fn with_app_name(mut self, app_name: String) -> Self {
+        self.app_name = app_name;
+        self
+    }
+
+    /// Sign data items with `signer` when an upload does not name its own
+    pub fn with_bundle_signer(mut self, signer: Keypair) -> Self {
+        self.bundle_signer = Some(BundleSigner(Arc::new(signer)));
+        self
+    }
+
+    /// Checkpoint uploads signed by the JWK wallet in `dir`, so one that
+    /// is interrupted continues from its last accepted chunk when the same
+    /// data is uploaded again
+    pub fn with_resume_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.resume_dir = Some(dir.into());
+        self
+    }
+
+    /// Report the progress of uploads signed by the JWK wallet to
+    /// `on_progress`, after the header and after every chunk
+    pub fn with_progress(mut self, on_progress: impl Fn(UploadProgress) + Send + Sync + 'static) -> Self {
+        self.progress = Some(ProgressReporter(Arc::new(on_progress)));
+        self
+    }
+
+    /// Serve reads from `cache` when possible and cache what gets fetched
+    pub fn with_cache(mut self, cache: ContentCache) -> Self {
+        self.cache = Some(Arc::new(cache));
+        self
+    }
+
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(|cache| cache.stats())
+    }
+
+    pub fn wallet(&self) -> Option<&ArweaveWallet> {
+        self.wallet.as_deref()
+    }
+
+    pub fn bundler_url(&self) -> &str {
+        &self.bundler_url
+    }
+
+    pub async fn upload_data(&self, data: &[u8], content_type: &str) -> Result<String> {
+        self.upload_tagged(data, content_type, &[], None).await
+    }
+
+    /// Upload `data` with a `Content-Type`, the `App-Name` tag and `tags`.
+    ///
+    /// A configured JWK wallet signs a native transaction. Without one the
+    /// data is bundled as an ANS-104 item signed by `signer`, or else by the
+    /// configured bundle signer. With none of them the upload fails:
+    /// bundlers no longer take unsigned data, so there is no anonymous
+    /// upload path.
+    pub async fn upload_tagged(
+        &self,
+        data: &[u8],
+        content_type: &str,
+        tags: &[ArweaveTag],
+        signer: Option<&Keypair>,
+    ) -> Result<String> {
+        let all_tags = self.upload_tags(content_type, tags);
+
+        if self.wallet.is_some() {
+            // Named by digest, so uploading the same data again finds it
+            let state_path = self
+                .resume_dir
+                .as_ref()
+                .map(|dir| dir.join(format!("{}.json", b64url_encode(&Sha256::digest(data)))));
+            let on_progress = |progress| {
+                if let Some(ProgressReporter(report)) = &self.progress {
+                    report(progress)
+                }
+            };
+            return self
+                .upload_with_state(data, &all_tags, state_path.as_deref(), &on_progress)
+                .await;
+        }
+        match signer.or(self.bundle_signer.as_ref().map(|BundleSigner(signer)| signer.as_ref())) {
+            Some(signer) => self.upload_data_item(data, &all_tags, signer).await,
+            None => Err(BlogClientError {
+                message: "No Arweave wallet or bundle signer configured for uploads".to_string(),
+            }),
+        }
+    }
+
+    /// `Content-Type` and `App-Name` followed by `tags`
+    fn upload_tags(&self, content_type: &str, tags: &[ArweaveTag]) -> Vec<ArweaveTag> {
+        let mut all_tags = vec![
+            ArweaveTag::new("Content-Type", content_type),
+            ArweaveTag::new("App-Name", self.app_name.clone()),
+        ];
+        all_tags.extend_from_slice(tags);
+        all_tags
+    }
+
+    /// Create, sign and post a v2 transaction carrying `data`. Data that fits
+    /// in one chunk travels in the transaction body; larger data is posted
+    /// chunk by chunk to `/chunk` after the header.
+    pub async fn upload_signed(&self, data: &[u8], tags: &[ArweaveTag]) -> Result<String> {
+        let transaction = self.signed_transaction(data, tags).await?;
+        self.upload_chunks(upload::UploadState::new(transaction), data, None, &|_| {})
+            .await
+    }
+
+    /// A transaction for `data` signed by the JWK wallet, after checking the
+    /// wallet can pay for it
+    async fn signed_transaction(&self, data: &[u8], tags: &[ArweaveTag]) -> Result<ArweaveTransaction> {
+        let wallet = self.wallet.as_deref().ok_or_else(|| BlogClientError {
+            message: "No Arweave wallet configured for signed uploads".to_string(),
+        })?;
+
+        let quote = self.ensure_affordable(data.len()).await?;
+        let last_tx = self.tx_anchor().await?;
+
+        let mut tx = ArweaveTransaction::new(data, wallet.owner(), last_tx, quote.winston, tags);
+        tx.sign(wallet)?;
+        Ok(tx)
+    }
+
+    async fn tx_anchor(&self) -> Result<String> {
+        let response = self
+            .client
+            .get(format!("{}/tx_anchor", self.gateway_url))
+            .send()
+            .await
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to fetch Arweave tx anchor: {}", e),
+            })?;
+
+        if !response.status().is_success() {
+            return Err(BlogClientError {
+                message: format!("Arweave tx anchor request failed with status: {}", response.status()),
+            });
+        }
+
+        response.text().await.map(|anchor| anchor.trim().to_string()).map_err(|e| BlogClientError {
+            message: format!("Failed to read Arweave tx anchor: {}", e),
+        })
+    }
+
+    async fn post_transaction(&self, tx: &ArweaveTransaction) -> Result<()> {
+        let response = self
+            .client
+            .post(format!("{}/tx", self.gateway_url))
+            .json(tx)
+            .send()
+            .await
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to post Arweave transaction: {}", e),
+            })?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            Err(BlogClientError {
+                message: format!("Arweave transaction rejected with status {}: {}", status, body),
+            })
+        }
+    }
+
+    pub(crate) async fn post_chunk(&self, tx: &ArweaveTransaction, data: &[u8], index: usize) -> Result<()> {
+        let proof = &tx.chunks.proofs[index];
+        let body = serde_json::json!({
+            "data_root": tx.data_root,
+            "data_size": tx.data_size,
+            "data_path": b64url_encode(&proof.proof),
+            "offset": proof.offset.to_string(),
+            "chunk": b64url_encode(tx.chunks.chunk_bytes(data, index)),
+        });
+
+        let response = self
+            .client
+            .post(format!("{}/chunk", self.gateway_url))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to upload Arweave chunk {}: {}", index, e),
+            })?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            Err(BlogClientError {
+                message: format!("Arweave chunk {} rejected with status {}: {}", index, status, body),
+            })
+        }
+    }
+
+    pub async fn get_status(&self, tx_id: &str) -> Result<TransactionStatus> {
+        let response = self
+            .client
+            .get(format!("{}/tx/{}/status", self.gateway_url, tx_id))
+            .send()
+            .await
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to fetch Arweave transaction status: {}", e),
+            })?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(TransactionStatus::NotFound),
+            StatusCode::ACCEPTED => Ok(TransactionStatus::Pending),
+            status if status.is_success() => {
+                let status: StatusResponse = response.json().await.map_err(|e| BlogClientError {
+                    message: format!("Failed to parse Arweave transaction status: {}", e),
+                })?;
+                Ok(TransactionStatus::Confirmed {
+                    block_height: status.block_height,
+                    block_indep_hash: status.block_indep_hash,
+                    number_of_confirmations: status.number_of_confirmations,
+                })
+            }
+            status => Err(BlogClientError {
+                message: format!("Arweave status request failed with status: {}", status),
+            }),
+        }
+    }
+
+    /// Poll until `tx_id` has at least `confirmations` confirmations
+    pub async fn wait_for_confirmation(
+        &self,
+        tx_id: &str,
+        confirmations: u64,
+        timeout: Duration,
+    ) -> Result<TransactionStatus> {
+        let started = std::time::Instant::now();
+
+        loop {
+            let status = self.get_status(tx_id).await?;
+            if let TransactionStatus::Confirmed {
+                number_of_confirmations,
+                ..
+            } = &status
+            {
+                if *number_of_confirmations >= confirmations {
+                    return Ok(status);
+                }
+            }
+
+            if started.elapsed() >= timeout {
+                return Err(BlogClientError {
+                    message: format!(
+                        "Arweave transaction {} not confirmed within {:?} (last status: {:?})",
+                        tx_id, timeout, status
+                    ),
+                });
+            }
+
+            tokio::time::sleep(STATUS_POLL_INTERVAL.min(timeout)).await;
+        }
+    }
+
+    /// Sign `data` as an ANS-104 data item with `signer` and post it to the
+    /// configured bundler, after checking the signer has funded it
+    pub async fn upload_data_item(
+        &self,
+        data: &[u8],
+        tags: &[ArweaveTag],
+        signer: &Keypair,
+    ) -> Result<String> {
+        let mut item = DataItem::new(&signer.pubkey().to_bytes(), data, tags);
+        item.sign(signer);
+        self.ensure_bundler_affordable(item.to_bytes().len(), &signer.pubkey())
+            .await?;
+
+        let response = self
+            .client
+            .post(format!("{}/tx/solana", self.bundler_url))
+            .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+            .body(item.to_bytes())
+            .send()
+            .await
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to upload to bundler: {}", e),
+            })?;
+
+        if response.status().is_success() {
+            let arweave_response: ArweaveResponse = response
+                .json()
+                .await
+                .map_err(|e| BlogClientError {
+                    message: format!("Failed to parse bundler response: {}", e),
+                })?;
+            Ok(arweave_response.id)
+        } else {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            Err(BlogClientError {
+                message: format!("Bundler upload failed with status {}: {}", status, body),
+            })
+        }
+    }
+
+    /// Fetch `tx_id` through the gateway pool, falling back to the next
+    /// gateway when one fails, times out or serves bytes that do not match
+    /// the signed header of `tx_id` (see `Integrity::Signed`). The bytes are
+    /// returned as uploaded, so a manifest id yields the manifest itself.
+    pub async fn get_data(&self, tx_id: &str) -> Result<Vec<u8>> {
+        self.get_data_verified(tx_id, &Integrity::Signed).await
+    }
+
+    /// Like `get_data`, but only accepts bytes that pass `integrity`; a
+    /// gateway serving mismatching data is treated as failed. Cached bytes
+    /// are only reused by reads checked the same way they were.
+    pub async fn get_data_verified(&self, tx_id: &str, integrity: &Integrity) -> Result<Vec<u8>> {
+        let key = cache_key(tx_id, integrity);
+        if let Some(cache) = &self.cache {
+            if let Some(data) = cache.get(&key).await {
+                return Ok(data);
+            }
+        }
+
+        let data = self.gateway_pool.fetch(&self.client, tx_id, integrity).await?;
+
+        if let Some(cache) = &self.cache {
+            // A cache write failure should not fail the read
+            let _ = cache.put(&key, &data).await;
+        }
+        Ok(data)
+    }
+
+    pub fn gateway_pool(&self) -> &GatewayPool {
+        &self.gateway_pool
+    }
+
+    /// Price in winston to store `bytes` bytes, from the gateway `/price` endpoint
+    pub async fn price(&self, bytes: usize) -> Result<u64> {
+        self.get_amount(&format!("{}/price/{}", self.gateway_url, bytes), "Arweave price")
+            .await
+    }
+
+    /// Balance in winston of the wallet at `address`
+    pub async fn balance(&self, address: &str) -> Result<u64> {
+        self.get_amount(
+            &format!("{}/wallet/{}/balance", self.gateway_url, address),
+            "Arweave balance",
+        )
+        .await
+    }
+
+    /// Price of uploading `bytes` bytes with the configured wallet, next to
+    /// that wallet's balance
+    pub async fn quote_upload(&self, bytes: usize) -> Result<UploadQuote> {
+        let wallet = self.wallet.as_deref().ok_or_else(|| BlogClientError {
+            message: "No Arweave wallet configured to quote uploads for".to_string(),
+        })?;
+        let winston = self.price(bytes).await?;
+        let balance_winston = self.balance(&wallet.address()).await?;
+        Ok(UploadQuote {
+            bytes,
+            winston,
+            balance_winston,
+        })
+    }
+
+    /// Quote an upload and refuse it up front when the wallet cannot pay,
+    /// instead of letting the gateway reject the signed transaction
+    pub async fn ensure_affordable(&self, bytes: usize) -> Result<UploadQuote> {
+        let quote = self.quote_upload(bytes).await?;
+        if !quote.is_affordable() {
+            return Err(BlogClientError {
+                message: format!(
+                    "Insufficient AR balance: uploading {} bytes costs {} AR, wallet holds {} AR",
+                    bytes,
+                    format_ar(quote.winston),
+                    format_ar(quote.balance_winston)
+                ),
+            });
+        }
+        Ok(quote)
+    }
+
+    /// Price in lamports the bundler charges for a `bytes`-byte data item
+    pub async fn bundler_price(&self, bytes: usize) -> Result<u64> {
+        self.get_amount(&format!("{}/price/solana/{}", self.bundler_url, bytes), "bundler price")
+            .await
+    }
+
+    /// Price of uploading `bytes` bytes through `upload_tagged`, from the
+    /// same source that upload checks: the gateway when a wallet signs
+    /// uploads, the bundler otherwise
+    pub async fn upload_price(&self, bytes: usize) -> Result<UploadPrice> {
+        if self.wallet.is_some() {
+            self.price(bytes).await.map(UploadPrice::Winston)
+        } else {
+            self.bundler_price(bytes).await.map(UploadPrice::Lamports)
+        }
+    }
+
+    /// Lamports `address` has funded with the bundler
+    pub async fn bundler_balance(&self, address: &Pubkey) -> Result<u64> {
+        let response = self
+            .client
+            .get(format!("{}/account/balance/solana", self.bundler_url))
+            .query(&[("address", address.to_string())])
+            .send()
+            .await
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to fetch bundler balance: {}", e),
+            })?;
+
+        if !response.status().is_success() {
+            return Err(BlogClientError {
+                message: format!("Bundler balance request failed with status: {}", response.status()),
+            });
+        }
+
+        let body: serde_json::Value = response.json().await.map_err(|e| BlogClientError {
+            message: format!("Failed to parse bundler balance: {}", e),
+        })?;
+        // Bundlers send the balance as a decimal string
+        let balance = &body["balance"];
+        balance
+            .as_u64()
+            .or_else(|| balance.as_str().and_then(|balance| balance.parse().ok()))
+            .ok_or_else(|| BlogClientError {
+                message: format!("Invalid bundler balance: {}", body),
+            })
+    }
+
+    /// Refuse a bundled upload up front when `signer` has not funded the
+    /// bundler enough to cover it. Uploads the bundler prices at zero, such
+    /// as small items on a free tier, need no balance.
+    pub async fn ensure_bundler_affordable(&self, bytes: usize, signer: &Pubkey) -> Result<()> {
+        let price = self.bundler_price(bytes).await?;
+        if price == 0 {
+            return Ok(());
+        }
+        let balance = self.bundler_balance(signer).await?;
+        if balance < price {
+            return Err(BlogClientError {
+                message: format!(
+                    "Insufficient bundler balance: uploading {} bytes costs {} lamports, {} has funded {} lamports",
+                    bytes, price, signer, balance
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// A plain integer amount served at `url`, with `what` naming it in errors
+    async fn get_amount(&self, url: &str, what: &str) -> Result<u64> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to fetch {}: {}", what, e),
+            })?;
+
+        if !response.status().is_success() {
+            return Err(BlogClientError {
+                message: format!("Request for {} failed with status: {}", what, response.status()),
+            });
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to read {}: {}", what, e),
+            })?;
+
+        body.trim().parse().map_err(|e| BlogClientError {
+            message: format!("Invalid {} '{}': {}", what, body.trim(), e),
+        })
+    }
+
+    pub fn get_url(&self, tx_id: &str) -> String {
+        format!("{}/{}", self.gateway_url, tx_id)
+    }
+
+    /// Gateway URL of `path` inside the path manifest `manifest_id`
+    pub fn get_path_url(&self, manifest_id: &str, path: &str) -> String {
+        format!("{}/{}/{}", self.gateway_url, manifest_id, path.trim_start_matches('/'))
+    }
+
+    /// Upload an `arweave/paths` manifest, returning its id
+    pub async fn upload_manifest(
+        &self,
+        manifest: &PathManifest,
+        tags: &[ArweaveTag],
+        signer: Option<&Keypair>,
+    ) -> Result<String> {
+        self.upload_tagged(&manifest.to_bytes()?, MANIFEST_CONTENT_TYPE, tags, signer)
+            .await
+    }
+
+    // Simple text upload for blog content
+    pub async fn upload_text(&self, text: &str) -> Result<String> {
+        self.upload_data(text.as_bytes(), "text/plain").await
+    }
+
+    // Upload JSON data
+    pub async fn upload_json(&self, data: &serde_json::Value) -> Result<String> {
+        let json_str = serde_json::to_string(data)
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to serialize JSON: {}", e),
+            })?;
+        self.upload_data(json_str.as_bytes(), "application/json").await
+    }
+}
+
+/// Cache key for `tx_id` read with `integrity`. Each level gets its own
+/// entry, so bytes an unverified read took from a gateway never answer a
+/// signed read.
+fn cache_key(tx_id: &str, integrity: &Integrity) -> String {
+    match integrity {
+        Integrity::Signed => tx_id.to_string(),
+        Integrity::Sha256(digest) => format!("{}_sha256_{}", tx_id, b64url_encode(digest)),
+        Integrity::None => format!("{}_unverified", tx_id),
+    }
+}
+
+impl Default for ArweaveClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mock_gateway::{GatewayState, MockGateway};
+    use solana_sdk::signature::keypair_from_seed;
+
+    #[tokio::test]
+    async fn cached_reads_keep_their_verification_level() {
+        let gateway = MockGateway::start(GatewayState::default()).await;
+        let dir = tempfile::tempdir().unwrap();
+        let client = ArweaveClient::new()
+            .with_gateway_url(gateway.url.clone())
+            .with_fallback_gateways(Vec::new())
+            .with_cache(ContentCache::open(dir.path(), 1 << 20).unwrap());
+
+        let mut item = DataItem::new(&[], b"# Hello", &[]);
+        item.sign(&keypair_from_seed(&[1u8; 32]).unwrap());
+        let id = item.id();
+        {
+            let mut state = gateway.state.lock().unwrap();
+            state.data_item_headers.insert(
+                id.clone(),
+                serde_json::json!({
+                    "signature": b64url_encode(item.signature()),
+                    "anchor": b64url_encode(item.anchor()),
+                    "owner": { "key": b64url_encode(item.owner()) },
+                    "tags": [],
+                }),
+            );
+            state.data.insert(id.clone(), b"# Hacked".to_vec());
+        }
+
+        // An unverified read caches what the gateway served...
+        assert_eq!(client.get_data_verified(&id, &Integrity::None).await.unwrap(), b"# Hacked");
+        // ...which a signed read must not pick up
+        assert!(client.get_data(&id).await.is_err());
+
+        gateway.state.lock().unwrap().data.insert(id.clone(), b"# Hello".to_vec());
+        assert_eq!(client.get_data(&id).await.unwrap(), b"# Hello");
+
+        // Verified bytes are served from the cache once the gateway is gone
+        drop(gateway);
+        assert_eq!(client.get_data(&id).await.unwrap(), b"# Hello");
+        assert_eq!(client.cache_stats().unwrap().hits, 1);
+    }
+
+    #[tokio::test]
+    async fn bundled_uploads_need_a_funded_bundler_balance() {
+        let gateway = MockGateway::start(GatewayState {
+            bundler_price: 5_000,
+            bundler_balance: 4_999,
+            ..GatewayState::default()
+        })
+        .await;
+        let signer = keypair_from_seed(&[1u8; 32]).unwrap();
+        let client = ArweaveClient::new().with_bundler_url(gateway.url.clone());
+
+        let error = client
+            .upload_tagged(b"hello", "text/plain", &[], Some(&signer))
+            .await
+            .unwrap_err();
+        assert!(error.message.contains("Insufficient bundler balance"), "{}", error.message);
+        assert!(error.message.contains(&signer.pubkey().to_string()), "{}", error.message);
+        assert!(gateway.state.lock().unwrap().data_items.is_empty());
+
+        gateway.state.lock().unwrap().bundler_balance = 5_000;
+        let id = client
+            .upload_tagged(b"hello", "text/plain", &[], Some(&signer))
+            .await
+            .unwrap();
+        assert_eq!(id, "item-1");
+        assert_eq!(
+            gateway.state.lock().unwrap().balance_queries,
+            [signer.pubkey().to_string(), signer.pubkey().to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn uploads_are_priced_by_whoever_will_be_paid() {
+        let gateway = MockGateway::start(GatewayState {
+            price: 700,
+            bundler_price: 5_000,
+            ..GatewayState::default()
+        })
+        .await;
+        let client = ArweaveClient::new()
+            .with_gateway_url(gateway.url.clone())
+            .with_bundler_url(gateway.url.clone());
+        assert_eq!(client.upload_price(10).await.unwrap(), UploadPrice::Lamports(5_000));
+
+        let client = client.with_wallet_key(include_str!("../../testdata/arweave-wallet.json")).unwrap();
+        assert_eq!(client.upload_price(10).await.unwrap(), UploadPrice::Winston(700));
+    }
+
+    #[tokio::test]
+    async fn bundler_price_errors_name_the_bundler() {
+        let client = ArweaveClient::new().with_bundler_url("http://127.0.0.1:1".to_string());
+        let error = client.bundler_price(10).await.unwrap_err();
+        assert!(error.message.starts_with("Failed to fetch bundler price"), "{}", error.message);
+    }
+}
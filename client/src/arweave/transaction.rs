@@ -0,0 +1,163 @@
+use super::{
+    b64url_decode, b64url_encode,
+    merkle::{self, TransactionChunks},
+    wallet::{self, ArweaveWallet},
+    ArweaveTag,
+};
+use crate::types::{BlogClientError, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use solana_blog_types::arweave::SignedHeader;
+pub use solana_blog_types::arweave::{deep_hash, DeepHashItem};
+
+/// A format 2 Arweave transaction. Binary fields are base64url strings and
+/// tags are stored encoded, exactly as the gateway `/tx` endpoint expects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArweaveTransaction {
+    pub format: u8,
+    pub id: String,
+    pub last_tx: String,
+    pub owner: String,
+    pub tags: Vec<ArweaveTag>,
+    pub target: String,
+    pub quantity: String,
+    /// Empty for format 2 transactions, whose data travels in chunks
+    #[serde(default)]
+    pub data: String,
+    pub data_size: String,
+    pub data_root: String,
+    pub reward: String,
+    pub signature: String,
+    #[serde(skip)]
+    pub chunks: TransactionChunks,
+}
+
+impl ArweaveTransaction {
+    /// Build an unsigned data transaction. `last_tx` is the anchor from
+    /// `/tx_anchor` and `reward` the winston price from `/price/{bytes}`.
+    pub fn new(data: &[u8], owner: &[u8], last_tx: String, reward: u64, tags: &[ArweaveTag]) -> Self {
+        let chunks = merkle::generate_transaction_chunks(data);
+
+        Self {
+            format: 2,
+            id: String::new(),
+            last_tx,
+            owner: b64url_encode(owner),
+            tags: tags
+                .iter()
+                .map(|tag| ArweaveTag {
+                    name: b64url_encode(tag.name.as_bytes()),
+                    value: b64url_encode(tag.value.as_bytes()),
+                })
+                .collect(),
+            target: String::new(),
+            quantity: "0".to_string(),
+            data: String::new(),
+            data_size: data.len().to_string(),
+            data_root: b64url_encode(&chunks.data_root),
+            reward: reward.to_string(),
+            signature: String::new(),
+            chunks,
+        }
+    }
+
+    /// The message that gets signed: a deep hash over the header fields
+    pub fn signature_data(&self) -> Result<[u8; 48]> {
+        if self.format != 2 {
+            return Err(BlogClientError {
+                message: format!("Unsupported Arweave transaction format {}", self.format),
+            });
+        }
+        let tags = self
+            .tags
+            .iter()
+            .map(|tag| Ok((b64url_decode(&tag.name)?, b64url_decode(&tag.value)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(SignedHeader {
+            owner: &b64url_decode(&self.owner)?,
+            target: &b64url_decode(&self.target)?,
+            quantity: &self.quantity,
+            reward: &self.reward,
+            last_tx: &b64url_decode(&self.last_tx)?,
+            tags: &tags,
+            data_size: &self.data_size,
+            data_root: &b64url_decode(&self.data_root)?,
+        }
+        .signature_data())
+    }
+
+    /// Sign with `wallet`; the id is the SHA-256 of the signature
+    pub fn sign(&mut self, wallet: &ArweaveWallet) -> Result<()> {
+        let signature = wallet.sign(&self.signature_data()?)?;
+        self.id = b64url_encode(&Sha256::digest(&signature));
+        self.signature = b64url_encode(&signature);
+        Ok(())
+    }
+
+    /// Check that this header, as served for `tx_id`, was signed by its
+    /// owner and commits to `data`. The id is the hash of the signature, so
+    /// a gateway cannot forge a header that passes.
+    pub fn verify(&self, tx_id: &str, data: &[u8]) -> Result<()> {
+        let fail = |reason: &str| BlogClientError {
+            message: format!("transaction header for {} {}", tx_id, reason),
+        };
+
+        if self.format != 2 {
+            return Err(fail(&format!("has unsupported format {}", self.format)));
+        }
+        let signature = b64url_decode(&self.signature)?;
+        if self.id != tx_id || b64url_encode(&Sha256::digest(&signature)) != tx_id {
+            return Err(fail("does not match its id"));
+        }
+        if !wallet::verify_signature(&b64url_decode(&self.owner)?, &self.signature_data()?, &signature) {
+            return Err(fail("has an invalid signature"));
+        }
+        if self.data_size != data.len().to_string()
+            || merkle::compute_data_root(data).as_slice() != b64url_decode(&self.data_root)?.as_slice()
+        {
+            return Err(fail("does not match the content"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arweave::wallet::verify_signature;
+
+    #[test]
+    fn signed_transactions_verify_and_are_identified_by_their_signature() {
+        let wallet = ArweaveWallet::from_jwk(include_str!("../../testdata/arweave-wallet.json")).unwrap();
+        let tags = [ArweaveTag::new("Content-Type", "text/plain")];
+        let mut tx = ArweaveTransaction::new(b"hello", wallet.owner(), b64url_encode(&[7; 48]), 1000, &tags);
+        tx.sign(&wallet).unwrap();
+
+        let signature = b64url_decode(&tx.signature).unwrap();
+        assert!(verify_signature(wallet.owner(), &tx.signature_data().unwrap(), &signature));
+        assert_eq!(tx.id, b64url_encode(&Sha256::digest(&signature)));
+        assert_eq!(tx.tags[0].name, "Q29udGVudC1UeXBl");
+        assert_eq!(tx.data_size, "5");
+    }
+
+    #[test]
+    fn headers_verify_only_against_their_own_id_and_data() {
+        let wallet = ArweaveWallet::from_jwk(include_str!("../../testdata/arweave-wallet.json")).unwrap();
+        let data = vec![5u8; 300 * 1024];
+        let mut tx = ArweaveTransaction::new(&data, wallet.owner(), b64url_encode(&[7; 48]), 1000, &[]);
+        tx.sign(&wallet).unwrap();
+
+        assert!(tx.verify(&tx.id, &data).is_ok());
+        assert!(tx.verify("some-other-id", &data).is_err());
+
+        let mut changed = data.clone();
+        changed[1000] = 6;
+        assert!(tx.verify(&tx.id, &changed).is_err());
+
+        // A gateway that swaps the data root breaks the signature
+        let mut forged = tx.clone();
+        forged.data_root = b64url_encode(&merkle::compute_data_root(&changed));
+        assert!(forged.verify(&tx.id, &changed).is_err());
+    }
+}
@@ -0,0 +1,388 @@
+use super::{b64url_encode, merkle, transaction::ArweaveTransaction, ArweaveClient, ArweaveTag};
+use crate::types::{BlogClientError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Progress of an upload, reported once the header is accepted and after
+/// every chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadProgress {
+    pub chunks_uploaded: usize,
+    pub total_chunks: usize,
+    pub bytes_uploaded: usize,
+    pub total_bytes: usize,
+}
+
+impl UploadProgress {
+    pub fn fraction(&self) -> f64 {
+        if self.total_bytes == 0 {
+            1.0
+        } else {
+            self.bytes_uploaded as f64 / self.total_bytes as f64
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.chunks_uploaded == self.total_chunks
+    }
+}
+
+/// Upload state saved after every accepted step so an interrupted upload
+/// can continue from the last confirmed chunk instead of starting over
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadState {
+    /// Signed header; chunk proofs are recomputed from the data on resume
+    pub transaction: ArweaveTransaction,
+    pub header_posted: bool,
+    pub next_chunk: usize,
+}
+
+impl UploadState {
+    pub(crate) fn new(transaction: ArweaveTransaction) -> Self {
+        Self {
+            transaction,
+            header_posted: false,
+            next_chunk: 0,
+        }
+    }
+
+    /// Saved state at `path`, or `None` if there is none
+    pub async fn load(path: &Path) -> Result<Option<Self>> {
+        let data = match tokio::fs::read(path).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(BlogClientError {
+                    message: format!("Failed to read upload state {}: {}", path.display(), e),
+                })
+            }
+        };
+        serde_json::from_slice(&data).map(Some).map_err(|e| BlogClientError {
+            message: format!("Failed to parse upload state {}: {}", path.display(), e),
+        })
+    }
+
+    async fn save(&self, path: &Path) -> Result<()> {
+        let data = serde_json::to_vec(self).map_err(|e| BlogClientError {
+            message: format!("Failed to serialize upload state: {}", e),
+        })?;
+        // Write then rename so a crash never leaves a truncated state file
+        let temp_path = path.with_extension("tmp");
+        let write = async {
+            if let Some(dir) = path.parent() {
+                tokio::fs::create_dir_all(dir).await?;
+            }
+            tokio::fs::write(&temp_path, data).await?;
+            tokio::fs::rename(&temp_path, path).await
+        };
+        write.await.map_err(|e| BlogClientError {
+            message: format!("Failed to save upload state {}: {}", path.display(), e),
+        })
+    }
+
+    /// Whether this state belongs to an upload of `data`
+    fn matches(&self, data: &[u8]) -> bool {
+        self.transaction.data_size == data.len().to_string()
+            && self.transaction.data_root == b64url_encode(&merkle::compute_data_root(data))
+    }
+
+    fn progress(&self, data: &[u8]) -> UploadProgress {
+        let chunks = &self.transaction.chunks;
+        let total_chunks = chunks.chunks.len();
+        let chunks_uploaded = if data.len() <= merkle::MAX_CHUNK_SIZE && self.header_posted {
+            // Small data travels in the header
+            total_chunks
+        } else {
+            self.next_chunk
+        };
+        UploadProgress {
+            chunks_uploaded,
+            total_chunks,
+            bytes_uploaded: chunks.chunks[..chunks_uploaded]
+                .iter()
+                .map(|chunk| chunk.max_byte_range - chunk.min_byte_range)
+                .sum(),
+            total_bytes: data.len(),
+        }
+    }
+}
+
+impl ArweaveClient {
+    /// `upload_signed` with a callback invoked after the header and after
+    /// each chunk is accepted
+    pub async fn upload_with_progress(
+        &self,
+        data: &[u8],
+        content_type: &str,
+        tags: &[ArweaveTag],
+        on_progress: impl Fn(UploadProgress) + Send + Sync,
+    ) -> Result<String> {
+        let tags = self.upload_tags(content_type, tags);
+        self.upload_with_state(data, &tags, None, &on_progress).await
+    }
+
+    /// Upload `data` with the JWK wallet, saving progress to `state_path`
+    /// after every accepted chunk. When `state_path` already holds state for
+    /// the same data, the saved transaction is resumed from its next chunk
+    /// (keeping its original tags); otherwise a new transaction is signed.
+    /// The state file is removed once the upload completes.
+    ///
+    /// Only JWK uploads can resume. Bundled uploads send the whole signed
+    /// data item to the bundler in one request, so an interrupted one has no
+    /// accepted chunks to continue from and is simply sent again.
+    pub async fn upload_resumable(
+        &self,
+        data: &[u8],
+        content_type: &str,
+        tags: &[ArweaveTag],
+        state_path: &Path,
+        on_progress: impl Fn(UploadProgress) + Send + Sync,
+    ) -> Result<String> {
+        let tags = self.upload_tags(content_type, tags);
+        self.upload_with_state(data, &tags, Some(state_path), &on_progress)
+            .await
+    }
+
+    /// Sign and upload `data` with the JWK wallet under exactly `tags`,
+    /// resuming from and checkpointing to `state_path` if given
+    pub(crate) async fn upload_with_state(
+        &self,
+        data: &[u8],
+        tags: &[ArweaveTag],
+        state_path: Option<&Path>,
+        on_progress: &(dyn Fn(UploadProgress) + Send + Sync),
+    ) -> Result<String> {
+        let Some(state_path) = state_path else {
+            let transaction = self.signed_transaction(data, tags).await?;
+            return self
+                .upload_chunks(UploadState::new(transaction), data, None, on_progress)
+                .await;
+        };
+
+        let state = match UploadState::load(state_path).await? {
+            Some(mut state) if state.matches(data) => {
+                state.transaction.chunks = merkle::generate_transaction_chunks(data);
+                state
+            }
+            _ => UploadState::new(self.signed_transaction(data, tags).await?),
+        };
+        state.save(state_path).await?;
+
+        let tx_id = self
+            .upload_chunks(state, data, Some(state_path), on_progress)
+            .await?;
+
+        let _ = tokio::fs::remove_file(state_path).await;
+        Ok(tx_id)
+    }
+
+    /// Post the header if it has not been accepted yet, then every chunk
+    /// from `state.next_chunk` on, checkpointing to `state_path` if given
+    pub(crate) async fn upload_chunks(
+        &self,
+        mut state: UploadState,
+        data: &[u8],
+        state_path: Option<&Path>,
+        on_progress: &(dyn Fn(UploadProgress) + Send + Sync),
+    ) -> Result<String> {
+        let in_header = data.len() <= merkle::MAX_CHUNK_SIZE;
+
+        if !state.header_posted {
+            let mut header = state.transaction.clone();
+            if in_header {
+                header.data = b64url_encode(data);
+            }
+            self.post_transaction(&header).await?;
+
+            state.header_posted = true;
+            if let Some(path) = state_path {
+                state.save(path).await?;
+            }
+            on_progress(state.progress(data));
+        }
+
+        if !in_header {
+            for index in state.next_chunk..state.transaction.chunks.chunks.len() {
+                self.post_chunk(&state.transaction, data, index).await?;
+
+                state.next_chunk = index + 1;
+                if let Some(path) = state_path {
+                    state.save(path).await?;
+                }
+                on_progress(state.progress(data));
+            }
+        }
+
+        Ok(state.transaction.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arweave::{
+        b64url_decode,
+        mock_gateway::{GatewayState, MockGateway},
+        wallet::verify_signature,
+    };
+    use crate::storage::{ArweaveStore, ContentStore};
+    use solana_sdk::signature::Keypair;
+    use std::sync::{Arc, Mutex};
+
+    const TEST_JWK: &str = include_str!("../../testdata/arweave-wallet.json");
+
+    async fn gateway(balance: u64) -> MockGateway {
+        MockGateway::start(GatewayState {
+            price: 1_000,
+            balance,
+            anchor: b64url_encode(&[7; 48]),
+            ..GatewayState::default()
+        })
+        .await
+    }
+
+    fn client(gateway: &MockGateway) -> ArweaveClient {
+        ArweaveClient::new()
+            .with_gateway_url(gateway.url.clone())
+            .with_fallback_gateways(Vec::new())
+            .with_wallet_key(TEST_JWK)
+            .unwrap()
+    }
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn posted_transaction(gateway: &MockGateway) -> ArweaveTransaction {
+        let state = gateway.state.lock().unwrap();
+        assert_eq!(state.transactions.len(), 1);
+        serde_json::from_value(state.transactions[0].clone()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn small_data_travels_in_a_signed_header() {
+        let gateway = gateway(1_000_000).await;
+        let client = client(&gateway);
+
+        let id = client.upload_tagged(b"hello", "text/plain", &[], None).await.unwrap();
+
+        let tx = posted_transaction(&gateway);
+        assert_eq!(tx.id, id);
+        assert_eq!(b64url_decode(&tx.data).unwrap(), b"hello");
+        assert_eq!(tx.reward, "1000");
+        assert_eq!(tx.last_tx, b64url_encode(&[7; 48]));
+        let signature = b64url_decode(&tx.signature).unwrap();
+        let owner = client.wallet().unwrap().owner();
+        assert!(verify_signature(owner, &tx.signature_data().unwrap(), &signature));
+        assert!(gateway.state.lock().unwrap().chunks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_configured_wallet_signs_signed_puts() {
+        let gateway = gateway(1_000_000).await;
+        let store = ArweaveStore::new(client(&gateway).with_bundler_url(gateway.url.clone()));
+        let signer = Keypair::new();
+
+        let id = store.put_signed(b"hello", "text/plain", &[], &signer).await.unwrap();
+
+        assert_eq!(posted_transaction(&gateway).id, id.as_str());
+        assert!(gateway.state.lock().unwrap().data_items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn large_data_is_posted_chunk_by_chunk_with_progress() {
+        let gateway = gateway(1_000_000).await;
+        let client = client(&gateway);
+        let data = data(600 * 1024);
+        let progress = Mutex::new(Vec::new());
+
+        client
+            .upload_with_progress(&data, "application/octet-stream", &[], |update| {
+                progress.lock().unwrap().push(update)
+            })
+            .await
+            .unwrap();
+
+        let tx = posted_transaction(&gateway);
+        assert!(tx.data.is_empty());
+        let state = gateway.state.lock().unwrap();
+        let mut uploaded = Vec::new();
+        for chunk in &state.chunks {
+            assert_eq!(chunk["data_root"], tx.data_root);
+            uploaded.extend(b64url_decode(chunk["chunk"].as_str().unwrap()).unwrap());
+        }
+        assert_eq!(uploaded, data);
+        assert_eq!(state.chunks[1]["offset"], (2 * merkle::MAX_CHUNK_SIZE - 1).to_string());
+
+        let progress = progress.into_inner().unwrap();
+        let uploaded: Vec<usize> = progress.iter().map(|update| update.bytes_uploaded).collect();
+        assert_eq!(uploaded, [0, 262144, 524288, 614400]);
+        assert!(progress.last().unwrap().is_complete());
+    }
+
+    #[tokio::test]
+    async fn unaffordable_uploads_are_refused_before_posting() {
+        let gateway = gateway(999).await;
+        let client = client(&gateway);
+
+        let error = client.upload_tagged(b"hello", "text/plain", &[], None).await.unwrap_err();
+
+        assert!(error.message.contains("Insufficient AR balance"), "{}", error.message);
+        assert!(gateway.state.lock().unwrap().transactions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn interrupted_uploads_resume_from_the_next_chunk() {
+        let gateway = gateway(1_000_000).await;
+        gateway.state.lock().unwrap().chunk_limit = Some(1);
+        let client = client(&gateway);
+        let data = data(600 * 1024);
+        let dir = tempfile::tempdir().unwrap();
+        let state_path = dir.path().join("upload-state.json");
+
+        assert!(client
+            .upload_resumable(&data, "application/octet-stream", &[], &state_path, |_| {})
+            .await
+            .is_err());
+        let saved = UploadState::load(&state_path).await.unwrap().unwrap();
+        assert!(saved.header_posted);
+        assert_eq!(saved.next_chunk, 1);
+
+        gateway.state.lock().unwrap().chunk_limit = None;
+        let id = client
+            .upload_resumable(&data, "application/octet-stream", &[], &state_path, |_| {})
+            .await
+            .unwrap();
+
+        let state = gateway.state.lock().unwrap();
+        assert_eq!(state.transactions.len(), 1);
+        assert_eq!(state.chunks.len(), 3);
+        assert_eq!(id, saved.transaction.id);
+        assert!(!state_path.exists());
+    }
+
+    #[tokio::test]
+    async fn stored_content_resumes_and_reports_progress() {
+        let gateway = gateway(1_000_000).await;
+        gateway.state.lock().unwrap().chunk_limit = Some(1);
+        let dir = tempfile::tempdir().unwrap();
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let reported = progress.clone();
+        let store = ArweaveStore::new(
+            client(&gateway)
+                .with_resume_dir(dir.path())
+                .with_progress(move |update| reported.lock().unwrap().push(update.chunks_uploaded)),
+        );
+        let data = data(600 * 1024);
+        let signer = Keypair::new();
+
+        assert!(store.put_signed(&data, "image/png", &[], &signer).await.is_err());
+        gateway.state.lock().unwrap().chunk_limit = None;
+        let id = store.put_signed(&data, "image/png", &[], &signer).await.unwrap();
+
+        assert_eq!(posted_transaction(&gateway).id, id.as_str());
+        assert_eq!(gateway.state.lock().unwrap().chunks.len(), 3);
+        assert_eq!(*progress.lock().unwrap(), [0, 1, 2, 3]);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
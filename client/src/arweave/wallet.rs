@@ -0,0 +1,161 @@
+use super::{b64url_decode, b64url_encode};
+use crate::types::{BlogClientError, Result};
+use ring::{
+    rand::SystemRandom,
+    rsa::{KeyPair, KeyPairComponents, PublicKeyComponents},
+    signature::{RsaPublicKeyComponents, RSA_PSS_2048_8192_SHA256, RSA_PSS_SHA256},
+};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+/// Public exponent of every Arweave wallet
+const PUBLIC_EXPONENT: [u8; 3] = [0x01, 0x00, 0x01];
+
+#[derive(Deserialize)]
+struct Jwk {
+    kty: String,
+    n: String,
+    e: String,
+    d: Option<String>,
+    p: Option<String>,
+    q: Option<String>,
+    dp: Option<String>,
+    dq: Option<String>,
+    qi: Option<String>,
+}
+
+/// An Arweave RSA wallet loaded from a JWK file. Signing is done by *ring*,
+/// whose RSA private key operations are constant-time and blinded.
+pub struct ArweaveWallet {
+    key_pair: KeyPair,
+    owner: Vec<u8>,
+}
+
+impl std::fmt::Debug for ArweaveWallet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ArweaveWallet")
+            .field("address", &self.address())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Whether `signature` is a valid RSA-PSS signature over `message` by the
+/// wallet whose modulus is `owner`
+pub fn verify_signature(owner: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    RsaPublicKeyComponents {
+        n: owner,
+        e: &PUBLIC_EXPONENT[..],
+    }
+    .verify(&RSA_PSS_2048_8192_SHA256, message, signature)
+    .is_ok()
+}
+
+impl ArweaveWallet {
+    /// Parse a JWK as exported by arweave.app, ArConnect or `arweave key-create`
+    pub fn from_jwk(jwk_json: &str) -> Result<Self> {
+        let jwk: Jwk = serde_json::from_str(jwk_json).map_err(|e| BlogClientError {
+            message: format!("Failed to parse Arweave JWK: {}", e),
+        })?;
+
+        if jwk.kty != "RSA" {
+            return Err(BlogClientError {
+                message: format!("Unsupported Arweave key type: {}", jwk.kty),
+            });
+        }
+
+        // Every wallet tool exports the CRT parameters, which *ring* signs with
+        let private = |name: &str, value: Option<String>| {
+            let value = value.ok_or_else(|| BlogClientError {
+                message: format!("Arweave JWK has no private parameter '{}'", name),
+            })?;
+            b64url_decode(&value)
+        };
+        let components = KeyPairComponents {
+            public_key: PublicKeyComponents {
+                n: b64url_decode(&jwk.n)?,
+                e: b64url_decode(&jwk.e)?,
+            },
+            d: private("d", jwk.d)?,
+            p: private("p", jwk.p)?,
+            q: private("q", jwk.q)?,
+            dP: private("dp", jwk.dp)?,
+            dQ: private("dq", jwk.dq)?,
+            qInv: private("qi", jwk.qi)?,
+        };
+
+        let key_pair = KeyPair::from_components(&components).map_err(|e| BlogClientError {
+            message: format!("Invalid Arweave JWK: {}", e),
+        })?;
+        Ok(Self {
+            key_pair,
+            owner: components.public_key.n,
+        })
+    }
+
+    pub fn from_jwk_file(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let jwk_json = std::fs::read_to_string(path.as_ref()).map_err(|e| BlogClientError {
+            message: format!("Failed to read Arweave JWK {}: {}", path.as_ref().display(), e),
+        })?;
+        Self::from_jwk(&jwk_json)
+    }
+
+    /// The RSA modulus, which Arweave calls the transaction owner
+    pub fn owner(&self) -> &[u8] {
+        &self.owner
+    }
+
+    /// Wallet address: base64url SHA-256 of the modulus
+    pub fn address(&self) -> String {
+        b64url_encode(&Sha256::digest(&self.owner))
+    }
+
+    /// RSA-PSS signature over `message` with SHA-256, MGF1-SHA-256 and a
+    /// 32-byte salt, as arweave-js signs
+    pub fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        let mut signature = vec![0u8; self.key_pair.public().modulus_len()];
+        self.key_pair
+            .sign(&RSA_PSS_SHA256, &SystemRandom::new(), message, &mut signature)
+            .map_err(|_| BlogClientError {
+                message: "Failed to sign with Arweave wallet".to_string(),
+            })?;
+        Ok(signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_JWK: &str = include_str!("../../testdata/arweave-wallet.json");
+
+    #[test]
+    fn address_is_the_hash_of_the_modulus() {
+        let wallet = ArweaveWallet::from_jwk(TEST_JWK).unwrap();
+        assert_eq!(wallet.address(), "SiCaMDcQcS0T-Z9bLKgl6N7O9anaVxc0kPzjb6y_76M");
+        assert_eq!(wallet.owner().len(), 256);
+    }
+
+    #[test]
+    fn signatures_verify_with_the_public_key() {
+        let wallet = ArweaveWallet::from_jwk(TEST_JWK).unwrap();
+        let signature = wallet.sign(b"message").unwrap();
+
+        assert_eq!(signature.len(), 256);
+        assert!(verify_signature(wallet.owner(), b"message", &signature));
+        assert!(!verify_signature(wallet.owner(), b"other message", &signature));
+        // PSS is salted, so signing twice gives different signatures
+        assert_ne!(wallet.sign(b"message").unwrap(), signature);
+    }
+
+    #[test]
+    fn rejects_keys_it_cannot_sign_with() {
+        let mut jwk: serde_json::Value = serde_json::from_str(TEST_JWK).unwrap();
+        jwk["kty"] = "EC".into();
+        assert!(ArweaveWallet::from_jwk(&jwk.to_string()).is_err());
+
+        let mut jwk: serde_json::Value = serde_json::from_str(TEST_JWK).unwrap();
+        jwk.as_object_mut().unwrap().remove("qi");
+        let error = ArweaveWallet::from_jwk(&jwk.to_string()).unwrap_err();
+        assert!(error.message.contains("'qi'"), "{}", error.message);
+    }
+}
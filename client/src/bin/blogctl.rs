@@ -0,0 +1,811 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use solana_blog_client::{
+    storage::{content_type_for_path, ContentId},
+    importers,
+    sync::SyncedPost,
+    ArweaveClient, UploadProgress, BlogArchive, BlogClient, BlogInfo, CreatePostRequest, LocalStore, PostInfo, SendResult,
+    ExternalImportOptions, FeedFormat, FeedOptions, SyncOptions,
+};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair},
+};
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+/// Program id of the devnet deployment
+const DEFAULT_PROGRAM_ID: &str = "BmqUqrFGJA7C9nzv787SrC59PUs2kSUvgdhDru6NQFmQ";
+const DEFAULT_RPC_URL: &str = "https://api.devnet.solana.com";
+
+#[derive(Parser)]
+#[command(name = "blogctl", version, about = "Manage Solana blogs and posts")]
+struct Cli {
+    /// RPC URL; defaults to the Solana CLI config, then devnet
+    #[arg(long, short = 'u', global = true, env = "BLOG_RPC_URL")]
+    url: Option<String>,
+
+    #[arg(long, global = true, env = "BLOG_PROGRAM_ID", default_value = DEFAULT_PROGRAM_ID)]
+    program_id: String,
+
+    /// Signing keypair; defaults to the one in the Solana CLI config
+    #[arg(long, short = 'k', global = true)]
+    keypair: Option<PathBuf>,
+
+    /// Solana CLI config file
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Arweave JWK wallet to pay for uploads instead of bundling them
+    #[arg(long, global = true)]
+    arweave_wallet: Option<PathBuf>,
+
+    /// Store content in this directory instead of on Arweave
+    #[arg(long, global = true)]
+    local_store: Option<PathBuf>,
+
+    #[arg(long, short = 'o', global = true, value_enum, default_value_t = Output::Human)]
+    output: Output,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Output {
+    Human,
+    Json,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create, inspect and edit blogs
+    #[command(subcommand)]
+    Blog(BlogCommand),
+    /// Create, inspect, edit and delete posts
+    #[command(subcommand)]
+    Post(PostCommand),
+    /// Upload a file to the content store and print its id
+    Upload {
+        file: PathBuf,
+        /// Guessed from the file extension when omitted
+        #[arg(long)]
+        content_type: Option<String>,
+    },
+    /// Publish a directory of markdown files with frontmatter, republishing
+    /// only files changed since the last sync
+    Sync {
+        blog: Pubkey,
+        dir: PathBuf,
+        /// Delete posts whose files were removed
+        #[arg(long)]
+        prune: bool,
+        /// Show what would change without publishing
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Import posts from a WordPress (.xml), Ghost (.json) or Medium (.zip)
+    /// export. Created posts are recorded in `<name>.imported.json` next to
+    /// the export and skipped when it is imported again
+    ImportPosts {
+        blog: Pubkey,
+        file: PathBuf,
+        /// Export format; guessed from the file extension when omitted
+        #[arg(long, value_enum)]
+        from: Option<Platform>,
+        /// Site URL for resolving relative image links
+        #[arg(long)]
+        base_url: Option<String>,
+        #[arg(long)]
+        include_drafts: bool,
+        /// Show what would be created without publishing
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Archive a blog with all of its posts, bodies and media
+    Export {
+        blog: Pubkey,
+        /// Write to a file instead of stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Recreate an exported blog on the cluster given by `--url`, owned by
+    /// the signing keypair. Progress is kept next to the archive in
+    /// `<name>.import.json`; run again with it to finish an interrupted
+    /// import
+    Import {
+        archive: PathBuf,
+    },
+    /// Render a blog as a static site: an index, a page per post, tag
+    /// pages, feeds and a sitemap
+    BuildSite {
+        blog: Pubkey,
+        /// Public URL the site is served from
+        #[arg(long)]
+        site_url: String,
+        /// Site directory; an app shell already at `index.html` is moved to
+        /// `app/index.html`
+        #[arg(long, default_value = "docs")]
+        dir: PathBuf,
+    },
+    /// Write RSS, Atom and JSON feeds for a blog into the static site
+    Feed {
+        blog: Pubkey,
+        /// Site directory; feeds are written to `<DIR>/feeds/<BLOG>/`
+        #[arg(long, default_value = "docs")]
+        dir: PathBuf,
+        /// Public URL of the site, for the feeds' links
+        #[arg(long)]
+        site_url: String,
+        /// Newest posts to include
+        #[arg(long, default_value_t = solana_blog_client::feed::DEFAULT_FEED_LIMIT)]
+        limit: usize,
+        /// Also upload each feed to the content store
+        #[arg(long)]
+        upload: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Platform {
+    Wordpress,
+    Ghost,
+    Medium,
+}
+
+#[derive(Subcommand)]
+enum BlogCommand {
+    /// Create a new blog owned by the signing keypair
+    Init {
+        #[arg(long)]
+        title: String,
+        #[arg(long, default_value = "")]
+        description: String,
+    },
+    Show {
+        blog: Pubkey,
+    },
+    Update {
+        blog: Pubkey,
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(long)]
+        description: Option<String>,
+    },
+}
+
+#[derive(Args)]
+struct PostBody {
+    /// Post body, or `-` to read it from stdin
+    #[arg(long, conflicts_with = "file")]
+    content: Option<String>,
+    /// Read the post body from a file
+    #[arg(long)]
+    file: Option<PathBuf>,
+    /// Attach an image
+    #[arg(long)]
+    image: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum PostCommand {
+    Create {
+        blog: Pubkey,
+        #[arg(long)]
+        title: String,
+        #[command(flatten)]
+        body: PostBody,
+    },
+    Edit {
+        post: Pubkey,
+        #[arg(long)]
+        title: Option<String>,
+        #[command(flatten)]
+        body: PostBody,
+    },
+    Delete {
+        post: Pubkey,
+    },
+    List {
+        blog: Pubkey,
+    },
+    Show {
+        post: Pubkey,
+        /// Also fetch the post body from the content store
+        #[arg(long)]
+        body: bool,
+    },
+}
+
+/// The parts of `~/.config/solana/cli/config.yml` blogctl uses
+#[derive(Default, Deserialize)]
+struct SolanaConfig {
+    json_rpc_url: Option<String>,
+    keypair_path: Option<String>,
+}
+
+impl SolanaConfig {
+    fn default_path() -> Option<PathBuf> {
+        std::env::var_os("HOME")
+            .map(|home| Path::new(&home).join(".config/solana/cli/config.yml"))
+    }
+
+    fn load(path: Option<&Path>) -> Result<Self, String> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match Self::default_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(Self::default()),
+            },
+        };
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_yaml::from_str(&text).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+}
+
+/// Where interrupted wallet uploads are checkpointed, so running the same
+/// command again continues them
+fn upload_state_dir() -> PathBuf {
+    match std::env::var_os("HOME") {
+        Some(home) => Path::new(&home).join(".cache/blogctl/uploads"),
+        None => std::env::temp_dir().join("blogctl-uploads"),
+    }
+}
+
+struct Context {
+    client: BlogClient,
+    keypair_path: Option<PathBuf>,
+    /// Uploads are paid by `--arweave-wallet` rather than bundled under
+    /// the keypair
+    arweave_wallet: bool,
+    output: Output,
+}
+
+impl Context {
+    fn new(cli: &Cli) -> Result<Self, String> {
+        let config = SolanaConfig::load(cli.config.as_deref())?;
+        let url = cli
+            .url
+            .clone()
+            .or(config.json_rpc_url)
+            .unwrap_or_else(|| DEFAULT_RPC_URL.to_string());
+
+        let mut client = BlogClient::new(&url, &cli.program_id).map_err(|e| e.message)?;
+        if let Some(dir) = &cli.local_store {
+            client = client.with_content_store(LocalStore::new(dir));
+        } else if let Some(wallet) = &cli.arweave_wallet {
+            let jwk = std::fs::read_to_string(wallet)
+                .map_err(|e| format!("Failed to read {}: {}", wallet.display(), e))?;
+            let arweave = ArweaveClient::new()
+                .with_wallet_key(&jwk)
+                .map_err(|e| e.message)?
+                .with_resume_dir(upload_state_dir())
+                .with_progress(|progress: UploadProgress| {
+                    eprintln!(
+                        "uploaded {} of {} bytes ({}/{} chunks)",
+                        progress.bytes_uploaded, progress.total_bytes, progress.chunks_uploaded, progress.total_chunks
+                    )
+                });
+            client = client.with_arweave_client(arweave);
+        }
+
+        Ok(Self {
+            client,
+            keypair_path: cli.keypair.clone().or(config.keypair_path.map(PathBuf::from)),
+            arweave_wallet: cli.local_store.is_none() && cli.arweave_wallet.is_some(),
+            output: cli.output,
+        })
+    }
+
+    fn keypair(&self) -> Result<Keypair, String> {
+        let path = self
+            .keypair_path
+            .as_ref()
+            .ok_or("No keypair given and none set in the Solana CLI config")?;
+        read_keypair_file(path).map_err(|e| format!("Failed to read keypair {}: {}", path.display(), e))
+    }
+
+    /// Store `data`, signed by the Arweave wallet when one was given and
+    /// by the keypair otherwise
+    async fn put(&self, data: &[u8], content_type: &str) -> Result<ContentId, String> {
+        let store = self.client.content_store();
+        let id = if self.arweave_wallet {
+            store.put(data, content_type, &[]).await
+        } else {
+            store.put_signed(data, content_type, &[], &self.keypair()?).await
+        };
+        id.map_err(|e| e.message)
+    }
+
+        fn print(&self, value: Value, human: impl FnOnce() -> String) {
+        match self.output {
+            Output::Json => println!("{}", serde_json::to_string_pretty(&value).unwrap_or_default()),
+            Output::Human => println!("{}", human()),
+        }
+    }
+
+    fn print_sent(&self, action: &str, result: &SendResult, extra: Value) {
+        let mut value = send_json(result);
+        if let (Value::Object(value), Value::Object(extra)) = (&mut value, extra) {
+            value.extend(extra);
+        }
+        self.print(value, || {
+            format!(
+                "{}\nsignature: {}\nslot: {}\nfee: {} lamports",
+                action, result.signature, result.slot, result.fee_lamports
+            )
+        });
+    }
+}
+
+fn send_json(result: &SendResult) -> Value {
+    json!({
+        "signature": result.signature.to_string(),
+        "slot": result.slot,
+        "fee_lamports": result.fee_lamports,
+        "priority_fee_lamports": result.priority_fee_lamports,
+        "attempts": result.attempts,
+    })
+}
+
+fn blog_json(blog: &BlogInfo) -> Value {
+    json!({
+        "pubkey": blog.pubkey.to_string(),
+        "authority": blog.authority.to_string(),
+        "title": blog.title,
+        "description": blog.description,
+        "post_count": blog.post_count,
+        "created_at": blog.created_at,
+    })
+}
+
+fn post_json(post: &PostInfo) -> Value {
+    json!({
+        "pubkey": post.pubkey.to_string(),
+        "blog": post.blog.to_string(),
+        "author": post.author.to_string(),
+        "title": post.title,
+        "content": post.content,
+        "arweave_hash": post.arweave_hash,
+        "created_at": post.created_at,
+        "updated_at": post.updated_at,
+    })
+}
+
+fn blog_human(blog: &BlogInfo) -> String {
+    format!(
+        "blog: {}\nauthority: {}\ntitle: {}\ndescription: {}\nposts: {}\ncreated: {}",
+        blog.pubkey, blog.authority, blog.title, blog.description, blog.post_count, blog.created_at
+    )
+}
+
+fn post_human(post: &PostInfo) -> String {
+    format!(
+        "post: {}\nblog: {}\nauthor: {}\ntitle: {}\ncontent id: {}\ncreated: {}\nupdated: {}\n\n{}",
+        post.pubkey,
+        post.blog,
+        post.author,
+        post.title,
+        post.arweave_hash,
+        post.created_at,
+        post.updated_at,
+        post.content
+    )
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+impl PostBody {
+    fn content(&self) -> Result<Option<String>, String> {
+        match (&self.content, &self.file) {
+            (Some(content), _) if content == "-" => {
+                let mut content = String::new();
+                std::io::Read::read_to_string(&mut std::io::stdin(), &mut content)
+                    .map_err(|e| format!("Failed to read stdin: {}", e))?;
+                Ok(Some(content))
+            }
+            (Some(content), _) => Ok(Some(content.clone())),
+            (None, Some(path)) => String::from_utf8(read_file(path)?)
+                .map(Some)
+                .map_err(|e| format!("{} is not UTF-8: {}", path.display(), e)),
+            (None, None) => Ok(None),
+        }
+    }
+
+    fn image(&self) -> Result<Option<(Vec<u8>, String)>, String> {
+        match &self.image {
+            Some(path) => Ok(Some((read_file(path)?, content_type_for_path(path).to_string()))),
+            None => Ok(None),
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), String> {
+    let ctx = Context::new(&cli)?;
+    let client = &ctx.client;
+
+    match cli.command {
+        Command::Blog(BlogCommand::Init { title, description }) => {
+            let authority = ctx.keypair()?;
+            let (blog, result) = client
+                .initialize_blog(&authority, title, description)
+                .await
+                .map_err(|e| e.message)?;
+            ctx.print_sent(&format!("created blog {}", blog), &result, json!({ "blog": blog.to_string() }));
+        }
+        Command::Blog(BlogCommand::Show { blog }) => {
+            let blog = client.get_blog(blog).await.map_err(|e| e.message)?;
+            ctx.print(blog_json(&blog), || blog_human(&blog));
+        }
+        Command::Blog(BlogCommand::Update { blog, title, description }) => {
+            if title.is_none() && description.is_none() {
+                return Err("Nothing to update: pass --title and/or --description".to_string());
+            }
+            let authority = ctx.keypair()?;
+            let result = client
+                .update_blog(&authority, blog, title, description)
+                .await
+                .map_err(|e| e.message)?;
+            ctx.print_sent(&format!("updated blog {}", blog), &result, json!({ "blog": blog.to_string() }));
+        }
+        Command::Post(PostCommand::Create { blog, title, body }) => {
+            let content = body
+                .content()?
+                .ok_or("A post needs a body: pass --content or --file")?;
+            let image = body.image()?;
+            let author = ctx.keypair()?;
+            let request = CreatePostRequest {
+                title,
+                content,
+                image_content_type: image.as_ref().map(|(_, content_type)| content_type.clone()),
+                image_data: image.map(|(data, _)| data),
+            };
+            let (post, result) = client
+                .create_post(&author, blog, request)
+                .await
+                .map_err(|e| e.message)?;
+            ctx.print_sent(&format!("created post {}", post), &result, json!({ "post": post.to_string() }));
+        }
+        Command::Post(PostCommand::Edit { post, title, body }) => {
+            let content = body.content()?;
+            let image = body.image()?;
+            if title.is_none() && content.is_none() && image.is_none() {
+                return Err("Nothing to update: pass --title, --content, --file or --image".to_string());
+            }
+            let author = ctx.keypair()?;
+            let result = client
+                .update_post(&author, post, title, content, image)
+                .await
+                .map_err(|e| e.message)?;
+            ctx.print_sent(&format!("updated post {}", post), &result, json!({ "post": post.to_string() }));
+        }
+        Command::Post(PostCommand::Delete { post }) => {
+            let author = ctx.keypair()?;
+            let result = client.delete_post(&author, post).await.map_err(|e| e.message)?;
+            ctx.print_sent(&format!("deleted post {}", post), &result, json!({ "post": post.to_string() }));
+        }
+        Command::Post(PostCommand::List { blog }) => {
+            let posts = client.get_posts_by_blog(blog).await.map_err(|e| e.message)?;
+            ctx.print(Value::Array(posts.iter().map(post_json).collect()), || {
+                posts
+                    .iter()
+                    .map(|post| format!("{}  {}  {}", post.pubkey, post.created_at, post.title))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            });
+        }
+        Command::Post(PostCommand::Show { post, body }) => {
+            let post = client.get_post(post).await.map_err(|e| e.message)?;
+            let body = if body {
+                let (body, _) = client
+                    .get_post_content(&post.arweave_hash)
+                    .await
+                    .map_err(|e| e.message)?;
+                body.map(|body| String::from_utf8_lossy(&body).into_owned())
+            } else {
+                None
+            };
+
+            let mut value = post_json(&post);
+            value["body"] = json!(body);
+            ctx.print(value, || match &body {
+                Some(body) => format!("{}\n\n---\n{}", post_human(&post), body),
+                None => post_human(&post),
+            });
+        }
+        Command::Upload { file, content_type } => {
+            let data = read_file(&file)?;
+            let content_type = content_type.unwrap_or_else(|| content_type_for_path(&file).to_string());
+            let id = ctx.put(&data, &content_type).await?;
+            let url = client.content_store().url(&id);
+            ctx.print(
+                json!({ "id": id.as_str(), "url": url, "bytes": data.len(), "content_type": content_type }),
+                || format!("id: {}\nurl: {}", id, url),
+            );
+        }
+        Command::Sync { blog, dir, prune, dry_run } => {
+            let author = ctx.keypair()?;
+            let options = SyncOptions { prune, dry_run };
+            let report = client
+                .sync_directory(&author, blog, &dir, &options)
+                .await
+                .map_err(|e| e.message)?;
+
+            let synced = |posts: &[SyncedPost]| -> Value {
+                posts
+                    .iter()
+                    .map(|synced| {
+                        json!({
+                            "path": synced.path,
+                            "post": synced.post.map(|post| post.to_string()),
+                            "content_id": synced.content_id.as_ref().map(|id| id.as_str()),
+                            "signature": synced.result.as_ref().map(|result| result.signature.to_string()),
+                        })
+                    })
+                    .collect()
+            };
+            ctx.print(
+                json!({
+                    "dry_run": dry_run,
+                    "created": synced(&report.created),
+                    "updated": synced(&report.updated),
+                    "deleted": synced(&report.deleted),
+                    "unchanged": report.unchanged,
+                    "skipped": report.skipped,
+                    "missing": report.missing,
+                }),
+                || {
+                    let mut lines = Vec::new();
+                    for (label, posts) in [
+                        ("created", &report.created),
+                        ("updated", &report.updated),
+                        ("deleted", &report.deleted),
+                    ] {
+                        for synced in posts {
+                            let post = synced.post.map(|post| post.to_string()).unwrap_or_default();
+                            lines.push(format!("{:<9} {}  {}", label, synced.path, post));
+                        }
+                    }
+                    for path in &report.missing {
+                        lines.push(format!("{:<9} {}  (pass --prune to delete its post)", "missing", path));
+                    }
+                    lines.push(format!(
+                        "{}{} created, {} updated, {} deleted, {} unchanged, {} skipped",
+                        if dry_run { "dry run: " } else { "" },
+                        report.created.len(),
+                        report.updated.len(),
+                        report.deleted.len(),
+                        report.unchanged.len(),
+                        report.skipped.len()
+                    ));
+                    lines.join("\n")
+                },
+            );
+        }
+        Command::ImportPosts { blog, file, from, base_url, include_drafts, dry_run } => {
+            let platform = match from {
+                Some(platform) => platform,
+                None => match file.extension().and_then(|extension| extension.to_str()) {
+                    Some("xml") => Platform::Wordpress,
+                    Some("json") => Platform::Ghost,
+                    Some("zip") => Platform::Medium,
+                    _ => return Err("Cannot tell the export format; pass --from".to_string()),
+                },
+            };
+            let data = read_file(&file)?;
+            let posts = match platform {
+                Platform::Wordpress => {
+                    let xml = String::from_utf8(data).map_err(|e| format!("{} is not UTF-8: {}", file.display(), e))?;
+                    importers::wordpress::parse(&xml)
+                }
+                Platform::Ghost => importers::ghost::parse(&data),
+                Platform::Medium => importers::medium::parse(&data),
+            }
+            .map_err(|e| e.message)?;
+
+            let author = ctx.keypair()?;
+            let options = ExternalImportOptions {
+                dry_run,
+                include_drafts,
+                base_url,
+                record: Some(file.with_extension("imported.json")),
+            };
+            let report = client
+                .import_external_posts(&author, blog, posts, &options)
+                .await
+                .map_err(|e| e.message)?;
+
+            let created: Vec<Value> = report
+                .created
+                .iter()
+                .map(|post| {
+                    json!({
+                        "source_id": post.source_id,
+                        "title": post.title,
+                        "published_at": post.published_at,
+                        "post": post.post.map(|post| post.to_string()),
+                        "bytes": post.bytes,
+                        "images": post.images,
+                        "failed_images": post.failed_images,
+                    })
+                })
+                .collect();
+            let skipped: Vec<Value> = report
+                .skipped
+                .iter()
+                .map(|(source_id, reason)| json!({ "source_id": source_id, "reason": reason }))
+                .collect();
+            ctx.print(json!({ "dry_run": dry_run, "created": created, "skipped": skipped }), || {
+                let mut lines = Vec::new();
+                for post in &report.created {
+                    lines.push(format!(
+                        "{} {}  {}  ({} bytes, {} images{})",
+                        if dry_run { "would create" } else { "created" },
+                        post.post.map(|post| post.to_string()).unwrap_or_else(|| post.source_id.clone()),
+                        post.title,
+                        post.bytes,
+                        post.images.len(),
+                        if post.failed_images.is_empty() {
+                            String::new()
+                        } else {
+                            format!(", {} left remote", post.failed_images.len())
+                        }
+                    ));
+                }
+                for (source_id, reason) in &report.skipped {
+                    lines.push(format!("skipped {}: {}", source_id, reason));
+                }
+                lines.push(format!("{} posts, {} skipped", report.created.len(), report.skipped.len()));
+                lines.join("\n")
+            });
+        }
+        Command::Export { blog, out } => {
+            let archive = client.export_blog(blog).await.map_err(|e| e.message)?;
+            match out {
+                Some(path) => {
+                    archive.save(&path).map_err(|e| e.message)?;
+                    if ctx.output == Output::Human {
+                        eprintln!("exported {} posts to {}", archive.posts.len(), path.display());
+                    }
+                }
+                None => {
+                    let data = archive.to_bytes().map_err(|e| e.message)?;
+                    println!("{}", String::from_utf8_lossy(&data));
+                }
+            }
+        }
+        Command::BuildSite { blog, site_url, dir } => {
+            let site = client.build_site(blog, &site_url).await.map_err(|e| e.message)?;
+            let report = site.write(&dir).map_err(|e| e.message)?;
+            let paths = |paths: &[PathBuf]| -> Vec<String> {
+                paths.iter().map(|path| path.display().to_string()).collect()
+            };
+            ctx.print(
+                json!({
+                    "posts": site.posts.len(),
+                    "written": paths(&report.written),
+                    "removed": paths(&report.removed),
+                    "moved_app": report.moved_app,
+                }),
+                || {
+                    let mut lines = vec![format!(
+                        "wrote {} files for {} posts to {}",
+                        report.written.len(),
+                        site.posts.len(),
+                        dir.display()
+                    )];
+                    for path in &report.removed {
+                        lines.push(format!("removed {}", path.display()));
+                    }
+                    if report.moved_app {
+                        lines.push(format!("moved the app shell to {}", dir.join("app/index.html").display()));
+                    }
+                    lines.join("\n")
+                },
+            );
+        }
+        Command::Feed {
+            blog,
+            dir,
+            site_url,
+            limit,
+            upload,
+        } => {
+            let site_url = site_url.trim_end_matches('/').to_string();
+            let options = FeedOptions {
+                feed_url: Some(format!("{}/feeds/{}", site_url, blog)),
+                site_url: Some(site_url),
+                limit,
+            };
+            let feed = client.blog_feed(blog, options).await.map_err(|e| e.message)?;
+
+            let feed_dir = dir.join("feeds").join(blog.to_string());
+            std::fs::create_dir_all(&feed_dir)
+                .map_err(|e| format!("Failed to create {}: {}", feed_dir.display(), e))?;
+
+            let mut written = Vec::new();
+            for format in FeedFormat::ALL {
+                let document = feed.render(format);
+                let path = feed_dir.join(format.file_name());
+                std::fs::write(&path, &document)
+                    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+                let url = if upload {
+                    let id = ctx.put(document.as_bytes(), format.content_type()).await?;
+                    Some(client.content_store().url(&id))
+                } else {
+                    None
+                };
+                written.push((path, url));
+            }
+
+            ctx.print(
+                json!({
+                    "entries": feed.entries.len(),
+                    "feeds": written
+                        .iter()
+                        .map(|(path, url)| json!({ "path": path.display().to_string(), "url": url }))
+                        .collect::<Vec<_>>(),
+                }),
+                || {
+                    let mut lines = vec![format!("{} entries", feed.entries.len())];
+                    for (path, url) in &written {
+                        match url {
+                            Some(url) => lines.push(format!("{} → {}", path.display(), url)),
+                            None => lines.push(path.display().to_string()),
+                        }
+                    }
+                    lines.join("\n")
+                },
+            );
+        }
+        Command::Import { archive: archive_path } => {
+            let archive = BlogArchive::load(&archive_path).map_err(|e| e.message)?;
+            let authority = ctx.keypair()?;
+            let progress = archive_path.with_extension("import.json");
+            let report = client
+                .import_blog(&authority, &archive, &progress)
+                .await
+                .map_err(|e| e.message)?;
+
+            let posts: serde_json::Map<String, Value> = report
+                .posts
+                .iter()
+                .map(|(source, post)| (source.clone(), json!(post.to_string())))
+                .collect();
+            ctx.print(
+                json!({ "source_blog": archive.blog.pubkey, "blog": report.blog.to_string(), "posts": posts }),
+                || {
+                    let mut lines = vec![format!("blog: {} -> {}", archive.blog.pubkey, report.blog)];
+                    lines.extend(
+                        report
+                            .posts
+                            .iter()
+                            .map(|(source, post)| format!("post: {} -> {}", source, post)),
+                    );
+                    lines.join("\n")
+                },
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
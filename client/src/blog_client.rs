@@ -1,28 +1,61 @@
 use crate::{
-    arweave::ArweaveClient,
-    types::{BlogClientError, BlogInfo, CreatePostRequest, PostInfo, Result},
+    arweave::{
+        manifest::{PathManifest, MANIFEST_CONTENT_TYPE},
+        ArweaveClient, ArweaveTag,
+    },
+    media::{self, EncodedImage, MediaAsset, MediaOptions, MediaVariant, PostManifest},
+    storage::{ArweaveStore, ContentId, ContentStore, StorageCost},
+    send::{self, SendPolicy, SendResult},
+    subscribe,
+    simulate::{self, CostBreakdown, SimulationReport},
+    types::{BlogClientError, BlogInfo, CreatePostRequest, PostAttachment, PostInfo, Result},
 };
 use borsh::BorshDeserialize;
 use solana_blog_program::{
     instruction::BlogInstruction,
-    state::{Blog, BlogPost},
+    state::{Blog, BlogPost, ProgramAccount},
+};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
 };
-use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
     signature::{Keypair, Signer},
     system_program,
-    transaction::Transaction,
 };
-use std::str::FromStr;
+use std::{str::FromStr, sync::Arc};
+
+/// Length of an Arweave transaction id, used as a stand-in for the content
+/// id when simulating before anything has been uploaded
+const PLACEHOLDER_CONTENT_ID_LENGTH: usize = 43;
+
+/// Paths inside a post's manifest. Image variants live under `images/`.
+pub const POST_INDEX_PATH: &str = "index.md";
+pub const POST_MEDIA_PATH: &str = "data.json";
+pub(crate) const POST_IMAGES_DIR: &str = "images/";
+
+/// Manifest path for an image variant, e.g. `images/960w.jpg`
+fn image_path(width: u32, content_type: &str) -> String {
+    let extension = match content_type {
+        "image/jpeg" => "jpg",
+        other => other.strip_prefix("image/").unwrap_or("bin"),
+    };
+    format!("{}{}w.{}", POST_IMAGES_DIR, width, extension)
+}
 
 pub struct BlogClient {
-    rpc_client: RpcClient,
-    arweave_client: ArweaveClient,
+    pub(crate) rpc_client: RpcClient,
+    content_store: Arc<dyn ContentStore>,
     program_id: Pubkey,
+    ws_url: Option<String>,
     payer: Option<Keypair>,
+    send_policy: SendPolicy,
+    media_options: MediaOptions,
 }
 
 impl BlogClient {
@@ -34,9 +67,12 @@ impl BlogClient {
 
         Ok(Self {
             rpc_client: RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed()),
-            arweave_client: ArweaveClient::new(),
+            content_store: Arc::new(ArweaveStore::new(ArweaveClient::new())),
             program_id,
+            ws_url: None,
             payer: None,
+            send_policy: SendPolicy::default(),
+            media_options: MediaOptions::default(),
         })
     }
 
@@ -45,23 +81,281 @@ impl BlogClient {
         self
     }
 
-    pub fn with_arweave_client(mut self, arweave_client: ArweaveClient) -> Self {
-        self.arweave_client = arweave_client;
+    /// Store content on Arweave through `arweave_client`
+    pub fn with_arweave_client(self, arweave_client: ArweaveClient) -> Self {
+        self.with_content_store(ArweaveStore::new(arweave_client))
+    }
+
+    /// Store post bodies and media in `content_store` instead of Arweave
+    pub fn with_content_store(mut self, content_store: impl ContentStore + 'static) -> Self {
+        self.content_store = Arc::new(content_store);
         self
     }
 
-    pub async fn initialize_blog(
+    pub fn content_store(&self) -> &dyn ContentStore {
+        self.content_store.as_ref()
+    }
+
+    pub fn with_send_policy(mut self, send_policy: SendPolicy) -> Self {
+        self.send_policy = send_policy;
+        self
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    pub fn rpc_url(&self) -> String {
+        self.rpc_client.url()
+    }
+
+    /// Websocket endpoint for subscriptions, when it cannot be derived
+    /// from the RPC URL
+    pub fn with_ws_url(mut self, ws_url: &str) -> Self {
+        self.ws_url = Some(ws_url.to_string());
+        self
+    }
+
+    pub fn ws_url(&self) -> String {
+        self.ws_url
+            .clone()
+            .unwrap_or_else(|| subscribe::derive_ws_url(&self.rpc_client.url()))
+    }
+
+    pub fn send_policy(&self) -> &SendPolicy {
+        &self.send_policy
+    }
+
+    /// Sizes and formats produced for attached images
+    pub fn with_media_options(mut self, media_options: MediaOptions) -> Self {
+        self.media_options = media_options;
+        self
+    }
+
+    /// Strip metadata from an image, upload it at every configured size and
+    /// format, and describe the uploads for a post manifest
+    pub async fn upload_image(
         &self,
-        authority: &Keypair,
-        title: String,
-        description: String,
-    ) -> Result<Pubkey> {
-        let blog_keypair = Keypair::new();
-        let blog_pubkey = blog_keypair.pubkey();
+        author: &Keypair,
+        data: &[u8],
+        alt: &str,
+        tags: &[ArweaveTag],
+    ) -> Result<MediaAsset> {
+        let encoded = media::process_image_async(data, &self.media_options).await?;
+        // Full-size copies are encoded first
+        let (width, height) = match encoded.first() {
+            Some(full_size) => (full_size.width, full_size.height),
+            None => {
+                return Err(BlogClientError {
+                    message: "Image processing produced no copies to upload".to_string(),
+                })
+            }
+        };
+
+        let mut variants = Vec::with_capacity(encoded.len());
+        for image in encoded {
+            let id = self
+                .content_store
+                .put_signed(&image.data, &image.content_type, tags, author)
+                .await?;
+            variants.push(MediaVariant {
+                id,
+                width: image.width,
+                height: image.height,
+                content_type: image.content_type,
+                bytes: image.data.len(),
+            });
+        }
+
+        Ok(MediaAsset {
+            alt: alt.to_string(),
+            width,
+            height,
+            variants,
+        })
+    }
 
-        let instruction_data = BlogInstruction::InitializeBlog {
-            title: title.clone(),
-            description: description.clone(),
+    /// Upload a post's files and the path manifest grouping them, returning
+    /// the manifest id to store on-chain. `base` is the previous version's
+    /// manifest when updating; paths not replaced here carry over.
+    pub(crate) async fn upload_post_files(
+        &self,
+        author: &Keypair,
+        tags: &[ArweaveTag],
+        base: PathManifest,
+        content: Option<&str>,
+        image: Option<(&[u8], &str)>, // (data, alt)
+        attachments: &[PostAttachment],
+    ) -> Result<ContentId> {
+        let mut manifest = base.with_index(POST_INDEX_PATH);
+
+        if let Some(content) = content {
+            let id = self
+                .content_store
+                .put_signed(content.as_bytes(), "text/markdown", tags, author)
+                .await?;
+            manifest.insert(POST_INDEX_PATH, id.0)?;
+        }
+
+        // data.json names the body, so a carried-over one is rebuilt
+        // whenever the body changes
+        let media = match (image, manifest.get(POST_MEDIA_PATH).map(str::to_string)) {
+            (Some((image_data, alt)), _) => {
+                let asset = self.upload_image(author, image_data, alt, tags).await?;
+                Some(self.add_image_paths(&mut manifest, asset)?)
+            }
+            (None, Some(previous)) if content.is_some() => {
+                let data = self.content_store.get(&ContentId::from(previous.as_str())).await?;
+                let previous = PostManifest::from_bytes(&data).ok_or_else(|| BlogClientError {
+                    message: format!("{} {} is not a post manifest", POST_MEDIA_PATH, previous),
+                })?;
+                Some(PostManifest::new(
+                    manifest.get(POST_INDEX_PATH).map(ContentId::from),
+                    previous.media,
+                ))
+            }
+            (None, _) => None,
+        };
+
+        if let Some(media) = media {
+            let mut media_tags = tags.to_vec();
+            media_tags.push(ArweaveTag::new("Type", media::MANIFEST_TYPE));
+            let id = self
+                .content_store
+                .put_signed(&media.to_bytes()?, "application/json", &media_tags, author)
+                .await?;
+            manifest.insert(POST_MEDIA_PATH, id.0)?;
+        }
+
+        for attachment in attachments {
+            let id = self
+                .content_store
+                .put_signed(&attachment.data, &attachment.content_type, tags, author)
+                .await?;
+            manifest.insert(&attachment.path, id.0)?;
+        }
+
+        self.content_store
+            .put_signed(&manifest.to_bytes()?, MANIFEST_CONTENT_TYPE, tags, author)
+            .await
+    }
+
+    /// Replace the image paths in `manifest` with the variants of `asset`,
+    /// returning the media manifest for `data.json`
+    fn add_image_paths(&self, manifest: &mut PathManifest, asset: MediaAsset) -> Result<PostManifest> {
+        let stale: Vec<String> = manifest
+            .paths()
+            .filter(|(path, _)| path.starts_with(POST_IMAGES_DIR))
+            .map(|(path, _)| path.to_string())
+            .collect();
+        for path in stale {
+            manifest.remove(&path);
+        }
+        for variant in &asset.variants {
+            manifest.insert(&image_path(variant.width, &variant.content_type), variant.id.0.clone())?;
+        }
+        Ok(PostManifest::new(
+            manifest.get(POST_INDEX_PATH).map(ContentId::from),
+            vec![asset],
+        ))
+    }
+
+    /// Bytes `upload_post_files` would upload, including both manifests.
+    /// Manifests are sized with placeholder ids of Arweave length.
+    async fn estimate_post_upload_bytes(
+        &self,
+        content: Option<&str>,
+        image_data: Option<&[u8]>,
+        attachments: &[PostAttachment],
+    ) -> Result<usize> {
+        let placeholder = || "0".repeat(PLACEHOLDER_CONTENT_ID_LENGTH);
+        let mut manifest = PathManifest::new().with_index(POST_INDEX_PATH);
+        let mut bytes = 0;
+
+        if let Some(content) = content {
+            bytes += content.len();
+            manifest.insert(POST_INDEX_PATH, placeholder())?;
+        }
+        if let Some(image_data) = image_data {
+            let encoded = media::process_image_async(image_data, &self.media_options).await?;
+            bytes += encoded.iter().map(|image| image.data.len()).sum::<usize>();
+            let media = self.add_image_paths(&mut manifest, placeholder_asset(&encoded, placeholder()))?;
+            bytes += media.to_bytes()?.len();
+            manifest.insert(POST_MEDIA_PATH, placeholder())?;
+        }
+        for attachment in attachments {
+            bytes += attachment.data.len();
+            manifest.insert(&attachment.path, placeholder())?;
+        }
+
+        Ok(bytes + manifest.to_bytes()?.len())
+    }
+
+    /// Path manifest of the post content at `arweave_hash`, or an equivalent
+    /// one for posts stored before path manifests: a bare body becomes
+    /// `index.md`, and a media manifest becomes `index.md` plus `data.json`
+    pub async fn get_post_manifest(&self, arweave_hash: &str) -> Result<PathManifest> {
+        let data = self.content_store.get_raw(&ContentId::from(arweave_hash)).await?;
+        if let Some(manifest) = PathManifest::from_bytes(&data) {
+            return Ok(manifest);
+        }
+
+        let mut manifest = PathManifest::new().with_index(POST_INDEX_PATH);
+        match PostManifest::from_bytes(&data) {
+            Some(media) => {
+                if let Some(content) = &media.content {
+                    manifest.insert(POST_INDEX_PATH, content.0.clone())?;
+                }
+                for variant in media.media.iter().flat_map(|asset| &asset.variants) {
+                    manifest.insert(&image_path(variant.width, &variant.content_type), variant.id.0.clone())?;
+                }
+                manifest.insert(POST_MEDIA_PATH, arweave_hash)?;
+            }
+            None => manifest.insert(POST_INDEX_PATH, arweave_hash)?,
+        }
+        Ok(manifest)
+    }
+
+    /// Fetch the file at `path` in a post's manifest
+    pub async fn get_post_file(&self, arweave_hash: &str, path: &str) -> Result<Vec<u8>> {
+        let manifest = self.get_post_manifest(arweave_hash).await?;
+        let id = manifest.get(path).ok_or_else(|| BlogClientError {
+            message: format!("Post {} has no file at {}", arweave_hash, path),
+        })?;
+        self.content_store.get(&ContentId::from(id)).await
+    }
+
+    /// URL for a relative link (such as `images/960w.jpg`) found in the file
+    /// at `from` in `manifest`, or `None` if the manifest has no such path
+    pub fn resolve_post_link(&self, manifest: &PathManifest, from: &str, link: &str) -> Option<String> {
+        manifest
+            .resolve(from, link)
+            .map(|id| self.content_store.url(&ContentId::from(id)))
+    }
+
+    pub(crate) async fn send_instructions(
+        &self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+        signers: &[&Keypair],
+    ) -> Result<SendResult> {
+        send::send_with_policy(&self.rpc_client, &self.send_policy, instructions, payer, signers)
+            .await
+    }
+
+    pub(crate) fn create_post_instruction(
+        &self,
+        author: Pubkey,
+        post_pubkey: Pubkey,
+        blog_pubkey: Pubkey,
+        title: String,
+        content: String,
+        arweave_hash: String,
+    ) -> Result<Instruction> {
+        let instruction_data = BlogInstruction::CreatePost {
+            title,
+            content,
+            arweave_hash,
         };
 
         // Manual serialization to avoid borsh version mismatch
@@ -70,62 +364,123 @@ impl BlogClient {
                 message: format!("Failed to serialize instruction: {}", e),
             })?;
 
-        let instruction = Instruction::new(
+        Ok(Instruction::new_with_bytes(
             self.program_id,
             &serialized_data,
             vec![
-                AccountMeta::new(authority.pubkey(), true),
-                AccountMeta::new(blog_pubkey, true),
+                AccountMeta::new(author, true),
+                AccountMeta::new(post_pubkey, true),
+                AccountMeta::new(blog_pubkey, false),
                 AccountMeta::new_readonly(system_program::id(), false),
             ],
-        );
+        ))
+    }
 
-        let recent_blockhash = self
-            .rpc_client
-            .get_latest_blockhash()
+    pub(crate) fn update_post_instruction(
+        &self,
+        author: Pubkey,
+        post_pubkey: Pubkey,
+        title: Option<String>,
+        content: Option<String>,
+        arweave_hash: Option<String>,
+    ) -> Result<Instruction> {
+        let instruction_data = BlogInstruction::UpdatePost {
+            title,
+            content,
+            arweave_hash,
+        };
+
+        // Manual serialization to avoid borsh version mismatch
+        let serialized_data = borsh::to_vec(&instruction_data)
             .map_err(|e| BlogClientError {
-                message: format!("Failed to get recent blockhash: {}", e),
+                message: format!("Failed to serialize instruction: {}", e),
             })?;
 
-        let transaction = Transaction::new_signed_with_payer(
-            &[instruction],
-            Some(&authority.pubkey()),
-            &[authority, &blog_keypair],
-            recent_blockhash,
-        );
+        Ok(Instruction::new_with_bytes(
+            self.program_id,
+            &serialized_data,
+            vec![
+                AccountMeta::new(author, true),
+                AccountMeta::new(post_pubkey, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        ))
+    }
 
-        self.rpc_client
-            .send_and_confirm_transaction(&transaction)
+    fn update_blog_instruction(
+        &self,
+        authority: Pubkey,
+        blog_pubkey: Pubkey,
+        title: Option<String>,
+        description: Option<String>,
+    ) -> Result<Instruction> {
+        let instruction_data = BlogInstruction::UpdateBlog { title, description };
+
+        // Manual serialization to avoid borsh version mismatch
+        let serialized_data = borsh::to_vec(&instruction_data)
             .map_err(|e| BlogClientError {
-                message: format!("Failed to send transaction: {}", e),
+                message: format!("Failed to serialize instruction: {}", e),
             })?;
 
-        Ok(blog_pubkey)
+        Ok(Instruction::new_with_bytes(
+            self.program_id,
+            &serialized_data,
+            vec![
+                AccountMeta::new(authority, true),
+                AccountMeta::new(blog_pubkey, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        ))
     }
 
-    pub async fn create_post(
+    fn delete_post_instruction(
         &self,
-        author: &Keypair,
+        author: Pubkey,
+        post_pubkey: Pubkey,
         blog_pubkey: Pubkey,
-        request: CreatePostRequest,
-    ) -> Result<Pubkey> {
-        // Upload image to Arweave if provided
-        let arweave_hash = if let Some(image_data) = &request.image_data {
-            let content_type = request.image_content_type.as_deref().unwrap_or("image/jpeg");
-            self.arweave_client.upload_data(image_data, content_type).await?
-        } else {
-            // Upload text content to Arweave as fallback
-            self.arweave_client.upload_text(&request.content).await?
-        };
+    ) -> Result<Instruction> {
+        // Manual serialization to avoid borsh version mismatch
+        let serialized_data = borsh::to_vec(&BlogInstruction::DeletePost)
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to serialize instruction: {}", e),
+            })?;
 
-        let post_keypair = Keypair::new();
-        let post_pubkey = post_keypair.pubkey();
+        Ok(Instruction::new_with_bytes(
+            self.program_id,
+            &serialized_data,
+            vec![
+                AccountMeta::new(author, true),
+                AccountMeta::new(post_pubkey, false),
+                AccountMeta::new(blog_pubkey, false),
+            ],
+        ))
+    }
 
-        let instruction_data = BlogInstruction::CreatePost {
-            title: request.title,
-            content: request.content,
-            arweave_hash,
-        };
+    pub async fn initialize_blog(
+        &self,
+        authority: &Keypair,
+        title: String,
+        description: String,
+    ) -> Result<(Pubkey, SendResult)> {
+        let blog_keypair = Keypair::new();
+        let send_result = self
+            .initialize_blog_account(authority, &blog_keypair, title, description)
+            .await?;
+        Ok((blog_keypair.pubkey(), send_result))
+    }
+
+    /// Initialize a blog at `blog_keypair`, for callers that need its
+    /// address before the transaction is sent
+    pub(crate) async fn initialize_blog_account(
+        &self,
+        authority: &Keypair,
+        blog_keypair: &Keypair,
+        title: String,
+        description: String,
+    ) -> Result<SendResult> {
+        let blog_pubkey = blog_keypair.pubkey();
+
+        let instruction_data = BlogInstruction::InitializeBlog { title, description };
 
         // Manual serialization to avoid borsh version mismatch
         let serialized_data = borsh::to_vec(&instruction_data)
@@ -133,38 +488,82 @@ impl BlogClient {
                 message: format!("Failed to serialize instruction: {}", e),
             })?;
 
-        let instruction = Instruction::new(
+        let instruction = Instruction::new_with_bytes(
             self.program_id,
             &serialized_data,
             vec![
-                AccountMeta::new(author.pubkey(), true),
-                AccountMeta::new(post_pubkey, true),
-                AccountMeta::new(blog_pubkey, false),
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new(blog_pubkey, true),
                 AccountMeta::new_readonly(system_program::id(), false),
             ],
         );
 
-        let recent_blockhash = self
-            .rpc_client
-            .get_latest_blockhash()
-            .map_err(|e| BlogClientError {
-                message: format!("Failed to get recent blockhash: {}", e),
-            })?;
+        self.send_instructions(&[instruction], authority, &[authority, blog_keypair])
+            .await
+    }
 
-        let transaction = Transaction::new_signed_with_payer(
-            &[instruction],
-            Some(&author.pubkey()),
-            &[author, &post_keypair],
-            recent_blockhash,
-        );
+    pub async fn create_post(
+        &self,
+        author: &Keypair,
+        blog_pubkey: Pubkey,
+        request: CreatePostRequest,
+    ) -> Result<(Pubkey, SendResult)> {
+        self.create_post_with_attachments(author, blog_pubkey, request, &[])
+            .await
+    }
 
-        self.rpc_client
-            .send_and_confirm_transaction(&transaction)
-            .map_err(|e| BlogClientError {
-                message: format!("Failed to send transaction: {}", e),
-            })?;
+    /// Create a post whose content is a path manifest holding the body as
+    /// `index.md`, image variants under `images/` with `data.json`
+    /// describing them, and each attachment at its own path
+    pub async fn create_post_with_attachments(
+        &self,
+        author: &Keypair,
+        blog_pubkey: Pubkey,
+        request: CreatePostRequest,
+        attachments: &[PostAttachment],
+    ) -> Result<(Pubkey, SendResult)> {
+        // Bundled uploads are signed by the author, tagged so they can be
+        // found through Arweave GraphQL
+        let tags = [
+            ArweaveTag::new("Blog", blog_pubkey.to_string()),
+            ArweaveTag::new("Post-Title", request.title.clone()),
+        ];
+
+        if let (Some(image_data), Some(content_type)) = (&request.image_data, &request.image_content_type) {
+            media::check_content_type(image_data, content_type)?;
+        }
+        let image = request
+            .image_data
+            .as_deref()
+            .map(|image_data| (image_data, request.title.as_str()));
+        let arweave_hash = self
+            .upload_post_files(
+                author,
+                &tags,
+                PathManifest::new(),
+                Some(&request.content),
+                image,
+                attachments,
+            )
+            .await?;
+
+        let post_keypair = Keypair::new();
+        let post_pubkey = post_keypair.pubkey();
+
+        let instruction = self.create_post_instruction(
+            author.pubkey(),
+            post_pubkey,
+            blog_pubkey,
+            request.title,
+            request.content,
+            arweave_hash.0,
+        )?;
 
-        Ok(post_pubkey)
+        let send_result = self
+            .send_instructions(&[instruction], author, &[author, &post_keypair])
+            .await?;
+
+        Ok((post_pubkey, send_result))
     }
 
     pub async fn get_blog(&self, blog_pubkey: Pubkey) -> Result<BlogInfo> {
@@ -180,14 +579,7 @@ impl BlogClient {
                 message: format!("Failed to deserialize blog: {}", e),
             })?;
 
-        Ok(BlogInfo {
-            pubkey: blog_pubkey,
-            authority: blog.authority,
-            title: blog.title,
-            description: blog.description,
-            post_count: blog.post_count,
-            created_at: blog.created_at,
-        })
+        Ok(BlogInfo::new(blog_pubkey, blog))
     }
 
     pub async fn get_post(&self, post_pubkey: Pubkey) -> Result<PostInfo> {
@@ -203,43 +595,33 @@ impl BlogClient {
                 message: format!("Failed to deserialize post: {}", e),
             })?;
 
-        Ok(PostInfo {
-            pubkey: post_pubkey,
-            author: post.author,
-            blog: post.blog,
-            title: post.title,
-            content: post.content,
-            arweave_hash: post.arweave_hash,
-            created_at: post.created_at,
-            updated_at: post.updated_at,
-        })
+        Ok(PostInfo::new(post_pubkey, post))
     }
 
     pub async fn get_posts_by_blog(&self, blog_pubkey: Pubkey) -> Result<Vec<PostInfo>> {
-        // This is a simplified implementation. In a real app, you'd want to index posts
-        // or use a more efficient method to find all posts for a blog
+        // Let the node match the blog field rather than sending every account
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                BlogPost::BLOG_OFFSET,
+                blog_pubkey.as_ref(),
+            ))]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
         let program_accounts = self
             .rpc_client
-            .get_program_accounts(&self.program_id)
+            .get_program_accounts_with_config(&self.program_id, config)
             .map_err(|e| BlogClientError {
                 message: format!("Failed to get program accounts: {}", e),
             })?;
 
         let mut posts = Vec::new();
         for (pubkey, account) in program_accounts {
-            if let Ok(post) = BlogPost::try_from_slice(&account.data) {
-                if post.blog == blog_pubkey {
-                    posts.push(PostInfo {
-                        pubkey,
-                        author: post.author,
-                        blog: post.blog,
-                        title: post.title,
-                        content: post.content,
-                        arweave_hash: post.arweave_hash,
-                        created_at: post.created_at,
-                        updated_at: post.updated_at,
-                    });
-                }
+            if let Some(ProgramAccount::Post(post)) = ProgramAccount::decode(&account.data) {
+                posts.push(PostInfo::new(pubkey, post));
             }
         }
 
@@ -255,62 +637,352 @@ impl BlogClient {
         title: Option<String>,
         content: Option<String>,
         image_data: Option<(Vec<u8>, String)>, // (data, content_type)
-    ) -> Result<()> {
-        // Upload new image to Arweave if provided
-        let arweave_hash = if let Some((image_data, content_type)) = image_data {
-            Some(self.arweave_client.upload_data(&image_data, &content_type).await?)
-        } else if let Some(ref content) = content {
-            // Upload updated content to Arweave
-            Some(self.arweave_client.upload_text(content).await?)
+    ) -> Result<SendResult> {
+        let mut tags = vec![ArweaveTag::new("Post", post_pubkey.to_string())];
+        if let Some(title) = &title {
+            tags.push(ArweaveTag::new("Post-Title", title.clone()));
+        }
+
+        // New content or images produce a new version of the post's path
+        // manifest; files that were not replaced carry over
+        if let Some((image_data, content_type)) = &image_data {
+            media::check_content_type(image_data, content_type)?;
+        }
+        let arweave_hash = if content.is_some() || image_data.is_some() {
+            let current = self.get_post(post_pubkey).await?;
+            let base = self.get_post_manifest(&current.arweave_hash).await?;
+            let alt = title.clone().unwrap_or(current.title);
+            let image = image_data
+                .as_ref()
+                .map(|(image_data, _)| (image_data.as_slice(), alt.as_str()));
+            Some(
+                self.upload_post_files(author, &tags, base, content.as_deref(), image, &[])
+                    .await?
+                    .0,
+            )
         } else {
             None
         };
 
-        let instruction_data = BlogInstruction::UpdatePost {
-            title,
-            content,
-            arweave_hash,
-        };
+        let instruction =
+            self.update_post_instruction(author.pubkey(), post_pubkey, title, content, arweave_hash)?;
 
-        // Manual serialization to avoid borsh version mismatch
-        let serialized_data = borsh::to_vec(&instruction_data)
-            .map_err(|e| BlogClientError {
-                message: format!("Failed to serialize instruction: {}", e),
-            })?;
+        let send_result = self
+            .send_instructions(&[instruction], author, &[author])
+            .await?;
 
-        let instruction = Instruction::new(
-            self.program_id,
-            &serialized_data,
-            vec![
-                AccountMeta::new(author.pubkey(), true),
-                AccountMeta::new(post_pubkey, false),
-            ],
-        );
+        Ok(send_result)
+    }
+
+    /// Change a blog's title or description; the authority pays for any
+    /// extra rent and gets back any surplus
+    pub async fn update_blog(
+        &self,
+        authority: &Keypair,
+        blog_pubkey: Pubkey,
+        title: Option<String>,
+        description: Option<String>,
+    ) -> Result<SendResult> {
+        let instruction =
+            self.update_blog_instruction(authority.pubkey(), blog_pubkey, title, description)?;
+
+        self.send_instructions(&[instruction], authority, &[authority])
+            .await
+    }
+
+    /// Close a post account, refunding its rent to the author. Content
+    /// already stored on Arweave is permanent and stays where it is.
+    pub async fn delete_post(&self, author: &Keypair, post_pubkey: Pubkey) -> Result<SendResult> {
+        let post = self.get_post(post_pubkey).await?;
+        let instruction = self.delete_post_instruction(author.pubkey(), post_pubkey, post.blog)?;
+
+        self.send_instructions(&[instruction], author, &[author])
+            .await
+    }
 
-        let recent_blockhash = self
+    /// Dry-runs `create_post` without uploading anything, reporting program
+    /// logs, compute usage, the decoded failure reason and the full cost
+    pub async fn simulate_create_post(
+        &self,
+        author: &Keypair,
+        blog_pubkey: Pubkey,
+        request: &CreatePostRequest,
+    ) -> Result<SimulationReport> {
+        let arweave_bytes =
+            self.estimate_post_upload_bytes(Some(&request.content), request.image_data.as_deref(), &[])
+                .await?;
+        let storage_cost = self.content_store.storage_cost(arweave_bytes).await?;
+
+        // The content id is only known after upload; an Arweave-length
+        // placeholder keeps the simulated account size exact for Arweave
+        let arweave_hash = "0".repeat(PLACEHOLDER_CONTENT_ID_LENGTH);
+
+        let rent_lamports = self
             .rpc_client
-            .get_latest_blockhash()
+            .get_minimum_balance_for_rent_exemption(BlogPost::get_size(
+                &request.title,
+                &request.content,
+                &arweave_hash,
+            ))
             .map_err(|e| BlogClientError {
-                message: format!("Failed to get recent blockhash: {}", e),
+                message: format!("Failed to get rent exemption: {}", e),
             })?;
 
-        let transaction = Transaction::new_signed_with_payer(
+        let post_keypair = Keypair::new();
+        let instruction = self.create_post_instruction(
+            author.pubkey(),
+            post_keypair.pubkey(),
+            blog_pubkey,
+            request.title.clone(),
+            request.content.clone(),
+            arweave_hash,
+        )?;
+
+        simulate::simulate_instructions(
+            &self.rpc_client,
+            &self.program_id,
+            &self.send_policy,
             &[instruction],
-            Some(&author.pubkey()),
-            &[author],
-            recent_blockhash,
-        );
+            author,
+            &[author, &post_keypair],
+            CostBreakdown {
+                rent_lamports,
+                arweave_bytes,
+                arweave_winston: storage_cost.winston,
+                arweave_bundler_lamports: storage_cost.bundler_lamports,
+                ..CostBreakdown::default()
+            },
+        )
+    }
 
-        self.rpc_client
-            .send_and_confirm_transaction(&transaction)
-            .map_err(|e| BlogClientError {
-                message: format!("Failed to send transaction: {}", e),
-            })?;
+    /// Dry-runs `update_post` without uploading anything. Takes the same
+    /// arguments as `update_post`, by reference.
+    pub async fn simulate_update_post(
+        &self,
+        author: &Keypair,
+        post_pubkey: Pubkey,
+        title: Option<&str>,
+        content: Option<&str>,
+        image_data: Option<(&[u8], &str)>, // (data, content_type)
+    ) -> Result<SimulationReport> {
+        // Files carried over from the current manifest add a few bytes per
+        // path to the new manifest, which this leaves out
+        let arweave_bytes = if content.is_some() || image_data.is_some() {
+            Some(
+                self.estimate_post_upload_bytes(content, image_data.map(|(data, _)| data), &[])
+                    .await?,
+            )
+        } else {
+            None
+        };
+
+        let (arweave_hash, storage_cost) = match arweave_bytes {
+            Some(bytes) => (
+                Some("0".repeat(PLACEHOLDER_CONTENT_ID_LENGTH)),
+                self.content_store.storage_cost(bytes).await?,
+            ),
+            None => (None, StorageCost::default()),
+        };
+
+        let instruction = self.update_post_instruction(
+            author.pubkey(),
+            post_pubkey,
+            title.map(str::to_string),
+            content.map(str::to_string),
+            arweave_hash,
+        )?;
 
-        Ok(())
+        simulate::simulate_instructions(
+            &self.rpc_client,
+            &self.program_id,
+            &self.send_policy,
+            &[instruction],
+            author,
+            &[author],
+            CostBreakdown {
+                arweave_bytes: arweave_bytes.unwrap_or(0),
+                arweave_winston: storage_cost.winston,
+                arweave_bundler_lamports: storage_cost.bundler_lamports,
+                ..CostBreakdown::default()
+            },
+        )
     }
 
     pub fn get_arweave_url(&self, tx_id: &str) -> String {
-        self.arweave_client.get_url(tx_id)
+        self.content_store.url(&ContentId::from(tx_id))
+    }
+
+    /// Fetch a post body or media object by the id stored on-chain
+    pub async fn get_content(&self, content_id: &str) -> Result<Vec<u8>> {
+        self.content_store.get(&ContentId::from(content_id)).await
+    }
+
+    /// Resolve a post's `arweave_hash` to its body and media
+    pub async fn get_post_content(&self, arweave_hash: &str) -> Result<(Option<Vec<u8>>, Vec<MediaAsset>)> {
+        let manifest = self.get_post_manifest(arweave_hash).await?;
+        let body = match manifest.get(POST_INDEX_PATH) {
+            Some(id) => Some(self.content_store.get(&ContentId::from(id)).await?),
+            None => None,
+        };
+        let media = match manifest.get(POST_MEDIA_PATH) {
+            Some(id) => {
+                let data = self.content_store.get(&ContentId::from(id)).await?;
+                PostManifest::from_bytes(&data)
+                    .map(|media| media.media)
+                    .unwrap_or_default()
+            }
+            None => Vec::new(),
+        };
+        Ok((body, media))
+    }
+} 
+
+/// A `MediaAsset` for `encoded` images with every id set to `id`, for sizing
+/// manifests before upload
+fn placeholder_asset(encoded: &[EncodedImage], id: String) -> MediaAsset {
+    let variants: Vec<MediaVariant> = encoded
+        .iter()
+        .map(|image| MediaVariant {
+            id: ContentId(id.clone()),
+            width: image.width,
+            height: image.height,
+            content_type: image.content_type.clone(),
+            bytes: image.data.len(),
+        })
+        .collect();
+    MediaAsset {
+        alt: String::new(),
+        width: variants.first().map_or(0, |variant| variant.width),
+        height: variants.first().map_or(0, |variant| variant.height),
+        variants,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        arweave::mock_gateway::{GatewayState, MockGateway},
+        storage::LocalStore,
+    };
+    use image::{DynamicImage, ImageFormat, RgbImage};
+
+    const TEST_JWK: &str = include_str!("../testdata/arweave-wallet.json");
+
+    fn client(store: &tempfile::TempDir) -> BlogClient {
+        BlogClient::new("http://127.0.0.1:1", &solana_blog_program::id().to_string())
+            .unwrap()
+            .with_content_store(LocalStore::new(store.path()))
     }
-} 
\ No newline at end of file
+
+    fn png() -> Vec<u8> {
+        let mut data = std::io::Cursor::new(Vec::new());
+        DynamicImage::ImageRgb8(RgbImage::new(64, 48))
+            .write_to(&mut data, ImageFormat::Png)
+            .unwrap();
+        data.into_inner()
+    }
+
+    async fn media_manifest(client: &BlogClient, content_id: &ContentId) -> PostManifest {
+        let data = client.get_post_file(content_id.as_str(), POST_MEDIA_PATH).await.unwrap();
+        PostManifest::from_bytes(&data).unwrap()
+    }
+
+    #[tokio::test]
+    async fn posts_from_before_path_manifests_still_resolve() {
+        let store = tempfile::tempdir().unwrap();
+        let client = client(&store);
+
+        let body = client.content_store().put(b"Bare body", "text/markdown", &[]).await.unwrap();
+        let manifest = client.get_post_manifest(body.as_str()).await.unwrap();
+        assert_eq!(manifest.get(POST_INDEX_PATH), Some(body.as_str()));
+        assert_eq!(manifest.get(POST_MEDIA_PATH), None);
+        assert_eq!(client.get_post_file(body.as_str(), POST_INDEX_PATH).await.unwrap(), b"Bare body");
+
+        let variant = MediaVariant {
+            id: ContentId::from("variant"),
+            content_type: "image/webp".to_string(),
+            width: 480,
+            height: 320,
+            bytes: 100,
+        };
+        let asset = MediaAsset {
+            alt: "Alt".to_string(),
+            width: 480,
+            height: 320,
+            variants: vec![variant],
+        };
+        let media = PostManifest::new(Some(body.clone()), vec![asset]).to_bytes().unwrap();
+        let media = client.content_store().put(&media, "application/json", &[]).await.unwrap();
+        let manifest = client.get_post_manifest(media.as_str()).await.unwrap();
+        assert_eq!(manifest.get(POST_INDEX_PATH), Some(body.as_str()));
+        assert_eq!(manifest.get(POST_MEDIA_PATH), Some(media.as_str()));
+        assert_eq!(manifest.get(&image_path(480, "image/webp")), Some("variant"));
+    }
+
+    #[tokio::test]
+    async fn editing_only_the_body_rebuilds_data_json() {
+        let store = tempfile::tempdir().unwrap();
+        let client = client(&store);
+        let author = Keypair::new();
+
+        let image = png();
+        let first = client
+            .upload_post_files(&author, &[], PathManifest::new(), Some("First"), Some((&image, "Alt")), &[])
+            .await
+            .unwrap();
+        let base = client.get_post_manifest(first.as_str()).await.unwrap();
+        let second = client
+            .upload_post_files(&author, &[], base, Some("Second"), None, &[])
+            .await
+            .unwrap();
+
+        let manifest = client.get_post_manifest(second.as_str()).await.unwrap();
+        let media = media_manifest(&client, &second).await;
+        assert_eq!(media.content.as_ref().map(ContentId::as_str), manifest.get(POST_INDEX_PATH));
+        let body = client.content_store().get(media.content.as_ref().unwrap()).await.unwrap();
+        assert_eq!(body, b"Second");
+        assert_eq!(media.media, media_manifest(&client, &first).await.media);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn simulation_quotes_the_price_the_upload_will_pay() {
+        let gateway = MockGateway::start(GatewayState {
+            price: 700,
+            bundler_price: 5_000,
+            ..GatewayState::default()
+        })
+        .await;
+        let arweave = ArweaveClient::new()
+            .with_gateway_url(gateway.url.clone())
+            .with_bundler_url(gateway.url.clone());
+        let request = CreatePostRequest {
+            title: "Title".to_string(),
+            content: "Body".to_string(),
+            image_data: None,
+            image_content_type: None,
+        };
+        let author = Keypair::new();
+        let blog = Pubkey::new_unique();
+
+        let mut bundled = BlogClient::new("http://127.0.0.1:1", &solana_blog_program::id().to_string())
+            .unwrap()
+            .with_arweave_client(arweave.clone());
+        bundled.rpc_client = RpcClient::new_mock("succeeds".to_string());
+        let cost = bundled.simulate_create_post(&author, blog, &request).await.unwrap().cost;
+        assert_eq!((cost.arweave_winston, cost.arweave_bundler_lamports), (0, 5_000));
+        let cost = bundled
+            .simulate_update_post(&author, Pubkey::new_unique(), None, Some("Edited"), None)
+            .await
+            .unwrap()
+            .cost;
+        assert_eq!((cost.arweave_winston, cost.arweave_bundler_lamports), (0, 5_000));
+
+        let mut signed = BlogClient::new("http://127.0.0.1:1", &solana_blog_program::id().to_string())
+            .unwrap()
+            .with_arweave_client(arweave.with_wallet_key(TEST_JWK).unwrap());
+        signed.rpc_client = RpcClient::new_mock("succeeds".to_string());
+        let cost = signed.simulate_create_post(&author, blog, &request).await.unwrap().cost;
+        assert_eq!((cost.arweave_winston, cost.arweave_bundler_lamports), (700, 0));
+    }
+}
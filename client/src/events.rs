@@ -0,0 +1,148 @@
+use crate::{
+    blog_client::BlogClient,
+    types::{BlogClientError, Result},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{option_serializer::OptionSerializer, UiTransactionEncoding};
+
+pub use solana_blog_program::events::{BlogEvent, EVENT_DISCRIMINATOR};
+
+const DATA_LOG_PREFIX: &str = "Program data: ";
+
+/// Events `program_id` emitted in a transaction's logs, in order. Logs
+/// from other programs, including ones this program invokes, are skipped,
+/// as is everything under an invocation that failed.
+pub fn parse_events(logs: &[String], program_id: &Pubkey) -> Vec<BlogEvent> {
+    let program_id = program_id.to_string();
+    // Programs currently executing, innermost last, with the events each
+    // has emitted so far
+    let mut stack: Vec<(String, Vec<BlogEvent>)> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        if let Some(data) = line.strip_prefix(DATA_LOG_PREFIX) {
+            let Some((program, emitted)) = stack.last_mut() else { continue };
+            if *program != program_id {
+                continue;
+            }
+            emitted.extend(
+                data.split(' ')
+                    .filter_map(|field| STANDARD.decode(field).ok())
+                    .filter_map(|field| BlogEvent::decode(&field)),
+            );
+            continue;
+        }
+
+        let Some(rest) = line.strip_prefix("Program ") else { continue };
+        let mut words = rest.split(' ');
+        let (Some(program), Some(status)) = (words.next(), words.next()) else { continue };
+        match status {
+            "invoke" => stack.push((program.to_string(), Vec::new())),
+            "success" => {
+                if let Some((_, emitted)) = stack.pop() {
+                    // Inner invocations succeed independently of the outer
+                    // one, so events only count once the outermost does
+                    match stack.last_mut() {
+                        Some((_, outer)) => outer.extend(emitted),
+                        None => events.extend(emitted),
+                    }
+                }
+            }
+            "failed:" => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+    events
+}
+
+impl BlogClient {
+    /// Events this program emitted in a confirmed transaction
+    pub async fn get_transaction_events(&self, signature: &Signature) -> Result<Vec<BlogEvent>> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        let transaction = self
+            .rpc_client
+            .get_transaction_with_config(signature, config)
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to get transaction: {}", e),
+            })?;
+
+        let logs = match transaction.transaction.meta.map(|meta| meta.log_messages) {
+            Some(OptionSerializer::Some(logs)) => logs,
+            _ => Vec::new(),
+        };
+        Ok(parse_events(&logs, &self.program_id()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::BorshSerialize;
+
+    fn data_log(event: &BlogEvent) -> String {
+        let mut data = EVENT_DISCRIMINATOR.to_vec();
+        event.serialize(&mut data).unwrap();
+        format!("{}{}", DATA_LOG_PREFIX, STANDARD.encode(data))
+    }
+
+    fn deleted(post: Pubkey) -> BlogEvent {
+        BlogEvent::PostDeleted {
+            post,
+            blog: Pubkey::new_unique(),
+            author: Pubkey::new_unique(),
+        }
+    }
+
+    #[test]
+    fn events_count_only_from_successful_invocations_of_the_program() {
+        let program_id = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let (kept, nested, failed, foreign) = (
+            deleted(Pubkey::new_unique()),
+            deleted(Pubkey::new_unique()),
+            deleted(Pubkey::new_unique()),
+            deleted(Pubkey::new_unique()),
+        );
+        let logs = vec![
+            format!("Program {} invoke [1]", program_id),
+            data_log(&kept),
+            "Program 11111111111111111111111111111111 invoke [2]".to_string(),
+            "Program 11111111111111111111111111111111 success".to_string(),
+            format!("Program {} invoke [2]", program_id),
+            data_log(&nested),
+            format!("Program {} success", program_id),
+            "Program data: bm90IGFuIGV2ZW50".to_string(),
+            format!("Program {} success", program_id),
+            format!("Program {} invoke [1]", other),
+            data_log(&foreign),
+            format!("Program {} success", other),
+            format!("Program {} invoke [1]", program_id),
+            data_log(&failed),
+            format!("Program {} failed: custom program error: 0x1", program_id),
+        ];
+
+        assert_eq!(parse_events(&logs, &program_id), [kept, nested]);
+    }
+
+    #[test]
+    fn events_of_a_failed_outer_invocation_are_dropped() {
+        let program_id = Pubkey::new_unique();
+        let logs = vec![
+            format!("Program {} invoke [1]", program_id),
+            format!("Program {} invoke [2]", program_id),
+            data_log(&deleted(Pubkey::new_unique())),
+            format!("Program {} success", program_id),
+            format!("Program {} failed: insufficient funds", program_id),
+        ];
+
+        assert!(parse_events(&logs, &program_id).is_empty());
+    }
+}
@@ -0,0 +1,380 @@
+use crate::{
+    blog_client::BlogClient,
+    sync::parse_frontmatter,
+    types::{BlogInfo, PostInfo, Result},
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+
+/// Entries per feed when the options do not say
+pub const DEFAULT_FEED_LIMIT: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    Json,
+}
+
+impl FeedFormat {
+    pub const ALL: [FeedFormat; 3] = [FeedFormat::Rss, FeedFormat::Atom, FeedFormat::Json];
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "rss.xml",
+            FeedFormat::Atom => "atom.xml",
+            FeedFormat::Json => "feed.json",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "application/rss+xml",
+            FeedFormat::Atom => "application/atom+xml",
+            FeedFormat::Json => "application/feed+json",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedOptions {
+    /// Home page of the blog, used as the feed's link. RSS requires one,
+    /// so without it RSS links to the newest post instead.
+    pub site_url: Option<String>,
+    /// URL the feeds will be published under; feeds link to themselves
+    /// as `{feed_url}/{file_name}`
+    pub feed_url: Option<String>,
+    /// Newest posts to include; `DEFAULT_FEED_LIMIT` when zero
+    pub limit: usize,
+}
+
+/// A post as it appears in a feed
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedEntry {
+    pub post: PostInfo,
+    /// Where the post's content can be read, such as its Arweave URL
+    pub url: String,
+    pub tags: Vec<String>,
+}
+
+/// A blog and its newest posts, ready to render in any `FeedFormat`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feed {
+    pub blog: BlogInfo,
+    pub entries: Vec<FeedEntry>,
+    pub options: FeedOptions,
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn datetime(timestamp: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(timestamp, 0).unwrap_or_default()
+}
+
+fn rfc3339(timestamp: i64) -> String {
+    datetime(timestamp).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn rfc2822(timestamp: i64) -> String {
+    datetime(timestamp).to_rfc2822()
+}
+
+fn blog_id(blog: &Pubkey) -> String {
+    format!("urn:solana:blog:{}", blog)
+}
+
+fn post_id(post: &Pubkey) -> String {
+    format!("urn:solana:post:{}", post)
+}
+
+impl Feed {
+    /// Keep the newest `options.limit` entries, newest first
+    pub fn new(blog: BlogInfo, mut entries: Vec<FeedEntry>, options: FeedOptions) -> Self {
+        let limit = if options.limit == 0 { DEFAULT_FEED_LIMIT } else { options.limit };
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.post.created_at));
+        entries.truncate(limit);
+        Self { blog, entries, options }
+    }
+
+    /// Latest change to the blog or any included post
+    pub fn updated_at(&self) -> i64 {
+        self.entries
+            .iter()
+            .map(|entry| entry.post.updated_at.max(entry.post.created_at))
+            .max()
+            .unwrap_or(self.blog.created_at)
+            .max(self.blog.created_at)
+    }
+
+    /// The channel link RSS requires: the site, else the newest post, else
+    /// the blog's id when there is neither
+    fn rss_link(&self) -> String {
+        self.options
+            .site_url
+            .clone()
+            .or_else(|| self.entries.first().map(|entry| entry.url.clone()))
+            .unwrap_or_else(|| blog_id(&self.blog.pubkey))
+    }
+
+    fn self_url(&self, format: FeedFormat) -> Option<String> {
+        self.options
+            .feed_url
+            .as_deref()
+            .map(|url| format!("{}/{}", url.trim_end_matches('/'), format.file_name()))
+    }
+
+    pub fn render(&self, format: FeedFormat) -> String {
+        match format {
+            FeedFormat::Rss => self.to_rss(),
+            FeedFormat::Atom => self.to_atom(),
+            FeedFormat::Json => self.to_json_feed(),
+        }
+    }
+
+    /// RSS 2.0
+    pub fn to_rss(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str("<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\">\n<channel>\n");
+        xml.push_str(&format!("<title>{}</title>\n", xml_escape(&self.blog.title)));
+        xml.push_str(&format!("<link>{}</link>\n", xml_escape(&self.rss_link())));
+        xml.push_str(&format!("<description>{}</description>\n", xml_escape(&self.blog.description)));
+        if let Some(url) = self.self_url(FeedFormat::Rss) {
+            xml.push_str(&format!(
+                "<atom:link href=\"{}\" rel=\"self\" type=\"{}\"/>\n",
+                xml_escape(&url),
+                FeedFormat::Rss.content_type()
+            ));
+        }
+        xml.push_str(&format!("<lastBuildDate>{}</lastBuildDate>\n", rfc2822(self.updated_at())));
+
+        for entry in &self.entries {
+            let post = &entry.post;
+            xml.push_str("<item>\n");
+            xml.push_str(&format!("<title>{}</title>\n", xml_escape(&post.title)));
+            xml.push_str(&format!("<link>{}</link>\n", xml_escape(&entry.url)));
+            xml.push_str(&format!("<guid isPermaLink=\"false\">{}</guid>\n", post_id(&post.pubkey)));
+            xml.push_str(&format!("<description>{}</description>\n", xml_escape(&post.content)));
+            xml.push_str(&format!("<pubDate>{}</pubDate>\n", rfc2822(post.created_at)));
+            for tag in &entry.tags {
+                xml.push_str(&format!("<category>{}</category>\n", xml_escape(tag)));
+            }
+            xml.push_str("</item>\n");
+        }
+        xml.push_str("</channel>\n</rss>\n");
+        xml
+    }
+
+    /// Atom 1.0
+    pub fn to_atom(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+        xml.push_str(&format!("<id>{}</id>\n", blog_id(&self.blog.pubkey)));
+        xml.push_str(&format!("<title>{}</title>\n", xml_escape(&self.blog.title)));
+        xml.push_str(&format!("<subtitle>{}</subtitle>\n", xml_escape(&self.blog.description)));
+        xml.push_str(&format!("<updated>{}</updated>\n", rfc3339(self.updated_at())));
+        xml.push_str(&format!("<author><name>{}</name></author>\n", self.blog.authority));
+        if let Some(url) = &self.options.site_url {
+            xml.push_str(&format!("<link rel=\"alternate\" href=\"{}\"/>\n", xml_escape(url)));
+        }
+        if let Some(url) = self.self_url(FeedFormat::Atom) {
+            xml.push_str(&format!("<link rel=\"self\" href=\"{}\"/>\n", xml_escape(&url)));
+        }
+
+        for entry in &self.entries {
+            let post = &entry.post;
+            xml.push_str("<entry>\n");
+            xml.push_str(&format!("<id>{}</id>\n", post_id(&post.pubkey)));
+            xml.push_str(&format!("<title>{}</title>\n", xml_escape(&post.title)));
+            xml.push_str(&format!("<link rel=\"alternate\" href=\"{}\"/>\n", xml_escape(&entry.url)));
+            xml.push_str(&format!("<published>{}</published>\n", rfc3339(post.created_at)));
+            xml.push_str(&format!(
+                "<updated>{}</updated>\n",
+                rfc3339(post.updated_at.max(post.created_at))
+            ));
+            if post.author != self.blog.authority {
+                xml.push_str(&format!("<author><name>{}</name></author>\n", post.author));
+            }
+            xml.push_str(&format!("<summary>{}</summary>\n", xml_escape(&post.content)));
+            for tag in &entry.tags {
+                xml.push_str(&format!("<category term=\"{}\"/>\n", xml_escape(tag)));
+            }
+            xml.push_str("</entry>\n");
+        }
+        xml.push_str("</feed>\n");
+        xml
+    }
+
+    /// JSON Feed 1.1
+    pub fn to_json_feed(&self) -> String {
+        let items: Vec<serde_json::Value> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let post = &entry.post;
+                json!({
+                    "id": post_id(&post.pubkey),
+                    "url": entry.url,
+                    "title": post.title,
+                    "summary": post.content,
+                    "content_text": post.content,
+                    "date_published": rfc3339(post.created_at),
+                    "date_modified": rfc3339(post.updated_at.max(post.created_at)),
+                    "authors": [{ "name": post.author.to_string() }],
+                    "tags": entry.tags,
+                })
+            })
+            .collect();
+
+        let mut feed = json!({
+            "version": "https://jsonfeed.org/version/1.1",
+            "title": self.blog.title,
+            "description": self.blog.description,
+            "authors": [{ "name": self.blog.authority.to_string() }],
+            "items": items,
+        });
+        if let Some(url) = &self.options.site_url {
+            feed["home_page_url"] = json!(url);
+        }
+        if let Some(url) = self.self_url(FeedFormat::Json) {
+            feed["feed_url"] = json!(url);
+        }
+        serde_json::to_string_pretty(&feed).unwrap_or_default()
+    }
+}
+
+impl BlogClient {
+    /// Build a feed of `blog`'s newest posts, linking each to its content
+    /// and reading its tags from the body's frontmatter. Posts whose body
+    /// cannot be fetched are included without tags.
+    pub async fn blog_feed(&self, blog_pubkey: Pubkey, options: FeedOptions) -> Result<Feed> {
+        let blog = self.get_blog(blog_pubkey).await?;
+        let limit = if options.limit == 0 { DEFAULT_FEED_LIMIT } else { options.limit };
+        let mut posts = self.get_posts_by_blog(blog_pubkey).await?;
+        posts.truncate(limit);
+
+        let mut entries = Vec::with_capacity(posts.len());
+        for post in posts {
+            let tags = match self.get_post_content(&post.arweave_hash).await {
+                Ok((Some(body), _)) => {
+                    let body = String::from_utf8_lossy(&body).replace("\r\n", "\n");
+                    parse_frontmatter(&body)
+                        .map(|(frontmatter, _)| frontmatter.tags)
+                        .unwrap_or_default()
+                }
+                _ => Vec::new(),
+            };
+            entries.push(FeedEntry {
+                url: self.get_arweave_url(&post.arweave_hash),
+                post,
+                tags,
+            });
+        }
+        Ok(Feed::new(blog, entries, options))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(site_url: Option<&str>, posts: usize) -> Feed {
+        let blog = BlogInfo {
+            pubkey: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            title: "Notes & <Thoughts>".to_string(),
+            description: "Tom's \"blog\"".to_string(),
+            post_count: posts as u64,
+            created_at: 0,
+        };
+        let entries = (0..posts as i64)
+            .map(|index| FeedEntry {
+                post: PostInfo {
+                    pubkey: Pubkey::new_unique(),
+                    author: blog.authority,
+                    blog: blog.pubkey,
+                    title: format!("Post {} <b>", index),
+                    content: "Fish & chips".to_string(),
+                    arweave_hash: format!("hash{}", index),
+                    // 2024-01-02T03:04:05Z, a day apart
+                    created_at: 1_704_164_645 + index * 86_400,
+                    updated_at: 0,
+                },
+                url: format!("https://arweave.net/hash{}?a=1&b=2", index),
+                tags: vec!["c&c".to_string()],
+            })
+            .collect();
+        let options = FeedOptions {
+            site_url: site_url.map(str::to_string),
+            feed_url: site_url.map(|url| format!("{}/feeds", url)),
+            limit: 0,
+        };
+        Feed::new(blog, entries, options)
+    }
+
+    #[test]
+    fn rss_escapes_text_and_uses_rfc2822_dates() {
+        let rss = feed(Some("https://example.com/blog"), 2).to_rss();
+        assert!(rss.contains("<title>Notes &amp; &lt;Thoughts&gt;</title>"));
+        assert!(rss.contains("<description>Tom&apos;s &quot;blog&quot;</description>"));
+        assert!(rss.contains("<link>https://example.com/blog</link>"));
+        assert!(rss.contains("<atom:link href=\"https://example.com/blog/feeds/rss.xml\""));
+        assert!(rss.contains("<lastBuildDate>Wed, 3 Jan 2024 03:04:05 +0000</lastBuildDate>"));
+        assert!(rss.contains("<title>Post 1 &lt;b&gt;</title>"));
+        assert!(rss.contains("<link>https://arweave.net/hash1?a=1&amp;b=2</link>"));
+        assert!(rss.contains("<pubDate>Tue, 2 Jan 2024 03:04:05 +0000</pubDate>"));
+        assert!(rss.contains("<category>c&amp;c</category>"));
+        // Newest first
+        assert!(rss.find("Post 1").unwrap() < rss.find("Post 0").unwrap());
+    }
+
+    #[test]
+    fn rss_always_has_a_channel_link() {
+        let without_site = feed(None, 2);
+        assert!(without_site
+            .to_rss()
+            .contains("<link>https://arweave.net/hash1?a=1&amp;b=2</link>\n<description>"));
+
+        let empty = feed(None, 0);
+        let link = format!("<link>urn:solana:blog:{}</link>", empty.blog.pubkey);
+        assert!(empty.to_rss().contains(&link));
+    }
+
+    #[test]
+    fn atom_escapes_text_and_uses_rfc3339_dates() {
+        let atom = feed(Some("https://example.com/blog"), 1).to_atom();
+        assert!(atom.contains("<title>Notes &amp; &lt;Thoughts&gt;</title>"));
+        assert!(atom.contains("<link rel=\"alternate\" href=\"https://example.com/blog\"/>"));
+        assert!(atom.contains("<link rel=\"alternate\" href=\"https://arweave.net/hash0?a=1&amp;b=2\"/>"));
+        assert!(atom.contains("<published>2024-01-02T03:04:05Z</published>"));
+        assert!(atom.contains("<updated>2024-01-02T03:04:05Z</updated>"));
+        assert!(atom.contains("<summary>Fish &amp; chips</summary>"));
+        assert!(atom.contains("<category term=\"c&amp;c\"/>"));
+
+        let without_site = feed(None, 1).to_atom();
+        assert!(!without_site.contains("https://example.com"));
+    }
+
+    #[test]
+    fn json_feed_keeps_text_as_is_and_uses_rfc3339_dates() {
+        let json: serde_json::Value = serde_json::from_str(&feed(Some("https://example.com/blog"), 1).to_json_feed()).unwrap();
+        assert_eq!(json["title"], "Notes & <Thoughts>");
+        assert_eq!(json["home_page_url"], "https://example.com/blog");
+        assert_eq!(json["feed_url"], "https://example.com/blog/feeds/feed.json");
+        let item = &json["items"][0];
+        assert_eq!(item["title"], "Post 0 <b>");
+        assert_eq!(item["url"], "https://arweave.net/hash0?a=1&b=2");
+        assert_eq!(item["date_published"], "2024-01-02T03:04:05Z");
+        assert_eq!(item["date_modified"], "2024-01-02T03:04:05Z");
+        assert_eq!(item["tags"], serde_json::json!(["c&c"]));
+
+        let json: serde_json::Value = serde_json::from_str(&feed(None, 1).to_json_feed()).unwrap();
+        assert!(json.get("home_page_url").is_none());
+    }
+}
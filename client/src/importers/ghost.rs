@@ -0,0 +1,223 @@
+use super::ExternalPost;
+use crate::types::{BlogClientError, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+struct GhostExport {
+    db: Vec<GhostDatabase>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhostDatabase {
+    data: GhostData,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GhostData {
+    posts: Vec<GhostPost>,
+    tags: Vec<GhostTag>,
+    users: Vec<GhostUser>,
+    posts_tags: Vec<GhostPostTag>,
+    posts_authors: Vec<GhostPostAuthor>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhostPost {
+    id: String,
+    #[serde(default)]
+    title: String,
+    slug: Option<String>,
+    html: Option<String>,
+    plaintext: Option<String>,
+    custom_excerpt: Option<String>,
+    feature_image: Option<String>,
+    status: Option<String>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    published_at: Option<String>,
+    created_at: Option<String>,
+    author_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhostTag {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhostUser {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhostPostTag {
+    post_id: String,
+    tag_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhostPostAuthor {
+    post_id: String,
+    author_id: String,
+}
+
+/// Posts in a Ghost JSON export (Settings → Labs → Export). Pages are left
+/// out; internal `#hash` tags are dropped.
+pub fn parse(json: &[u8]) -> Result<Vec<ExternalPost>> {
+    let export: GhostExport = serde_json::from_slice(json).map_err(|e| BlogClientError {
+        message: format!("Failed to parse Ghost export: {}", e),
+    })?;
+
+    let mut posts = Vec::new();
+    for database in export.db {
+        let data = database.data;
+        let tags: HashMap<&str, &str> = data
+            .tags
+            .iter()
+            .map(|tag| (tag.id.as_str(), tag.name.as_str()))
+            .collect();
+        let users: HashMap<&str, &str> = data
+            .users
+            .iter()
+            .map(|user| (user.id.as_str(), user.name.as_str()))
+            .collect();
+
+        for post in &data.posts {
+            if post.kind.as_deref().is_some_and(|kind| kind != "post") {
+                continue;
+            }
+
+            let html = match (&post.html, &post.plaintext) {
+                (Some(html), _) => html.clone(),
+                // Escape plain text so it survives the HTML conversion
+                (None, Some(text)) => text
+                    .split("\n\n")
+                    .map(|paragraph| {
+                        format!(
+                            "<p>{}</p>",
+                            paragraph.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+                        )
+                    })
+                    .collect(),
+                (None, None) => String::new(),
+            };
+
+            let author_id = data
+                .posts_authors
+                .iter()
+                .find(|link| link.post_id == post.id)
+                .map(|link| link.author_id.as_str())
+                .or(post.author_id.as_deref());
+
+            posts.push(ExternalPost {
+                source: "ghost".to_string(),
+                source_id: post.slug.clone().unwrap_or_else(|| post.id.clone()),
+                title: post.title.trim().to_string(),
+                html,
+                excerpt: post.custom_excerpt.clone().filter(|excerpt| !excerpt.is_empty()),
+                author: author_id.and_then(|id| users.get(id)).map(|name| name.to_string()),
+                tags: data
+                    .posts_tags
+                    .iter()
+                    .filter(|link| link.post_id == post.id)
+                    .filter_map(|link| tags.get(link.tag_id.as_str()))
+                    .filter(|name| !name.starts_with('#'))
+                    .map(|name| name.to_string())
+                    .collect(),
+                published_at: post.published_at.clone().or_else(|| post.created_at.clone()),
+                feature_image: post.feature_image.clone(),
+                draft: post.status.as_deref() != Some("published"),
+            });
+        }
+    }
+    Ok(posts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        importers::{format_body, html_to_markdown},
+        sync::parse_frontmatter,
+    };
+
+    const EXPORT: &str = r##"{
+        "db": [{
+            "data": {
+                "posts": [
+                    {
+                        "id": "p1",
+                        "title": " Hello Ghost ",
+                        "slug": "hello-ghost",
+                        "html": "<p>Hi <img src=\"__GHOST_URL__/content/images/wave.png\"></p>",
+                        "custom_excerpt": "Waving",
+                        "feature_image": "__GHOST_URL__/content/images/cover.jpg",
+                        "status": "published",
+                        "type": "post",
+                        "published_at": "2022-01-02T03:04:05.000Z",
+                        "created_at": "2022-01-01T00:00:00.000Z"
+                    },
+                    {
+                        "id": "p2",
+                        "title": "Plain",
+                        "plaintext": "1 < 2 & 3",
+                        "status": "draft",
+                        "created_at": "2022-02-01T00:00:00.000Z",
+                        "author_id": "u2"
+                    },
+                    { "id": "p3", "title": "About", "status": "published", "type": "page" }
+                ],
+                "tags": [
+                    { "id": "t1", "name": "Rust" },
+                    { "id": "t2", "name": "#internal" }
+                ],
+                "users": [
+                    { "id": "u1", "name": "Ana" },
+                    { "id": "u2", "name": "Bo" }
+                ],
+                "posts_tags": [
+                    { "post_id": "p1", "tag_id": "t1" },
+                    { "post_id": "p1", "tag_id": "t2" }
+                ],
+                "posts_authors": [{ "post_id": "p1", "author_id": "u1" }]
+            }
+        }]
+    }"##;
+
+    #[test]
+    fn posts_are_read_from_a_json_export() {
+        let posts = parse(EXPORT.as_bytes()).unwrap();
+        let titles: Vec<&str> = posts.iter().map(|post| post.title.as_str()).collect();
+        assert_eq!(titles, ["Hello Ghost", "Plain"]);
+
+        let post = &posts[0];
+        assert_eq!(post.source_id, "hello-ghost");
+        assert_eq!(post.author.as_deref(), Some("Ana"));
+        assert_eq!(post.tags, ["Rust"]);
+        assert_eq!(post.excerpt.as_deref(), Some("Waving"));
+        assert_eq!(post.feature_image.as_deref(), Some("__GHOST_URL__/content/images/cover.jpg"));
+        assert!(!post.draft);
+
+        let markdown = html_to_markdown(&post.html);
+        assert!(markdown.contains("![](__GHOST_URL__/content/images/wave.png)"), "{}", markdown);
+        let body = format_body(post, "Waving", &markdown);
+        let (frontmatter, _) = parse_frontmatter(&body).unwrap();
+        assert_eq!(frontmatter.title.as_deref(), Some("Hello Ghost"));
+        assert_eq!(frontmatter.author.as_deref(), Some("Ana"));
+        assert_eq!(frontmatter.tags, ["Rust"]);
+        assert_eq!(frontmatter.created_at.as_deref(), Some("2022-01-02T03:04:05.000Z"));
+
+        // Plain text is escaped, and unpublished posts fall back to their
+        // creation date and the author on the post itself
+        let plain = &posts[1];
+        assert_eq!(plain.html, "<p>1 &lt; 2 &amp; 3</p>");
+        assert_eq!(plain.source_id, "p2");
+        assert_eq!(plain.author.as_deref(), Some("Bo"));
+        assert_eq!(plain.published_at.as_deref(), Some("2022-02-01T00:00:00.000Z"));
+        assert!(plain.draft);
+    }
+}
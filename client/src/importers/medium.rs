@@ -0,0 +1,178 @@
+use super::{decode_entities, ExternalPost};
+use crate::types::{BlogClientError, Result};
+use std::io::{Cursor, Read};
+
+/// Text between the first `start` and the following `end`
+fn between<'a>(html: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let from = html.find(start)? + start.len();
+    let to = html[from..].find(end)? + from;
+    Some(&html[from..to])
+}
+
+/// Contents of the first element whose opening tag contains `marker`
+fn element_with<'a>(html: &'a str, marker: &str, closing: &str) -> Option<&'a str> {
+    let at = html.find(marker)?;
+    let open_end = html[at..].find('>')? + at + 1;
+    let close = html[open_end..].find(closing)? + open_end;
+    Some(&html[open_end..close])
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    decode_entities(text.trim())
+}
+
+/// One exported post page
+fn parse_post(name: &str, html: &str) -> ExternalPost {
+    let title = element_with(html, "class=\"p-name\"", "</h1>")
+        .or_else(|| between(html, "<title>", "</title>"))
+        .map(strip_tags)
+        .unwrap_or_default();
+    // Medium writes `<a href="..." class="p-canonical">`
+    let canonical = html.find("class=\"p-canonical\"").and_then(|at| {
+        let tag = &html[html[..at].rfind('<')?..at];
+        between(tag, "href=\"", "\"")
+    });
+
+    // The body section is followed by the export's footer
+    let body = html
+        .find("data-field=\"body\"")
+        .map(|at| {
+            let rest = &html[at..];
+            let start = rest.find('>').map(|end| end + 1).unwrap_or(0);
+            let end = rest.find("<footer").unwrap_or(rest.len());
+            &rest[start..end]
+        })
+        .unwrap_or_default();
+
+    // Medium repeats the title as the first heading of the body
+    let body = match body.find("graf--title") {
+        Some(at) => {
+            let heading_end = body[at..]
+                .find("</h3>")
+                .or_else(|| body[at..].find("</h1>"))
+                .map(|end| at + end + "</h3>".len());
+            match (body[..at].rfind('<'), heading_end) {
+                (Some(start), Some(end)) => format!("{}{}", &body[..start], &body[end..]),
+                _ => body.to_string(),
+            }
+        }
+        None => body.to_string(),
+    };
+
+    ExternalPost {
+        source: "medium".to_string(),
+        source_id: canonical.map(str::to_string).unwrap_or_else(|| name.to_string()),
+        title,
+        html: body,
+        excerpt: element_with(html, "data-field=\"subtitle\"", "</section>")
+            .map(strip_tags)
+            .filter(|subtitle| !subtitle.is_empty()),
+        author: element_with(html, "class=\"p-author h-card\"", "</a>").map(strip_tags),
+        tags: Vec::new(),
+        published_at: between(html, "class=\"dt-published\" datetime=\"", "\"").map(str::to_string),
+        feature_image: None,
+        draft: name.rsplit('/').next().is_some_and(|file| file.starts_with("draft_")),
+    }
+}
+
+/// Posts in a Medium account export zip (Settings → Download your
+/// information), read from its `posts/*.html` pages
+pub fn parse(zip: &[u8]) -> Result<Vec<ExternalPost>> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(zip)).map_err(|e| BlogClientError {
+        message: format!("Failed to open Medium export: {}", e),
+    })?;
+
+    let mut posts = Vec::new();
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index).map_err(|e| BlogClientError {
+            message: format!("Failed to read Medium export: {}", e),
+        })?;
+        let name = file.name().to_string();
+        let in_posts = name.starts_with("posts/") || name.contains("/posts/");
+        if !in_posts || !name.ends_with(".html") {
+            continue;
+        }
+
+        let mut html = String::new();
+        file.read_to_string(&mut html).map_err(|e| BlogClientError {
+            message: format!("Failed to read {} from Medium export: {}", name, e),
+        })?;
+        posts.push(parse_post(&name, &html));
+    }
+    Ok(posts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        importers::{format_body, html_to_markdown},
+        sync::parse_frontmatter,
+    };
+    use std::io::Write;
+
+    const POST: &str = r#"<!DOCTYPE html><html><head><title>Medium Title</title></head><body>
+<article class="h-entry">
+<header><h1 class="p-name">Going &amp; Coming</h1></header>
+<section data-field="subtitle" class="p-summary">A <em>round</em> trip</section>
+<section data-field="body" class="e-content">
+<h3 class="graf graf--h3 graf--title">Going &amp; Coming</h3>
+<p class="graf graf--p">First paragraph.</p>
+<figure><img class="graf-image" src="https://cdn-images-1.medium.com/max/800/trip.jpeg"></figure>
+</section>
+<footer><p>By <a href="https://medium.com/@ana" class="p-author h-card">Ana</a> on
+<a href="https://medium.com/p/abc"><time class="dt-published" datetime="2020-05-06T07:08:09.010Z">May 6</time></a>.</p>
+<p><a href="https://medium.com/@ana/going-and-coming-abc" class="p-canonical">Canonical link</a></p>
+</footer></article></body></html>"#;
+
+    fn export() -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, html) in [
+            ("posts/2020-05-06_Going-Coming-abc.html", POST),
+            ("posts/draft_Later-def.html", "<title>Later</title><section data-field=\"body\"><p>Soon</p></section>"),
+            ("profile/profile.html", "<title>Profile</title>"),
+        ] {
+            zip.start_file(name, zip::write::FileOptions::default()).unwrap();
+            zip.write_all(html.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn posts_are_read_from_an_export_zip() {
+        let posts = parse(&export()).unwrap();
+        let titles: Vec<&str> = posts.iter().map(|post| post.title.as_str()).collect();
+        assert_eq!(titles, ["Going & Coming", "Later"]);
+
+        let post = &posts[0];
+        assert_eq!(post.source_id, "https://medium.com/@ana/going-and-coming-abc");
+        assert_eq!(post.excerpt.as_deref(), Some("A round trip"));
+        assert_eq!(post.author.as_deref(), Some("Ana"));
+        assert_eq!(post.published_at.as_deref(), Some("2020-05-06T07:08:09.010Z"));
+        assert!(!post.draft);
+
+        // The repeated title heading is dropped from the body
+        let markdown = html_to_markdown(&post.html);
+        assert!(!markdown.contains("Going"), "{}", markdown);
+        assert!(markdown.contains("First paragraph."), "{}", markdown);
+        assert!(markdown.contains("![](https://cdn-images-1.medium.com/max/800/trip.jpeg)"), "{}", markdown);
+        let body = format_body(post, "A round trip", &markdown);
+        let (frontmatter, _) = parse_frontmatter(&body).unwrap();
+        assert_eq!(frontmatter.title.as_deref(), Some("Going & Coming"));
+        assert_eq!(frontmatter.author.as_deref(), Some("Ana"));
+        assert_eq!(frontmatter.created_at.as_deref(), Some("2020-05-06T07:08:09.010Z"));
+
+        assert!(posts[1].draft);
+        assert_eq!(posts[1].source_id, "posts/draft_Later-def.html");
+    }
+}
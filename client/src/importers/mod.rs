@@ -0,0 +1,534 @@
+pub mod ghost;
+pub mod medium;
+pub mod wordpress;
+
+use crate::{
+    arweave::{manifest::PathManifest, ArweaveTag},
+    blog_client::BlogClient,
+    storage::content_type_for_path,
+    sync::{frontmatter_line, truncate},
+    types::{BlogClientError, PostAttachment, Result},
+};
+use solana_blog_program::state::BlogPost;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Manifest directory for images pulled into an imported post
+const IMPORTED_FILES_DIR: &str = "files/";
+/// Images larger than this stay remote links rather than being uploaded
+const MAX_IMAGE_BYTES: usize = 25 << 20;
+/// A slow image host fails that image instead of stalling the import
+const IMAGE_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A post read from another platform's export, before conversion
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExternalPost {
+    /// Platform the post came from, such as `wordpress`
+    pub source: String,
+    /// The post's id or URL on that platform
+    pub source_id: String,
+    pub title: String,
+    pub html: String,
+    pub excerpt: Option<String>,
+    pub author: Option<String>,
+    pub tags: Vec<String>,
+    /// RFC 3339 timestamp, when the export has one
+    pub published_at: Option<String>,
+    /// Cover image URL
+    pub feature_image: Option<String>,
+    pub draft: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExternalImportOptions {
+    /// Convert and report without downloading, uploading or sending anything
+    pub dry_run: bool,
+    pub include_drafts: bool,
+    /// Site URL that relative image links (and Ghost's `__GHOST_URL__`)
+    /// resolve against
+    pub base_url: Option<String>,
+    /// File mapping source ids to the posts created from them, updated
+    /// after every post so a repeated import skips what it already created.
+    /// Read but not written on dry runs.
+    pub record: Option<PathBuf>,
+}
+
+/// A post an import created, or would create on a dry run
+#[derive(Debug, Clone)]
+pub struct ImportedPost {
+    pub source_id: String,
+    pub title: String,
+    pub published_at: Option<String>,
+    /// `None` on dry runs
+    pub post: Option<Pubkey>,
+    /// Size of the converted markdown body
+    pub bytes: usize,
+    /// Image URLs pulled into the post (or that would be)
+    pub images: Vec<String>,
+    /// Image URLs that could not be fetched and were left as remote links
+    pub failed_images: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExternalImportReport {
+    pub created: Vec<ImportedPost>,
+    /// Source id and reason for posts that were not imported
+    pub skipped: Vec<(String, String)>,
+}
+
+/// Convert post HTML to markdown
+pub fn html_to_markdown(html: &str) -> String {
+    html2md::parse_html(html).trim().to_string()
+}
+
+/// Decode the handful of entities that show up in titles and attributes
+pub(crate) fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// Absolute URL for an image link found in an export, or `None` if it is
+/// relative and there is no base URL to resolve it against
+fn resolve_url(url: &str, base_url: Option<&str>) -> Option<String> {
+    let base = base_url.map(|base| base.trim_end_matches('/'));
+    if url.starts_with("https://") || url.starts_with("http://") {
+        Some(url.to_string())
+    } else if let Some(rest) = url.strip_prefix("//") {
+        Some(format!("https://{}", rest))
+    } else if let Some(rest) = url.strip_prefix("__GHOST_URL__") {
+        base.map(|base| format!("{}{}", base, rest))
+    } else if url.starts_with('/') {
+        base.map(|base| format!("{}{}", base, url))
+    } else {
+        None
+    }
+}
+
+/// Markdown image targets in order of appearance, as (start, end) byte
+/// ranges of the URL
+fn image_links(markdown: &str) -> Vec<(usize, usize)> {
+    let mut links = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = markdown[offset..].find("![") {
+        let alt_start = offset + start + 2;
+        let Some(close) = markdown[alt_start..].find("](") else { break };
+        let url_start = alt_start + close + 2;
+        let url_end = markdown[url_start..]
+            .find(|c: char| c == ')' || c.is_whitespace())
+            .map(|end| url_start + end)
+            .unwrap_or(markdown.len());
+        links.push((url_start, url_end));
+        offset = url_end;
+    }
+    links
+}
+
+/// Manifest path for a downloaded image, unique among `taken`
+fn attachment_path(url: &str, taken: &[PostAttachment]) -> String {
+    let name = url
+        .split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit('/').next())
+        .filter(|name| !name.is_empty())
+        .unwrap_or("image");
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '-' })
+        .collect();
+
+    let mut path = format!("{}{}", IMPORTED_FILES_DIR, name);
+    let mut counter = 1;
+    while taken.iter().any(|attachment| attachment.path == path) {
+        counter += 1;
+        path = format!("{}{}-{}", IMPORTED_FILES_DIR, counter, name);
+    }
+    path
+}
+
+/// Body in the frontmatter format `ArweaveService::format_post_content`
+/// writes, so the frontend shows the original date and author. Values are
+/// quoted, and tags written as a JSON array so they may contain commas.
+fn format_body(post: &ExternalPost, description: &str, markdown: &str) -> String {
+    let mut body = String::from("---\n");
+    body.push_str(&frontmatter_line("title", &post.title));
+    body.push_str(&frontmatter_line("description", description));
+    body.push_str(&format!("tags: {}\n", serde_json::Value::from(post.tags.clone())));
+    body.push_str(&frontmatter_line("created_at", post.published_at.as_deref().unwrap_or_default()));
+    body.push_str(&frontmatter_line("author", post.author.as_deref().unwrap_or_default()));
+    body.push_str("---\n\n");
+    body.push_str(markdown);
+    body.push('\n');
+    body
+}
+
+/// Source ids already imported, from `ExternalImportOptions::record`
+fn load_record(path: &Path) -> Result<BTreeMap<String, String>> {
+    match std::fs::read(path) {
+        Ok(data) => serde_json::from_slice(&data).map_err(|e| BlogClientError {
+            message: format!("Failed to parse {}: {}", path.display(), e),
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(BlogClientError {
+            message: format!("Failed to read {}: {}", path.display(), e),
+        }),
+    }
+}
+
+fn save_record(path: &Path, record: &BTreeMap<String, String>) -> Result<()> {
+    let data = serde_json::to_vec_pretty(record).map_err(|e| BlogClientError {
+        message: format!("Failed to serialize import record: {}", e),
+    })?;
+    // Write then rename so an interrupted import never truncates it
+    let temp_path = path.with_extension("tmp");
+    std::fs::write(&temp_path, data)
+        .and_then(|_| std::fs::rename(&temp_path, path))
+        .map_err(|e| BlogClientError {
+            message: format!("Failed to save {}: {}", path.display(), e),
+        })
+}
+
+/// Download an image, refusing bodies over `MAX_IMAGE_BYTES` whether or
+/// not the server declares their length
+async fn fetch(http: &reqwest::Client, url: &str) -> Result<(Vec<u8>, Option<String>)> {
+    let too_large = || BlogClientError {
+        message: format!("{} is larger than {} bytes", url, MAX_IMAGE_BYTES),
+    };
+    let mut response = http
+        .get(url)
+        .timeout(IMAGE_FETCH_TIMEOUT)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| BlogClientError {
+            message: format!("Failed to fetch {}: {}", url, e),
+        })?;
+    if response.content_length().is_some_and(|length| length > MAX_IMAGE_BYTES as u64) {
+        return Err(too_large());
+    }
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.split(';').next().unwrap_or(value).trim().to_string());
+
+    let mut data = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| BlogClientError {
+        message: format!("Failed to read {}: {}", url, e),
+    })? {
+        if data.len() + chunk.len() > MAX_IMAGE_BYTES {
+            return Err(too_large());
+        }
+        data.extend_from_slice(&chunk);
+    }
+    Ok((data, content_type))
+}
+
+impl BlogClient {
+    /// Convert posts from another platform to markdown, pull their images
+    /// into each post's manifest, and create them on `blog` oldest first.
+    /// Images that cannot be fetched stay as remote links and are reported.
+    pub async fn import_external_posts(
+        &self,
+        author: &Keypair,
+        blog_pubkey: Pubkey,
+        mut posts: Vec<ExternalPost>,
+        options: &ExternalImportOptions,
+    ) -> Result<ExternalImportReport> {
+        posts.sort_by(|a, b| a.published_at.cmp(&b.published_at));
+        let http = reqwest::Client::new();
+        let mut report = ExternalImportReport::default();
+        let mut record = match &options.record {
+            Some(path) => load_record(path)?,
+            None => BTreeMap::new(),
+        };
+
+        for post in posts {
+            if let Some(existing) = record.get(&post.source_id) {
+                let reason = format!("already imported as {}", existing);
+                report.skipped.push((post.source_id, reason));
+                continue;
+            }
+            if post.draft && !options.include_drafts {
+                report.skipped.push((post.source_id, "draft".to_string()));
+                continue;
+            }
+            if post.title.is_empty() {
+                report.skipped.push((post.source_id, "no title".to_string()));
+                continue;
+            }
+            if post.title.len() > BlogPost::MAX_TITLE_LENGTH {
+                report.skipped.push((
+                    post.source_id,
+                    format!("title is longer than {} bytes", BlogPost::MAX_TITLE_LENGTH),
+                ));
+                continue;
+            }
+
+            let mut markdown = html_to_markdown(&post.html);
+            let mut images = Vec::new();
+            let mut failed_images = Vec::new();
+            let mut attachments: Vec<PostAttachment> = Vec::new();
+
+            // Rewrite back to front so earlier ranges stay valid
+            for (start, end) in image_links(&markdown).into_iter().rev() {
+                let link = markdown[start..end].to_string();
+                let Some(url) = resolve_url(&link, options.base_url.as_deref()) else {
+                    failed_images.push(link);
+                    continue;
+                };
+                if options.dry_run {
+                    images.push(url);
+                    continue;
+                }
+                match fetch(&http, &url).await {
+                    Ok((data, content_type)) => {
+                        let path = attachment_path(&url, &attachments);
+                        let content_type = content_type
+                            .filter(|content_type| content_type.starts_with("image/"))
+                            .unwrap_or_else(|| content_type_for_path(Path::new(&path)).to_string());
+                        markdown.replace_range(start..end, &path);
+                        attachments.push(PostAttachment { path, data, content_type });
+                        images.push(url);
+                    }
+                    Err(_) => failed_images.push(url),
+                }
+            }
+            images.reverse();
+            failed_images.reverse();
+
+            // Cover images go through the resizing pipeline when it can
+            // decode them, and are attached as-is otherwise
+            let mut cover = None;
+            if let Some(url) = post
+                .feature_image
+                .as_deref()
+                .and_then(|url| resolve_url(url, options.base_url.as_deref()))
+            {
+                if options.dry_run {
+                    images.insert(0, url);
+                } else {
+                    match fetch(&http, &url).await {
+                        Ok((data, content_type)) => {
+                            if matches!(
+                                image::guess_format(&data),
+                                Ok(image::ImageFormat::Jpeg | image::ImageFormat::Png | image::ImageFormat::WebP)
+                            ) {
+                                cover = Some(data);
+                            } else {
+                                let path = attachment_path(&url, &attachments);
+                                markdown = format!("![{}]({})\n\n{}", post.title, path, markdown);
+                                attachments.push(PostAttachment {
+                                    content_type: content_type
+                                        .unwrap_or_else(|| content_type_for_path(Path::new(&path)).to_string()),
+                                    path,
+                                    data,
+                                });
+                            }
+                            images.insert(0, url);
+                        }
+                        Err(_) => failed_images.insert(0, url),
+                    }
+                }
+            }
+
+            // Excerpts are HTML on some platforms, and become both the
+            // description and the on-chain summary
+            let excerpt = post
+                .excerpt
+                .as_deref()
+                .map(html_to_markdown)
+                .filter(|excerpt| !excerpt.is_empty());
+            let body = format_body(&post, excerpt.as_deref().unwrap_or_default(), &markdown);
+            let summary = excerpt.unwrap_or_else(|| markdown.trim().to_string());
+            let summary = truncate(&summary, BlogPost::MAX_CONTENT_LENGTH).to_string();
+
+            let post_pubkey = if options.dry_run {
+                None
+            } else {
+                let post_keypair = Keypair::new();
+                let post_pubkey = post_keypair.pubkey();
+                let tags = [
+                    ArweaveTag::new("Blog", blog_pubkey.to_string()),
+                    ArweaveTag::new("Post", post_pubkey.to_string()),
+                    ArweaveTag::new("Post-Title", post.title.clone()),
+                    ArweaveTag::new("Imported-From", post.source.clone()),
+                ];
+                let image = cover.as_deref().map(|data| (data, post.title.as_str()));
+                let arweave_hash = self
+                    .upload_post_files(author, &tags, PathManifest::new(), Some(&body), image, &attachments)
+                    .await?;
+
+                let instruction = self.create_post_instruction(
+                    author.pubkey(),
+                    post_pubkey,
+                    blog_pubkey,
+                    post.title.clone(),
+                    summary,
+                    arweave_hash.0,
+                )?;
+                self.send_instructions(&[instruction], author, &[author, &post_keypair])
+                    .await?;
+                if let Some(path) = &options.record {
+                    record.insert(post.source_id.clone(), post_pubkey.to_string());
+                    save_record(path, &record)?;
+                }
+                Some(post_pubkey)
+            };
+
+            report.created.push(ImportedPost {
+                source_id: post.source_id,
+                title: post.title,
+                published_at: post.published_at,
+                post: post_pubkey,
+                bytes: body.len(),
+                images,
+                failed_images,
+            });
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::parse_frontmatter;
+    use axum::{body::Body, routing::get, Router};
+
+    fn post(source_id: &str) -> ExternalPost {
+        ExternalPost {
+            source: "wordpress".to_string(),
+            source_id: source_id.to_string(),
+            title: "Post".to_string(),
+            html: "<p>Body</p>".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn frontmatter_values_survive_quotes_newlines_and_commas() {
+        let post = ExternalPost {
+            title: "Rust: \"fast\", safe".to_string(),
+            author: Some("Ana\ntitle: injected".to_string()),
+            tags: vec!["rust, systems".to_string(), "web".to_string()],
+            published_at: Some("2023-04-01T10:00:00Z".to_string()),
+            ..post("1")
+        };
+
+        let body = format_body(&post, "An excerpt", "Body");
+        let (frontmatter, text) = parse_frontmatter(&body).unwrap();
+        assert_eq!(frontmatter.title.as_deref(), Some("Rust: \"fast\", safe"));
+        assert_eq!(frontmatter.description.as_deref(), Some("An excerpt"));
+        assert_eq!(frontmatter.author.as_deref(), Some("Ana\ntitle: injected"));
+        assert_eq!(frontmatter.tags, ["rust, systems", "web"]);
+        assert_eq!(frontmatter.created_at.as_deref(), Some("2023-04-01T10:00:00Z"));
+        assert_eq!(text, "Body\n");
+    }
+
+    #[tokio::test]
+    async fn recorded_source_ids_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.imported.json");
+        let existing = Pubkey::new_unique().to_string();
+        save_record(&path, &BTreeMap::from([("1".to_string(), existing.clone())])).unwrap();
+
+        let client = BlogClient::new("http://127.0.0.1:1", &Pubkey::new_unique().to_string()).unwrap();
+        let options = ExternalImportOptions {
+            dry_run: true,
+            record: Some(path),
+            ..Default::default()
+        };
+        let report = client
+            .import_external_posts(&Keypair::new(), Pubkey::new_unique(), vec![post("1"), post("2")], &options)
+            .await
+            .unwrap();
+
+        assert_eq!(report.skipped, [("1".to_string(), format!("already imported as {}", existing))]);
+        let created: Vec<&str> = report.created.iter().map(|post| post.source_id.as_str()).collect();
+        assert_eq!(created, ["2"]);
+    }
+
+    #[tokio::test]
+    async fn dry_runs_report_images_and_skipped_posts() {
+        let client = BlogClient::new("http://127.0.0.1:1", &Pubkey::new_unique().to_string()).unwrap();
+        let posts = vec![
+            ExternalPost {
+                html: "<p>Hi</p><img src=\"/uploads/a.png\"><img src=\"relative.png\">".to_string(),
+                feature_image: Some("//cdn.example.com/cover.jpg".to_string()),
+                published_at: Some("2021-01-01T00:00:00Z".to_string()),
+                ..post("1")
+            },
+            ExternalPost {
+                draft: true,
+                ..post("2")
+            },
+            ExternalPost {
+                title: String::new(),
+                ..post("3")
+            },
+        ];
+        let options = ExternalImportOptions {
+            dry_run: true,
+            base_url: Some("https://example.com/".to_string()),
+            ..Default::default()
+        };
+        let report = client
+            .import_external_posts(&Keypair::new(), Pubkey::new_unique(), posts, &options)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            report.skipped,
+            [("2".to_string(), "draft".to_string()), ("3".to_string(), "no title".to_string())]
+        );
+        let [imported] = &report.created[..] else { panic!("{:?}", report.created) };
+        assert_eq!(imported.post, None);
+        assert_eq!(imported.published_at.as_deref(), Some("2021-01-01T00:00:00Z"));
+        assert_eq!(
+            imported.images,
+            ["https://cdn.example.com/cover.jpg", "https://example.com/uploads/a.png"]
+        );
+        assert_eq!(imported.failed_images, ["relative.png"]);
+        assert!(imported.bytes > 0);
+    }
+
+    #[tokio::test]
+    async fn image_fetches_stop_at_the_size_limit() {
+        let app = Router::new()
+            .route("/small.png", get(|| async { ([("content-type", "image/png; charset=binary")], vec![1u8; 10]) }))
+            .route("/large.png", get(|| async { vec![0u8; MAX_IMAGE_BYTES + 1] }))
+            // Streamed without a declared length
+            .route(
+                "/stream.png",
+                get(|| async {
+                    let chunk = || Ok::<_, std::io::Error>(vec![0u8; MAX_IMAGE_BYTES / 2 + 1]);
+                    Body::from_stream(futures_util::stream::iter([chunk(), chunk()]))
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        let http = reqwest::Client::new();
+
+        let (data, content_type) = fetch(&http, &format!("{}/small.png", url)).await.unwrap();
+        assert_eq!(data, [1u8; 10]);
+        assert_eq!(content_type.as_deref(), Some("image/png"));
+        for path in ["large.png", "stream.png"] {
+            let error = fetch(&http, &format!("{}/{}", url, path)).await.unwrap_err();
+            assert!(error.message.contains("is larger than"), "{}", error.message);
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use super::{decode_entities, ExternalPost};
+use crate::types::{BlogClientError, Result};
+use roxmltree::{Document, Node};
+use std::collections::HashMap;
+
+const CONTENT_NAMESPACE: &str = "http://purl.org/rss/1.0/modules/content/";
+
+/// Text of the first child of `item` named `name`, optionally restricted to
+/// namespaces containing `namespace`
+fn child_text<'a>(item: Node<'a, 'a>, name: &str, namespace: Option<&str>) -> Option<&'a str> {
+    item.children()
+        .find(|child| {
+            child.tag_name().name() == name
+                && namespace.is_none_or(|namespace| {
+                    child.tag_name().namespace().unwrap_or_default().contains(namespace)
+                })
+        })
+        .and_then(|child| child.text())
+}
+
+/// Add paragraph tags to content saved without them. WordPress stores
+/// classic-editor posts with blank lines between paragraphs and adds the
+/// markup when rendering.
+fn autop(content: &str) -> String {
+    if content.contains("<p") || content.contains("<!-- wp:") {
+        return content.to_string();
+    }
+    content
+        .replace("\r\n", "\n")
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| format!("<p>{}</p>", paragraph.replace('\n', "<br>\n")))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `2021-03-04 05:06:07` as RFC 3339, or `None` for WordPress's zero date
+fn gmt_to_rfc3339(date: &str) -> Option<String> {
+    let date = date.trim();
+    if date.is_empty() || date.starts_with("0000") {
+        return None;
+    }
+    Some(format!("{}Z", date.replacen(' ', "T", 1)))
+}
+
+/// Posts in a WordPress WXR export (Tools → Export). Pages, attachments and
+/// other post types are left out.
+pub fn parse(xml: &str) -> Result<Vec<ExternalPost>> {
+    let document = Document::parse(xml).map_err(|e| BlogClientError {
+        message: format!("Failed to parse WordPress export: {}", e),
+    })?;
+
+    // Featured images are attachment items linked by `_thumbnail_id`
+    let attachments: HashMap<&str, &str> = document
+        .descendants()
+        .filter(|node| node.has_tag_name("item"))
+        .filter(|item| child_text(*item, "post_type", None) == Some("attachment"))
+        .filter_map(|item| Some((child_text(item, "post_id", None)?, child_text(item, "attachment_url", None)?)))
+        .collect();
+
+    let mut posts = Vec::new();
+    for item in document.descendants().filter(|node| node.has_tag_name("item")) {
+        if child_text(item, "post_type", None) != Some("post") {
+            continue;
+        }
+        let status = child_text(item, "status", None).unwrap_or_default();
+        if status == "trash" {
+            continue;
+        }
+
+        let tags = item
+            .children()
+            .filter(|child| {
+                child.has_tag_name("category")
+                    && matches!(child.attribute("domain"), Some("post_tag" | "category"))
+            })
+            .filter_map(|child| child.text())
+            .map(|tag| decode_entities(tag.trim()))
+            .filter(|tag| !tag.is_empty() && tag != "Uncategorized")
+            .collect();
+
+        let thumbnail = item
+            .children()
+            .filter(|child| child.tag_name().name() == "postmeta")
+            .find(|meta| child_text(*meta, "meta_key", None) == Some("_thumbnail_id"))
+            .and_then(|meta| child_text(meta, "meta_value", None))
+            .and_then(|id| attachments.get(id.trim()))
+            .map(|url| url.to_string());
+
+        let id = child_text(item, "post_id", None).unwrap_or_default();
+        posts.push(ExternalPost {
+            source: "wordpress".to_string(),
+            source_id: child_text(item, "link", None)
+                .map(str::to_string)
+                .unwrap_or_else(|| id.to_string()),
+            title: decode_entities(child_text(item, "title", None).unwrap_or_default().trim()),
+            html: autop(child_text(item, "encoded", Some(CONTENT_NAMESPACE)).unwrap_or_default()),
+            excerpt: child_text(item, "encoded", Some("/excerpt/"))
+                .map(str::trim)
+                .filter(|excerpt| !excerpt.is_empty())
+                .map(str::to_string),
+            author: child_text(item, "creator", None).map(str::to_string),
+            tags,
+            published_at: child_text(item, "post_date_gmt", None).and_then(gmt_to_rfc3339),
+            feature_image: thumbnail,
+            draft: status != "publish",
+        });
+    }
+    Ok(posts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        importers::{format_body, html_to_markdown},
+        sync::parse_frontmatter,
+    };
+
+    const EXPORT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0"
+    xmlns:excerpt="http://wordpress.org/export/1.2/excerpt/"
+    xmlns:content="http://purl.org/rss/1.0/modules/content/"
+    xmlns:dc="http://purl.org/dc/elements/1.1/"
+    xmlns:wp="http://wordpress.org/export/1.2/">
+<channel>
+    <item>
+        <title>Fish &amp; Chips</title>
+        <link>https://example.com/fish-and-chips/</link>
+        <dc:creator>ana</dc:creator>
+        <content:encoded><![CDATA[Batter first.
+
+Then <img src="/wp-content/uploads/fish.jpg" />]]></content:encoded>
+        <excerpt:encoded><![CDATA[ A short one ]]></excerpt:encoded>
+        <wp:post_id>1</wp:post_id>
+        <wp:post_date_gmt>2021-03-04 05:06:07</wp:post_date_gmt>
+        <wp:status>publish</wp:status>
+        <wp:post_type>post</wp:post_type>
+        <category domain="category" nicename="uncategorized">Uncategorized</category>
+        <category domain="post_tag" nicename="food">Food &amp; Drink</category>
+        <wp:postmeta>
+            <wp:meta_key>_thumbnail_id</wp:meta_key>
+            <wp:meta_value>9</wp:meta_value>
+        </wp:postmeta>
+    </item>
+    <item>
+        <title>Cover</title>
+        <wp:post_id>9</wp:post_id>
+        <wp:post_type>attachment</wp:post_type>
+        <wp:attachment_url>https://example.com/wp-content/uploads/cover.png</wp:attachment_url>
+    </item>
+    <item>
+        <title>Unfinished</title>
+        <wp:post_id>2</wp:post_id>
+        <wp:post_date_gmt>0000-00-00 00:00:00</wp:post_date_gmt>
+        <wp:status>draft</wp:status>
+        <wp:post_type>post</wp:post_type>
+    </item>
+    <item>
+        <title>Binned</title>
+        <wp:post_id>3</wp:post_id>
+        <wp:status>trash</wp:status>
+        <wp:post_type>post</wp:post_type>
+    </item>
+    <item>
+        <title>About</title>
+        <wp:post_id>4</wp:post_id>
+        <wp:status>publish</wp:status>
+        <wp:post_type>page</wp:post_type>
+    </item>
+</channel>
+</rss>"#;
+
+    #[test]
+    fn posts_are_read_from_a_wxr_export() {
+        let posts = parse(EXPORT).unwrap();
+        let titles: Vec<&str> = posts.iter().map(|post| post.title.as_str()).collect();
+        assert_eq!(titles, ["Fish & Chips", "Unfinished"]);
+
+        let post = &posts[0];
+        assert_eq!(post.source_id, "https://example.com/fish-and-chips/");
+        assert_eq!(post.excerpt.as_deref(), Some("A short one"));
+        assert_eq!(post.tags, ["Food & Drink"]);
+        assert_eq!(post.published_at.as_deref(), Some("2021-03-04T05:06:07Z"));
+        assert_eq!(post.feature_image.as_deref(), Some("https://example.com/wp-content/uploads/cover.png"));
+        assert!(!post.draft);
+        // Classic-editor paragraphs get their markup back
+        assert!(post.html.starts_with("<p>Batter first.</p>"), "{}", post.html);
+
+        let markdown = html_to_markdown(&post.html);
+        assert!(markdown.contains("![](/wp-content/uploads/fish.jpg)"), "{}", markdown);
+        let body = format_body(post, "A short one", &markdown);
+        let (frontmatter, _) = parse_frontmatter(&body).unwrap();
+        assert_eq!(frontmatter.title.as_deref(), Some("Fish & Chips"));
+        assert_eq!(frontmatter.author.as_deref(), Some("ana"));
+        assert_eq!(frontmatter.tags, ["Food & Drink"]);
+        assert_eq!(frontmatter.created_at.as_deref(), Some("2021-03-04T05:06:07Z"));
+
+        assert!(posts[1].draft);
+        assert_eq!(posts[1].source_id, "2");
+        assert_eq!(posts[1].published_at, None);
+    }
+}
@@ -1,7 +1,31 @@
+pub mod archive;
 pub mod arweave;
 pub mod blog_client;
+pub mod events;
+pub mod feed;
+pub mod importers;
+pub mod media;
+pub mod search;
+pub mod send;
+pub mod simulate;
+pub mod site;
+pub mod storage;
+pub mod subscribe;
+pub mod sync;
 pub mod types;
 
+pub use archive::{BlogArchive, ImportProgress, ImportReport};
 pub use blog_client::BlogClient;
+pub use events::{parse_events, BlogEvent};
+pub use feed::{Feed, FeedEntry, FeedFormat, FeedOptions};
+pub use importers::{ExternalImportOptions, ExternalImportReport, ExternalPost};
 pub use types::*;
-pub use arweave::ArweaveClient; 
\ No newline at end of file
+pub use arweave::{manifest::PathManifest, upload::UploadProgress, ArweaveClient, ArweaveTag, UploadPrice, UploadQuote};
+pub use media::{MediaAsset, MediaOptions, MediaVariant, PostManifest};
+pub use search::{SearchClient, SearchQuery, SearchResult};
+pub use send::{ComputeUnitPrice, SendPolicy, SendResult};
+pub use simulate::{CostBreakdown, SimulationReport};
+pub use site::{SiteReport, StaticSite};
+pub use subscribe::{BlogSubscription, PostEvent};
+pub use storage::{ArweaveStore, ContentId, ContentStore, IpfsStore, LocalStore, StorageCost};
+pub use sync::{SyncOptions, SyncReport};
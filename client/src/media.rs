@@ -0,0 +1,338 @@
+use crate::{
+    storage::ContentId,
+    types::{BlogClientError, Result},
+};
+use image::{
+    codecs::{jpeg::JpegEncoder, png::PngEncoder},
+    imageops::FilterType,
+    DynamicImage, ImageDecoder, ImageFormat, ImageReader,
+};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+
+/// Manifest schema version written by this client
+pub const MANIFEST_VERSION: u32 = 1;
+/// Tag value identifying manifest uploads on Arweave
+pub const MANIFEST_TYPE: &str = "Post-Manifest";
+
+/// How uploaded images are re-encoded
+#[derive(Debug, Clone)]
+pub struct MediaOptions {
+    /// Target widths for resized variants; widths at or above the source
+    /// width are skipped
+    pub widths: Vec<u32>,
+    /// Also produce lossy WebP copies of each size at `jpeg_quality`, kept
+    /// when smaller than the JPEG/PNG encoding
+    pub webp: bool,
+    /// Quality of JPEG and WebP copies, 0 to 100
+    pub jpeg_quality: u8,
+    /// Longest edge of the full-size copy
+    pub max_dimension: u32,
+}
+
+impl Default for MediaOptions {
+    fn default() -> Self {
+        Self {
+            widths: vec![480, 960, 1600],
+            webp: true,
+            jpeg_quality: 82,
+            max_dimension: 2560,
+        }
+    }
+}
+
+/// One re-encoded image, ready to upload
+#[derive(Debug, Clone)]
+pub struct EncodedImage {
+    pub width: u32,
+    pub height: u32,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+/// An uploaded copy of an image
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MediaVariant {
+    pub id: ContentId,
+    pub width: u32,
+    pub height: u32,
+    pub content_type: String,
+    pub bytes: usize,
+}
+
+/// An image attached to a post, with every uploaded size and format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MediaAsset {
+    pub alt: String,
+    pub width: u32,
+    pub height: u32,
+    pub variants: Vec<MediaVariant>,
+}
+
+impl MediaAsset {
+    /// `srcset` attribute value for the variants of `content_type`, smallest first
+    pub fn srcset(&self, content_type: &str, url: impl Fn(&ContentId) -> String) -> String {
+        let mut variants: Vec<&MediaVariant> = self
+            .variants
+            .iter()
+            .filter(|variant| variant.content_type == content_type)
+            .collect();
+        variants.sort_by_key(|variant| variant.width);
+        variants
+            .iter()
+            .map(|variant| format!("{} {}w", url(&variant.id), variant.width))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Largest variant in a format every browser can display, for `src`
+    pub fn fallback(&self) -> Option<&MediaVariant> {
+        self.variants
+            .iter()
+            .filter(|variant| variant.content_type != "image/webp")
+            .max_by_key(|variant| variant.width)
+            .or_else(|| self.variants.iter().max_by_key(|variant| variant.width))
+    }
+}
+
+/// A post's `data.json`: the body plus the uploaded images
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PostManifest {
+    pub version: u32,
+    /// Post body, if one was uploaded alongside the media
+    pub content: Option<ContentId>,
+    pub media: Vec<MediaAsset>,
+}
+
+impl PostManifest {
+    pub fn new(content: Option<ContentId>, media: Vec<MediaAsset>) -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            content,
+            media,
+        }
+    }
+
+    /// Parse `data` as a manifest, returning `None` for anything else (such
+    /// as a plain post body)
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        serde_json::from_slice::<Self>(data)
+            .ok()
+            .filter(|manifest| manifest.version <= MANIFEST_VERSION)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(|e| BlogClientError {
+            message: format!("Failed to serialize post manifest: {}", e),
+        })
+    }
+}
+
+/// Check that `data` is an image of the `declared` content type, so a
+/// mislabeled upload is refused rather than stored under the wrong type
+pub fn check_content_type(data: &[u8], declared: &str) -> Result<()> {
+    let format = image::guess_format(data).map_err(|_| BlogClientError {
+        message: "Unrecognized image format".to_string(),
+    })?;
+    let actual = format.to_mime_type();
+    let declared_type = declared.split(';').next().unwrap_or_default().trim();
+    if !actual.eq_ignore_ascii_case(declared_type) {
+        return Err(BlogClientError {
+            message: format!("Image is {} but was declared as {}", actual, declared),
+        });
+    }
+    Ok(())
+}
+
+/// `process_image` on the blocking thread pool. Decoding, Lanczos resizing
+/// and encoding take long enough to stall other tasks on the runtime.
+pub async fn process_image_async(data: &[u8], options: &MediaOptions) -> Result<Vec<EncodedImage>> {
+    let data = data.to_vec();
+    let options = options.clone();
+    tokio::task::spawn_blocking(move || process_image(&data, &options))
+        .await
+        .map_err(|e| BlogClientError {
+            message: format!("Image processing failed: {}", e),
+        })?
+}
+
+/// Decode `data`, apply and drop its EXIF orientation, and re-encode it at
+/// full size and at each of `options.widths`. Re-encoding from pixels drops
+/// EXIF and any other embedded metadata. Full-size copies come first.
+pub fn process_image(data: &[u8], options: &MediaOptions) -> Result<Vec<EncodedImage>> {
+    let reader = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .map_err(|e| BlogClientError {
+            message: format!("Failed to read image: {}", e),
+        })?;
+    let format = reader.format().ok_or_else(|| BlogClientError {
+        message: "Unrecognized image format".to_string(),
+    })?;
+
+    let mut decoder = reader.into_decoder().map_err(|e| BlogClientError {
+        message: format!("Failed to decode image: {}", e),
+    })?;
+    let orientation = decoder.orientation().map_err(|e| BlogClientError {
+        message: format!("Failed to read image orientation: {}", e),
+    })?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|e| BlogClientError {
+        message: format!("Failed to decode image: {}", e),
+    })?;
+    image.apply_orientation(orientation);
+
+    if image.width().max(image.height()) > options.max_dimension {
+        image = image.resize(options.max_dimension, options.max_dimension, FilterType::Lanczos3);
+    }
+
+    // Photos stay JPEG and graphics keep their alpha as PNG. WebP sources
+    // still get one of the two, for browsers that cannot show WebP.
+    let fallback = match format {
+        ImageFormat::Jpeg => ImageFormat::Jpeg,
+        ImageFormat::WebP if !image.color().has_alpha() => ImageFormat::Jpeg,
+        _ => ImageFormat::Png,
+    };
+
+    let mut sizes = vec![image.clone()];
+    let mut widths = options.widths.clone();
+    widths.sort_unstable();
+    widths.dedup();
+    for width in widths.into_iter().filter(|width| *width < image.width()) {
+        sizes.push(image.resize(width, u32::MAX, FilterType::Lanczos3));
+    }
+
+    let mut encoded = Vec::new();
+    for size in &sizes {
+        let primary = encode(size, fallback, options.jpeg_quality)?;
+        if options.webp {
+            let webp = encode(size, ImageFormat::WebP, options.jpeg_quality)?;
+            if webp.data.len() < primary.data.len() {
+                encoded.push(webp);
+            }
+        }
+        encoded.push(primary);
+    }
+    Ok(encoded)
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat, jpeg_quality: u8) -> Result<EncodedImage> {
+    // Normalize to 8-bit RGB(A), which every encoder here accepts
+    let image = if image.color().has_alpha() && format != ImageFormat::Jpeg {
+        DynamicImage::ImageRgba8(image.to_rgba8())
+    } else {
+        DynamicImage::ImageRgb8(image.to_rgb8())
+    };
+
+    let mut data = Vec::new();
+    let result = match format {
+        ImageFormat::Jpeg => image.write_with_encoder(JpegEncoder::new_with_quality(&mut data, jpeg_quality)),
+        ImageFormat::WebP => {
+            let encoder = match &image {
+                DynamicImage::ImageRgba8(pixels) => webp::Encoder::from_rgba(pixels, image.width(), image.height()),
+                _ => webp::Encoder::from_rgb(image.as_bytes(), image.width(), image.height()),
+            };
+            data = encoder.encode(f32::from(jpeg_quality.min(100))).to_vec();
+            Ok(())
+        }
+        _ => image.write_with_encoder(PngEncoder::new(&mut data)),
+    };
+    result.map_err(|e| BlogClientError {
+        message: format!("Failed to encode {:?} image: {}", format, e),
+    })?;
+
+    Ok(EncodedImage {
+        width: image.width(),
+        height: image.height(),
+        content_type: format.to_mime_type().to_string(),
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage, Rgba, RgbaImage};
+
+    /// A noisy gradient that compresses like a photo
+    fn photo(width: u32, height: u32) -> Vec<u8> {
+        let image = RgbImage::from_fn(width, height, |x, y| {
+            let noise = ((x * 7919 + y * 104729) % 31) as u8;
+            Rgb([(x % 256) as u8 ^ noise, (y % 256) as u8, ((x + y) % 256) as u8 ^ noise])
+        });
+        let mut data = Vec::new();
+        DynamicImage::ImageRgb8(image)
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut data, 95))
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn photos_get_smaller_lossy_webp_copies() {
+        let options = MediaOptions {
+            widths: vec![200, 400, 2000],
+            ..MediaOptions::default()
+        };
+        let encoded = process_image(&photo(800, 600), &options).unwrap();
+
+        let sizes: Vec<(u32, &str)> = encoded
+            .iter()
+            .map(|image| (image.width, image.content_type.as_str()))
+            .collect();
+        assert_eq!(
+            sizes,
+            [
+                (800, "image/webp"),
+                (800, "image/jpeg"),
+                (200, "image/webp"),
+                (200, "image/jpeg"),
+                (400, "image/webp"),
+                (400, "image/jpeg"),
+            ]
+        );
+        assert_eq!(encoded[2].height, 150);
+        assert!(encoded[0].data.starts_with(b"RIFF"));
+    }
+
+    #[test]
+    fn webp_copies_can_be_turned_off() {
+        let options = MediaOptions {
+            widths: Vec::new(),
+            webp: false,
+            ..MediaOptions::default()
+        };
+        let encoded = process_image(&photo(64, 64), &options).unwrap();
+        assert_eq!(encoded.len(), 1);
+        assert_eq!(encoded[0].content_type, "image/jpeg");
+    }
+
+    #[test]
+    fn webp_sources_get_a_fallback_copy() {
+        let options = MediaOptions {
+            widths: vec![32],
+            ..MediaOptions::default()
+        };
+        let to_webp = |image: DynamicImage| encode(&image, ImageFormat::WebP, 90).unwrap().data;
+
+        let opaque = to_webp(DynamicImage::ImageRgb8(RgbImage::from_pixel(64, 64, Rgb([200, 30, 30]))));
+        let encoded = process_image(&opaque, &options).unwrap();
+        for width in [64, 32] {
+            assert!(encoded
+                .iter()
+                .any(|image| image.width == width && image.content_type == "image/jpeg"));
+        }
+
+        let transparent = to_webp(DynamicImage::ImageRgba8(RgbaImage::from_pixel(64, 64, Rgba([0, 0, 0, 0]))));
+        let encoded = process_image(&transparent, &options).unwrap();
+        assert_eq!(encoded.iter().filter(|image| image.content_type == "image/png").count(), 2);
+        assert!(encoded.iter().all(|image| image.content_type != "image/jpeg"));
+    }
+
+    #[test]
+    fn declared_content_types_must_match() {
+        let data = photo(8, 8);
+        assert!(check_content_type(&data, "image/jpeg").is_ok());
+        assert!(check_content_type(&data, "IMAGE/JPEG; q=1").is_ok());
+        let error = check_content_type(&data, "image/png").unwrap_err();
+        assert_eq!(error.message, "Image is image/jpeg but was declared as image/png");
+        assert!(check_content_type(b"not an image", "image/png").is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::types::{BlogClientError, Result};
+use serde::{Deserialize, Serialize};
+
+/// A full-text search over indexed posts. Filters are optional; dates are
+/// unix timestamps compared against the post's creation time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchQuery {
+    /// Words to find, each matching as a prefix
+    #[serde(rename = "q")]
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blog: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Created at or after
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<i64>,
+    /// Created before
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+impl SearchQuery {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_blog(mut self, blog: impl Into<String>) -> Self {
+        self.blog = Some(blog.into());
+        self
+    }
+
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    pub fn with_dates(mut self, from: Option<i64>, to: Option<i64>) -> Self {
+        self.from = from;
+        self.to = to;
+        self
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+/// One matching post, best match first. The `_html` fields are escaped
+/// HTML with matched words wrapped in `<mark>`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub post: String,
+    pub blog: String,
+    pub title: String,
+    pub title_html: String,
+    /// Excerpt of the body around the matches
+    pub snippet_html: String,
+    pub tags: Vec<String>,
+    pub created_at: i64,
+    /// Relevance; higher is better
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub results: Vec<SearchResult>,
+}
+
+/// Searches posts through a `blog-api` server
+#[derive(Debug, Clone)]
+pub struct SearchClient {
+    client: reqwest::Client,
+    api_url: String,
+}
+
+impl SearchClient {
+    pub fn new(api_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>> {
+        let response = self
+            .client
+            .get(format!("{}/search", self.api_url))
+            .query(query)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| BlogClientError {
+                message: format!("Search request failed: {}", e),
+            })?;
+        let response: SearchResponse = response.json().await.map_err(|e| BlogClientError {
+            message: format!("Invalid search response: {}", e),
+        })?;
+        Ok(response.results)
+    }
+}
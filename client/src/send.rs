@@ -0,0 +1,437 @@
+use crate::types::{BlogClientError, Result};
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_client::RpcClient,
+    rpc_config::RpcSendTransactionConfig,
+    rpc_request::{RpcError, RpcResponseErrorData},
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+use solana_sdk::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// Lamports charged per compute unit are expressed in micro-lamports
+const MICRO_LAMPORTS_PER_LAMPORT: u64 = 1_000_000;
+
+/// How often the signature status is polled while waiting for confirmation
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How the compute-unit price (priority fee) is chosen for a transaction
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComputeUnitPrice {
+    /// No `SetComputeUnitPrice` instruction is added
+    None,
+    /// A fixed price in micro-lamports per compute unit
+    Fixed(u64),
+    /// Estimated from `getRecentPrioritizationFees` for the writable accounts
+    /// of the transaction, taking the given percentile (0-100) of recent fees
+    /// and never exceeding `max_micro_lamports`
+    Auto { percentile: u8, max_micro_lamports: u64 },
+}
+
+/// Controls how `BlogClient` builds, sends and confirms transactions
+#[derive(Debug, Clone)]
+pub struct SendPolicy {
+    /// Adds a `SetComputeUnitLimit` instruction when set
+    pub compute_unit_limit: Option<u32>,
+    pub compute_unit_price: ComputeUnitPrice,
+    /// Number of additional attempts after the first send fails or expires
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Fetch a fresh blockhash (and re-sign) once the previous one has
+    /// expired; otherwise give up at that point. While the blockhash is
+    /// still valid, retries resend the same signed transaction, so it can
+    /// never land twice.
+    pub refresh_blockhash: bool,
+    /// Commitment level the transaction must reach before returning
+    pub commitment: CommitmentConfig,
+    /// Stop waiting for a sent transaction after this long. It may still
+    /// land, so this is reported as an error rather than retried.
+    pub confirm_timeout: Duration,
+}
+
+impl Default for SendPolicy {
+    fn default() -> Self {
+        Self {
+            compute_unit_limit: None,
+            compute_unit_price: ComputeUnitPrice::None,
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+            refresh_blockhash: true,
+            commitment: CommitmentConfig::confirmed(),
+            confirm_timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+impl SendPolicy {
+    /// Policy suited for congested clusters: explicit compute limit, priority
+    /// fee estimated from recent fees, and more retries
+    pub fn congested(compute_unit_limit: u32) -> Self {
+        Self {
+            compute_unit_limit: Some(compute_unit_limit),
+            compute_unit_price: ComputeUnitPrice::Auto {
+                percentile: 75,
+                max_micro_lamports: 1_000_000,
+            },
+            max_retries: 5,
+            ..Self::default()
+        }
+    }
+}
+
+/// Outcome of a confirmed transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct SendResult {
+    pub signature: Signature,
+    /// Slot the transaction landed in
+    pub slot: u64,
+    /// Total fee charged for the transaction, including the priority fee
+    pub fee_lamports: u64,
+    /// Portion of the fee paid for compute-unit price
+    pub priority_fee_lamports: u64,
+    pub compute_unit_limit: Option<u32>,
+    pub compute_unit_price: Option<u64>,
+    /// Number of send attempts made, including the successful one
+    pub attempts: u32,
+}
+
+/// Builds the compute budget instructions for `policy`, resolving an automatic
+/// price against the cluster. Returns the instructions and the chosen price.
+pub(crate) fn compute_budget_instructions(
+    rpc_client: &RpcClient,
+    policy: &SendPolicy,
+    instructions: &[Instruction],
+) -> Result<(Vec<Instruction>, Option<u64>)> {
+    let mut budget = Vec::new();
+
+    if let Some(limit) = policy.compute_unit_limit {
+        budget.push(ComputeBudgetInstruction::set_compute_unit_limit(limit));
+    }
+
+    let price = match &policy.compute_unit_price {
+        ComputeUnitPrice::None => None,
+        ComputeUnitPrice::Fixed(price) => Some(*price),
+        ComputeUnitPrice::Auto { percentile, max_micro_lamports } => {
+            let estimate = estimate_compute_unit_price(rpc_client, instructions, *percentile)?;
+            Some(estimate.min(*max_micro_lamports))
+        }
+    };
+
+    if let Some(price) = price {
+        budget.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+    }
+
+    Ok((budget, price))
+}
+
+/// Estimates a compute-unit price from the recent prioritization fees paid to
+/// write-lock the accounts used by `instructions`
+pub fn estimate_compute_unit_price(
+    rpc_client: &RpcClient,
+    instructions: &[Instruction],
+    percentile: u8,
+) -> Result<u64> {
+    let mut writable: Vec<Pubkey> = instructions
+        .iter()
+        .flat_map(|ix| ix.accounts.iter())
+        .filter(|meta| meta.is_writable)
+        .map(|meta| meta.pubkey)
+        .collect();
+    writable.sort();
+    writable.dedup();
+
+    let fees = rpc_client
+        .get_recent_prioritization_fees(&writable)
+        .map_err(|e| BlogClientError {
+            message: format!("Failed to get recent prioritization fees: {}", e),
+        })?;
+
+    let mut fees: Vec<u64> = fees.into_iter().map(|fee| fee.prioritization_fee).collect();
+    if fees.is_empty() {
+        return Ok(0);
+    }
+    fees.sort_unstable();
+
+    let percentile = percentile.min(100) as usize;
+    let index = (fees.len() - 1) * percentile / 100;
+    Ok(fees[index])
+}
+
+/// Lamports paid for the compute-unit price. The runtime charges for the
+/// requested limit, or the default of 200k units per instruction if unset.
+pub(crate) fn priority_fee_lamports(
+    compute_unit_limit: Option<u32>,
+    compute_unit_price: Option<u64>,
+    instruction_count: usize,
+) -> u64 {
+    let Some(price) = compute_unit_price else {
+        return 0;
+    };
+    let limit = compute_unit_limit
+        .map(u64::from)
+        .unwrap_or(200_000 * instruction_count as u64)
+        .min(1_400_000);
+    (limit * price).div_ceil(MICRO_LAMPORTS_PER_LAMPORT)
+}
+
+/// Sends `instructions` according to `policy`, retrying with exponential
+/// backoff when the send fails transiently or the blockhash expires before
+/// the transaction is confirmed.
+pub(crate) async fn send_with_policy(
+    rpc_client: &RpcClient,
+    policy: &SendPolicy,
+    instructions: &[Instruction],
+    payer: &Keypair,
+    signers: &[&Keypair],
+) -> Result<SendResult> {
+    let (mut all_instructions, compute_unit_price) =
+        compute_budget_instructions(rpc_client, policy, instructions)?;
+    all_instructions.extend_from_slice(instructions);
+
+    let priority_fee_lamports = priority_fee_lamports(
+        policy.compute_unit_limit,
+        compute_unit_price,
+        instructions.len(),
+    );
+    let result = |signature, slot, fee_lamports, attempts| SendResult {
+        signature,
+        slot,
+        fee_lamports,
+        priority_fee_lamports,
+        compute_unit_limit: policy.compute_unit_limit,
+        compute_unit_price,
+        attempts,
+    };
+
+    let mut backoff = policy.initial_backoff;
+    let mut blockhash: Option<Hash> = None;
+    // Every transaction signed so far with its fee. Any of them may land
+    // after a later attempt has been sent.
+    let mut sent: Vec<(Signature, u64)> = Vec::new();
+    let mut last_error = None;
+
+    for attempt in 1..=policy.max_retries + 1 {
+        // Re-signing with a new blockhash while the old one is valid could
+        // land the transaction twice, so only do it once it has expired
+        let expired = match &blockhash {
+            Some(blockhash) => !rpc_client
+                .is_blockhash_valid(blockhash, CommitmentConfig::processed())
+                .map_err(|e| BlogClientError {
+                    message: format!("Failed to check blockhash: {}", e),
+                })?,
+            None => true,
+        };
+        if expired {
+            if blockhash.is_some() && !policy.refresh_blockhash {
+                break;
+            }
+            blockhash = Some(
+                rpc_client
+                    .get_latest_blockhash()
+                    .map_err(|e| BlogClientError {
+                        message: format!("Failed to get recent blockhash: {}", e),
+                    })?,
+            );
+        }
+        let recent_blockhash = blockhash.unwrap();
+
+        let transaction = Transaction::new_signed_with_payer(
+            &all_instructions,
+            Some(&payer.pubkey()),
+            signers,
+            recent_blockhash,
+        );
+        let signature = transaction.signatures[0];
+
+        let fee_lamports = rpc_client
+            .get_fee_for_message(&transaction.message)
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to get fee for message: {}", e),
+            })?;
+        if !sent.iter().any(|(sent, _)| *sent == signature) {
+            sent.push((signature, fee_lamports));
+        }
+
+        let send_config = RpcSendTransactionConfig {
+            preflight_commitment: Some(policy.commitment.commitment),
+            // Retries are handled here so that the blockhash can be refreshed
+            max_retries: Some(0),
+            ..RpcSendTransactionConfig::default()
+        };
+
+        match rpc_client.send_transaction_with_config(&transaction, send_config) {
+            Ok(signature) => {
+                match wait_for_confirmation(rpc_client, policy, &signature, &recent_blockhash).await? {
+                    Confirmation::Confirmed(slot) => {
+                        return Ok(result(signature, slot, fee_lamports, attempt));
+                    }
+                    Confirmation::Expired => {
+                        last_error = Some(format!(
+                            "Transaction {} was not confirmed before its blockhash expired",
+                            signature
+                        ));
+                    }
+                }
+            }
+            Err(e) => {
+                // A retry can fail because an earlier attempt already landed,
+                // e.g. with "account already in use" for a new post
+                if sent.len() > 1 {
+                    if let Some((signature, slot, fee_lamports)) = find_landed(rpc_client, policy, &sent)? {
+                        return Ok(result(signature, slot, fee_lamports, attempt));
+                    }
+                }
+                if !is_retryable(&e) {
+                    return Err(BlogClientError {
+                        message: format!("Failed to send transaction: {}", e),
+                    });
+                }
+                last_error = Some(format!("Failed to send transaction: {}", e));
+            }
+        }
+
+        if attempt <= policy.max_retries {
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(policy.max_backoff);
+        }
+    }
+
+    if let Some((signature, slot, fee_lamports)) = find_landed(rpc_client, policy, &sent)? {
+        return Ok(result(signature, slot, fee_lamports, sent.len() as u32));
+    }
+    Err(BlogClientError {
+        message: format!(
+            "{} (gave up after {} attempts)",
+            last_error.unwrap_or_else(|| "Failed to send transaction".to_string()),
+            policy.max_retries + 1
+        ),
+    })
+}
+
+/// How waiting for a sent transaction ended
+enum Confirmation {
+    Confirmed(u64),
+    /// Its blockhash expired before it landed, so it never will
+    Expired,
+}
+
+/// Polls the signature status until it reaches the policy commitment or
+/// the blockhash expires. Fails if neither happens within the timeout.
+async fn wait_for_confirmation(
+    rpc_client: &RpcClient,
+    policy: &SendPolicy,
+    signature: &Signature,
+    blockhash: &Hash,
+) -> Result<Confirmation> {
+    let started = Instant::now();
+
+    loop {
+        let statuses = rpc_client
+            .get_signature_statuses(&[*signature])
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to get signature status: {}", e),
+            })?;
+
+        if let Some(Some(status)) = statuses.value.first() {
+            if let Some(err) = &status.err {
+                return Err(BlogClientError {
+                    message: format!("Transaction {} failed: {}", signature, err),
+                });
+            }
+            if status.satisfies_commitment(policy.commitment) {
+                return Ok(Confirmation::Confirmed(status.slot));
+            }
+        } else {
+            let still_valid = rpc_client
+                .is_blockhash_valid(blockhash, CommitmentConfig::processed())
+                .unwrap_or(true);
+            if !still_valid {
+                return Ok(Confirmation::Expired);
+            }
+        }
+
+        if started.elapsed() >= policy.confirm_timeout {
+            return Err(BlogClientError {
+                message: format!(
+                    "Transaction {} was not confirmed within {:?}; it may still land, so check its status before sending again",
+                    signature, policy.confirm_timeout
+                ),
+            });
+        }
+
+        tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await;
+    }
+}
+
+/// The first of `sent` that landed successfully, with its slot and fee
+fn find_landed(
+    rpc_client: &RpcClient,
+    policy: &SendPolicy,
+    sent: &[(Signature, u64)],
+) -> Result<Option<(Signature, u64, u64)>> {
+    let signatures: Vec<Signature> = sent.iter().map(|(signature, _)| *signature).collect();
+    let statuses = rpc_client
+        .get_signature_statuses_with_history(&signatures)
+        .map_err(|e| BlogClientError {
+            message: format!("Failed to get signature status: {}", e),
+        })?;
+
+    Ok(sent
+        .iter()
+        .zip(statuses.value)
+        .find_map(|((signature, fee_lamports), status)| {
+            let status = status?;
+            (status.err.is_none() && status.satisfies_commitment(policy.commitment))
+                .then_some((*signature, status.slot, *fee_lamports))
+        }))
+}
+
+/// Preflight simulation failures and transaction errors are deterministic, so
+/// resending the same transaction would fail the same way
+fn is_retryable(error: &ClientError) -> bool {
+    !matches!(
+        error.kind(),
+        ClientErrorKind::TransactionError(_)
+            | ClientErrorKind::SigningError(_)
+            | ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                data: RpcResponseErrorData::SendTransactionPreflightFailure(_),
+                ..
+            })
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn priority_fee_is_zero_without_a_price() {
+        assert_eq!(priority_fee_lamports(Some(200_000), None, 1), 0);
+    }
+
+    #[test]
+    fn priority_fee_uses_the_requested_limit() {
+        // 50k units at 1000 micro-lamports each is 50 lamports
+        assert_eq!(priority_fee_lamports(Some(50_000), Some(1_000), 3), 50);
+    }
+
+    #[test]
+    fn priority_fee_defaults_to_200k_units_per_instruction() {
+        assert_eq!(priority_fee_lamports(None, Some(1_000_000), 2), 400_000);
+    }
+
+    #[test]
+    fn priority_fee_caps_the_limit_and_rounds_up() {
+        assert_eq!(priority_fee_lamports(Some(2_000_000), Some(1_000_000), 1), 1_400_000);
+        assert_eq!(priority_fee_lamports(Some(1), Some(1), 1), 1);
+    }
+}
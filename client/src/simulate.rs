@@ -0,0 +1,272 @@
+use crate::{
+    send::{self, SendPolicy},
+    types::{BlogClientError, Result},
+};
+use serde::{Deserialize, Serialize};
+use solana_client::{rpc_client::RpcClient, rpc_config::RpcSimulateTransactionConfig};
+use solana_sdk::{
+    compute_budget,
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+
+/// What publishing will cost, split by where the money goes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CostBreakdown {
+    /// Rent-exempt deposit for new accounts (zero for updates)
+    pub rent_lamports: u64,
+    /// Transaction fee, including the priority fee
+    pub fee_lamports: u64,
+    /// Portion of `fee_lamports` paid for compute-unit price
+    pub priority_fee_lamports: u64,
+    /// Bytes that will be uploaded to Arweave
+    pub arweave_bytes: usize,
+    /// Storage price for `arweave_bytes` when an Arweave wallet pays for the
+    /// upload; zero when it is bundled or the store has no per-upload cost
+    pub arweave_winston: u64,
+    /// Bundler price for `arweave_bytes` when the upload is bundled, drawn
+    /// from the author's funded bundler balance rather than this transaction
+    #[serde(default)]
+    pub arweave_bundler_lamports: u64,
+}
+
+impl CostBreakdown {
+    /// Total SOL cost in lamports (the Arweave upload is paid separately)
+    pub fn total_lamports(&self) -> u64 {
+        self.rent_lamports + self.fee_lamports
+    }
+}
+
+/// Result of simulating a transaction against the current cluster state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationReport {
+    pub success: bool,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+    /// Human readable reason the transaction would fail
+    pub error: Option<String>,
+    pub cost: CostBreakdown,
+}
+
+/// Simulates `instructions` for `program_id` with the compute budget
+/// `policy` would add and fills in the fee part of `cost`
+pub(crate) fn simulate_instructions(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    policy: &SendPolicy,
+    instructions: &[Instruction],
+    payer: &Keypair,
+    signers: &[&Keypair],
+    mut cost: CostBreakdown,
+) -> Result<SimulationReport> {
+    let (mut all_instructions, compute_unit_price) =
+        send::compute_budget_instructions(rpc_client, policy, instructions)?;
+    all_instructions.extend_from_slice(instructions);
+
+    let recent_blockhash = rpc_client
+        .get_latest_blockhash()
+        .map_err(|e| BlogClientError {
+            message: format!("Failed to get recent blockhash: {}", e),
+        })?;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &all_instructions,
+        Some(&payer.pubkey()),
+        signers,
+        recent_blockhash,
+    );
+
+    cost.fee_lamports = rpc_client
+        .get_fee_for_message(&transaction.message)
+        .map_err(|e| BlogClientError {
+            message: format!("Failed to get fee for message: {}", e),
+        })?;
+    cost.priority_fee_lamports = send::priority_fee_lamports(
+        policy.compute_unit_limit,
+        compute_unit_price,
+        instructions.len(),
+    );
+
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        commitment: Some(policy.commitment),
+        ..RpcSimulateTransactionConfig::default()
+    };
+
+    let result = rpc_client
+        .simulate_transaction_with_config(&transaction, config)
+        .map_err(|e| BlogClientError {
+            message: format!("Failed to simulate transaction: {}", e),
+        })?
+        .value;
+
+    Ok(SimulationReport {
+        success: result.err.is_none(),
+        logs: result.logs.unwrap_or_default(),
+        units_consumed: result.units_consumed,
+        error: result
+            .err
+            .as_ref()
+            .map(|err| decode_transaction_error(err, &all_instructions, program_id)),
+        cost,
+    })
+}
+
+/// Explains a transaction error in terms of the blog program. `instructions`
+/// are the transaction's instructions, compute budget ones included; the
+/// failing one's program decides how its error is read.
+pub fn decode_transaction_error(
+    error: &TransactionError,
+    instructions: &[Instruction],
+    program_id: &Pubkey,
+) -> String {
+    match error {
+        TransactionError::InstructionError(index, instruction_error) => {
+            let index = *index as usize;
+            let Some(program) = instructions.get(index).map(|instruction| instruction.program_id) else {
+                return format!("Instruction {} failed: {}", index, instruction_error);
+            };
+            if program == compute_budget::id() {
+                return format!("Compute budget instruction failed: {}", instruction_error);
+            }
+            // Numbered among the caller's instructions, as budget ones come first
+            let number = index
+                - instructions
+                    .iter()
+                    .take_while(|instruction| instruction.program_id == compute_budget::id())
+                    .count();
+
+            let reason = if program == *program_id {
+                blog_error(instruction_error)
+            } else if program == system_program::id() {
+                match instruction_error {
+                    InstructionError::Custom(code) => system_error(*code),
+                    other => other.to_string(),
+                }
+            } else {
+                format!("program {}: {}", program, instruction_error)
+            };
+            format!("Instruction {} failed: {}", number, reason)
+        }
+        TransactionError::InsufficientFundsForFee => {
+            "The fee payer cannot cover the transaction fee".to_string()
+        }
+        TransactionError::InsufficientFundsForRent { .. } => {
+            "The fee payer would be left below the rent-exempt minimum".to_string()
+        }
+        TransactionError::AccountNotFound => {
+            "The fee payer account does not exist on this cluster".to_string()
+        }
+        other => other.to_string(),
+    }
+}
+
+fn blog_error(error: &InstructionError) -> String {
+    match error {
+        InstructionError::InvalidInstructionData => {
+            "title, content or Arweave hash exceeds its maximum length".to_string()
+        }
+        InstructionError::MissingRequiredSignature => {
+            "the author did not sign the transaction".to_string()
+        }
+        InstructionError::InvalidAccountData => {
+            "the signer is not the author of this post".to_string()
+        }
+        InstructionError::AccountDataTooSmall | InstructionError::BorshIoError(_) => {
+            "the post account is too small for the new title, content or hash".to_string()
+        }
+        // The program has no custom errors of its own; these come from the
+        // System Program calls it makes to create and fund accounts
+        InstructionError::Custom(code) => system_error(*code),
+        InstructionError::ComputationalBudgetExceeded => {
+            "the compute unit limit is too low".to_string()
+        }
+        other => other.to_string(),
+    }
+}
+
+fn system_error(code: u32) -> String {
+    match code {
+        0 => "the account being created already exists".to_string(),
+        1 => "the payer cannot cover the transfer or rent-exempt deposit".to_string(),
+        code => format!("system program error {}", code),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{compute_budget::ComputeBudgetInstruction, system_instruction};
+
+    fn blog_instruction(program_id: Pubkey) -> Instruction {
+        Instruction::new_with_bytes(program_id, &[], vec![])
+    }
+
+    fn instruction_error(index: u8, error: InstructionError) -> TransactionError {
+        TransactionError::InstructionError(index, error)
+    }
+
+    #[test]
+    fn custom_errors_are_read_by_failing_program() {
+        let program_id = Pubkey::new_unique();
+        let other_program = Pubkey::new_unique();
+        let instructions = [
+            ComputeBudgetInstruction::set_compute_unit_limit(100_000),
+            blog_instruction(program_id),
+            Instruction::new_with_bytes(other_program, &[], vec![]),
+            system_instruction::transfer(&Pubkey::new_unique(), &Pubkey::new_unique(), 1),
+        ];
+
+        assert_eq!(
+            decode_transaction_error(&instruction_error(1, InstructionError::Custom(0)), &instructions, &program_id),
+            "Instruction 0 failed: the account being created already exists"
+        );
+        assert_eq!(
+            decode_transaction_error(&instruction_error(2, InstructionError::Custom(0)), &instructions, &program_id),
+            format!("Instruction 1 failed: program {}: custom program error: 0x0", other_program)
+        );
+        assert_eq!(
+            decode_transaction_error(&instruction_error(3, InstructionError::Custom(1)), &instructions, &program_id),
+            "Instruction 2 failed: the payer cannot cover the transfer or rent-exempt deposit"
+        );
+    }
+
+    #[test]
+    fn compute_budget_failures_are_reported_as_such() {
+        let program_id = Pubkey::new_unique();
+        let instructions = [
+            ComputeBudgetInstruction::set_compute_unit_limit(100_000),
+            blog_instruction(program_id),
+        ];
+        assert_eq!(
+            decode_transaction_error(
+                &instruction_error(0, InstructionError::InvalidInstructionData),
+                &instructions,
+                &program_id
+            ),
+            "Compute budget instruction failed: invalid instruction data"
+        );
+    }
+
+    #[test]
+    fn blog_program_errors_are_explained() {
+        let program_id = Pubkey::new_unique();
+        let instructions = [blog_instruction(program_id)];
+        assert_eq!(
+            decode_transaction_error(
+                &instruction_error(0, InstructionError::MissingRequiredSignature),
+                &instructions,
+                &program_id
+            ),
+            "Instruction 0 failed: the author did not sign the transaction"
+        );
+        assert_eq!(
+            decode_transaction_error(&TransactionError::InsufficientFundsForFee, &instructions, &program_id),
+            "The fee payer cannot cover the transaction fee"
+        );
+    }
+}
@@ -0,0 +1,601 @@
+use crate::{
+    blog_client::{BlogClient, POST_INDEX_PATH, POST_MEDIA_PATH},
+    feed::{Feed, FeedEntry, FeedFormat, FeedOptions},
+    media::PostManifest,
+    storage::ContentId,
+    sync::parse_frontmatter,
+    types::{BlogClientError, BlogInfo, PostInfo, Result},
+};
+use chrono::DateTime;
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Written into every generated page, so rebuilds only ever replace or
+/// remove pages they made
+const GENERATED_MARKER: &str = "<!-- generated by blogctl build-site -->";
+/// Where an existing single-page app shell at `index.html` is moved
+const APP_DIR: &str = "app";
+/// Characters of the on-chain summary used as a page description
+const DESCRIPTION_LENGTH: usize = 200;
+
+/// A post with everything its static page needs
+#[derive(Debug, Clone, PartialEq)]
+pub struct SitePost {
+    pub info: PostInfo,
+    /// Directory name under `posts/`, unique within the site
+    pub slug: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    /// Rendered body, with links to the post's own files resolved
+    pub html: String,
+    /// Cover image URL, for the page and its OpenGraph card
+    pub image_url: Option<String>,
+    /// Where the post's content lives, such as its Arweave manifest
+    pub source_url: String,
+}
+
+/// A complete static rendering of one blog
+#[derive(Debug, Clone, PartialEq)]
+pub struct StaticSite {
+    pub blog: BlogInfo,
+    /// Newest first
+    pub posts: Vec<SitePost>,
+    /// Public URL the site is served from, for canonical and OpenGraph links
+    pub site_url: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SiteReport {
+    /// Files written, relative to the site directory
+    pub written: Vec<PathBuf>,
+    /// Previously generated pages removed because their post or tag is gone
+    pub removed: Vec<PathBuf>,
+    /// Set when an app shell at `index.html` was moved to `app/index.html`
+    pub moved_app: bool,
+}
+
+/// Lowercase ASCII words joined by dashes, such as `hello-world`
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() { "post".to_string() } else { slug.to_string() }
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn date(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Lowercased scheme of `link`, if it has one. Browsers ignore whitespace
+/// and control characters in it, so `java\tscript:` counts as `javascript`.
+fn link_scheme(link: &str) -> Option<String> {
+    let link: String = link.chars().filter(|c| !c.is_ascii_whitespace() && !c.is_ascii_control()).collect();
+    let end = link.find([':', '/', '?', '#'])?;
+    let scheme = &link[..end];
+    let valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    (link[end..].starts_with(':') && valid).then(|| scheme.to_ascii_lowercase())
+}
+
+fn is_relative(link: &str) -> bool {
+    !(link.is_empty() || link.starts_with('#') || link.starts_with('/') || link_scheme(link).is_some())
+}
+
+/// Whether a page may link to `link`: only web and mail schemes, so a post
+/// cannot carry `javascript:` or `data:` URLs
+fn is_safe_link(link: &str) -> bool {
+    link_scheme(link).is_none_or(|scheme| matches!(scheme.as_str(), "http" | "https" | "mailto"))
+}
+
+/// Render markdown to HTML, passing relative link and image targets through
+/// `resolve`; targets it cannot resolve are left as they are. Raw HTML in
+/// the markdown is shown as text, and links and images with other schemes
+/// are dropped, keeping their text.
+pub fn markdown_to_html(markdown: &str, resolve: impl Fn(&str) -> Option<String>) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_FOOTNOTES;
+    let resolve_link = |link: CowStr<'_>| -> String {
+        if is_relative(&link) {
+            resolve(&link).unwrap_or_else(|| link.to_string())
+        } else {
+            link.to_string()
+        }
+    };
+    let events = Parser::new_ext(markdown, options).filter_map(|event| match event {
+        Event::Html(html) => Some(Event::Text(html)),
+        Event::Start(Tag::Link(_, ref link, _) | Tag::Image(_, ref link, _))
+        | Event::End(Tag::Link(_, ref link, _) | Tag::Image(_, ref link, _))
+            if !is_safe_link(link) =>
+        {
+            None
+        }
+        Event::Start(Tag::Link(kind, link, title)) => {
+            Some(Event::Start(Tag::Link(kind, resolve_link(link).into(), title)))
+        }
+        Event::Start(Tag::Image(kind, link, title)) => {
+            Some(Event::Start(Tag::Image(kind, resolve_link(link).into(), title)))
+        }
+        event => Some(event),
+    });
+    let mut output = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut output, events);
+    output
+}
+
+/// Page metadata for `page`
+struct Meta<'a> {
+    title: &'a str,
+    description: &'a str,
+    /// Path of the page below the site root, such as `posts/hello/`
+    path: &'a str,
+    og_type: &'a str,
+    image: Option<&'a str>,
+}
+
+impl StaticSite {
+    /// Order posts newest first and give each a unique slug
+    pub fn new(blog: BlogInfo, mut posts: Vec<SitePost>, site_url: &str) -> Self {
+        posts.sort_by_key(|post| std::cmp::Reverse(post.info.created_at));
+        // Older posts keep the plain slug; newer duplicates get a pubkey suffix
+        let mut taken = BTreeSet::new();
+        for post in posts.iter_mut().rev() {
+            if !taken.insert(post.slug.clone()) {
+                let pubkey = post.info.pubkey.to_string();
+                post.slug = format!("{}-{}", post.slug, &pubkey[..8.min(pubkey.len())].to_lowercase());
+                taken.insert(post.slug.clone());
+            }
+        }
+        Self {
+            blog,
+            posts,
+            site_url: site_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.site_url, path)
+    }
+
+    fn feed_dir(&self) -> String {
+        format!("feeds/{}", self.blog.pubkey)
+    }
+
+    /// Tags and their posts, newest first, keyed by tag slug
+    fn tags(&self) -> BTreeMap<String, (String, Vec<&SitePost>)> {
+        let mut tags: BTreeMap<String, (String, Vec<&SitePost>)> = BTreeMap::new();
+        for post in &self.posts {
+            for tag in &post.tags {
+                tags.entry(slugify(tag))
+                    .or_insert_with(|| (tag.clone(), Vec::new()))
+                    .1
+                    .push(post);
+            }
+        }
+        tags
+    }
+
+    fn page(&self, meta: Meta<'_>, body: &str, has_app: bool) -> String {
+        let root = "../".repeat(meta.path.matches('/').count());
+        let root = if root.is_empty() { "./".to_string() } else { root };
+        let url = self.url(meta.path);
+        let feed_dir = self.feed_dir();
+
+        let mut head = format!(
+            "<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+             <title>{title}</title>\n<meta name=\"description\" content=\"{description}\">\n\
+             <link rel=\"canonical\" href=\"{url}\">\n\
+             <meta property=\"og:site_name\" content=\"{site}\">\n<meta property=\"og:title\" content=\"{title}\">\n\
+             <meta property=\"og:description\" content=\"{description}\">\n<meta property=\"og:type\" content=\"{og_type}\">\n\
+             <meta property=\"og:url\" content=\"{url}\">\n",
+            title = html_escape(meta.title),
+            description = html_escape(meta.description),
+            url = html_escape(&url),
+            site = html_escape(&self.blog.title),
+            og_type = meta.og_type,
+        );
+        match meta.image {
+            Some(image) => head.push_str(&format!(
+                "<meta property=\"og:image\" content=\"{}\">\n<meta name=\"twitter:card\" content=\"summary_large_image\">\n",
+                html_escape(image)
+            )),
+            None => head.push_str("<meta name=\"twitter:card\" content=\"summary\">\n"),
+        }
+        for format in FeedFormat::ALL {
+            head.push_str(&format!(
+                "<link rel=\"alternate\" type=\"{}\" title=\"{}\" href=\"{}{}/{}\">\n",
+                format.content_type(),
+                html_escape(&self.blog.title),
+                root,
+                feed_dir,
+                format.file_name()
+            ));
+        }
+
+        let app_link = if has_app {
+            format!(" <a href=\"{}{}/\">app</a>", root, APP_DIR)
+        } else {
+            String::new()
+        };
+        format!(
+            "<!DOCTYPE html>\n{marker}\n<html lang=\"en\">\n<head>\n{head}<style>\n{style}</style>\n</head>\n<body>\n\
+             <header><a href=\"{root}\">{site}</a> <nav><a href=\"{root}tags/\">tags</a> \
+             <a href=\"{root}{feed_dir}/rss.xml\">rss</a>{app_link}</nav></header>\n\
+             <main>\n{body}</main>\n</body>\n</html>\n",
+            marker = GENERATED_MARKER,
+            head = head,
+            style = STYLE,
+            root = root,
+            site = html_escape(&self.blog.title),
+            feed_dir = feed_dir,
+            app_link = app_link,
+            body = body,
+        )
+    }
+
+    fn post_list(&self, posts: &[&SitePost], root: &str) -> String {
+        let mut html = String::from("<ul class=\"posts\">\n");
+        for post in posts {
+            html.push_str(&format!(
+                "<li><time datetime=\"{date}\">{date}</time> <a href=\"{root}posts/{slug}/\">{title}</a>\
+                 <p>{description}</p></li>\n",
+                date = date(post.info.created_at),
+                root = root,
+                slug = post.slug,
+                title = html_escape(&post.info.title),
+                description = html_escape(&post.description),
+            ));
+        }
+        html.push_str("</ul>\n");
+        html
+    }
+
+    fn tag_links(&self, tags: &[String], root: &str) -> String {
+        tags.iter()
+            .map(|tag| format!("<a class=\"tag\" href=\"{}tags/{}/\">#{}</a>", root, slugify(tag), html_escape(tag)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn sitemap(&self, pages: &[(String, i64)]) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+        );
+        for (path, updated_at) in pages {
+            xml.push_str(&format!(
+                "<url><loc>{}</loc><lastmod>{}</lastmod></url>\n",
+                html_escape(&self.url(path)),
+                date(*updated_at)
+            ));
+        }
+        xml.push_str("</urlset>\n");
+        xml
+    }
+
+    /// Every file of the site as (path below the site root, contents)
+    pub fn render(&self) -> Vec<(String, String)> {
+        self.render_pages(false)
+    }
+
+    /// `has_app` adds a link to the app shell in `app/` to every page
+    fn render_pages(&self, has_app: bool) -> Vec<(String, String)> {
+        let mut files = Vec::new();
+        let mut sitemap = Vec::new();
+        let all: Vec<&SitePost> = self.posts.iter().collect();
+        let updated_at = |posts: &[&SitePost]| {
+            posts
+                .iter()
+                .map(|post| post.info.updated_at.max(post.info.created_at))
+                .max()
+                .unwrap_or(self.blog.created_at)
+        };
+
+        let index = format!(
+            "<h1>{}</h1>\n<p class=\"lead\">{}</p>\n{}",
+            html_escape(&self.blog.title),
+            html_escape(&self.blog.description),
+            self.post_list(&all, "./")
+        );
+        files.push((
+            "index.html".to_string(),
+            self.page(
+                Meta {
+                    title: &self.blog.title,
+                    description: &self.blog.description,
+                    path: "",
+                    og_type: "website",
+                    image: None,
+                },
+                &index,
+                has_app,
+            ),
+        ));
+        sitemap.push((String::new(), updated_at(&all)));
+
+        for post in &self.posts {
+            let path = format!("posts/{}/", post.slug);
+            let mut body = format!(
+                "<article>\n<h1>{}</h1>\n<p class=\"meta\"><time datetime=\"{date}\">{date}</time> {}</p>\n",
+                html_escape(&post.info.title),
+                self.tag_links(&post.tags, "../../"),
+                date = date(post.info.created_at),
+            );
+            if let Some(image) = &post.image_url {
+                body.push_str(&format!(
+                    "<img class=\"cover\" src=\"{}\" alt=\"{}\">\n",
+                    html_escape(image),
+                    html_escape(&post.info.title)
+                ));
+            }
+            body.push_str(&post.html);
+            body.push_str(&format!(
+                "<p class=\"meta\">Stored on chain as <code>{}</code> · <a href=\"{}\">source</a></p>\n</article>\n",
+                post.info.pubkey,
+                html_escape(&post.source_url)
+            ));
+            files.push((
+                format!("{}index.html", path),
+                self.page(
+                    Meta {
+                        title: &post.info.title,
+                        description: &post.description,
+                        path: &path,
+                        og_type: "article",
+                        image: post.image_url.as_deref(),
+                    },
+                    &body,
+                    has_app,
+                ),
+            ));
+            sitemap.push((path, post.info.updated_at.max(post.info.created_at)));
+        }
+
+        let tags = self.tags();
+        let mut tag_index = String::from("<h1>Tags</h1>\n<ul class=\"tags\">\n");
+        for (slug, (tag, posts)) in &tags {
+            tag_index.push_str(&format!(
+                "<li><a href=\"{}/\">#{}</a> ({})</li>\n",
+                slug,
+                html_escape(tag),
+                posts.len()
+            ));
+            let path = format!("tags/{}/", slug);
+            let title = format!("#{}", tag);
+            let body = format!("<h1>{}</h1>\n{}", html_escape(&title), self.post_list(posts, "../../"));
+            let description = format!("Posts tagged {} on {}", tag, self.blog.title);
+            files.push((
+                format!("{}index.html", path),
+                self.page(
+                    Meta {
+                        title: &title,
+                        description: &description,
+                        path: &path,
+                        og_type: "website",
+                        image: None,
+                    },
+                    &body,
+                    has_app,
+                ),
+            ));
+            sitemap.push((path, updated_at(posts)));
+        }
+        tag_index.push_str("</ul>\n");
+        let description = format!("Tags on {}", self.blog.title);
+        files.push((
+            "tags/index.html".to_string(),
+            self.page(
+                Meta {
+                    title: "Tags",
+                    description: &description,
+                    path: "tags/",
+                    og_type: "website",
+                    image: None,
+                },
+                &tag_index,
+                has_app,
+            ),
+        ));
+
+        // Feeds link to the static pages rather than raw content
+        let feed = Feed::new(
+            self.blog.clone(),
+            self.posts
+                .iter()
+                .map(|post| FeedEntry {
+                    post: post.info.clone(),
+                    url: self.url(&format!("posts/{}/", post.slug)),
+                    tags: post.tags.clone(),
+                })
+                .collect(),
+            FeedOptions {
+                site_url: Some(self.url("")),
+                feed_url: Some(self.url(&self.feed_dir())),
+                limit: 0,
+            },
+        );
+        for format in FeedFormat::ALL {
+            files.push((format!("{}/{}", self.feed_dir(), format.file_name()), feed.render(format)));
+        }
+
+        files.push(("sitemap.xml".to_string(), self.sitemap(&sitemap)));
+        files
+    }
+
+    /// Write the site into `dir`. An app shell already at `index.html` is
+    /// moved to `app/index.html`, and generated post and tag pages that no
+    /// longer exist are removed; nothing else in `dir` is touched.
+    pub fn write(&self, dir: &Path) -> Result<SiteReport> {
+        let io_error = |action: &str, path: &Path, e: std::io::Error| BlogClientError {
+            message: format!("Failed to {} {}: {}", action, path.display(), e),
+        };
+        let mut report = SiteReport::default();
+
+        let index = dir.join("index.html");
+        if let Ok(existing) = fs::read_to_string(&index) {
+            let app_index = dir.join(APP_DIR).join("index.html");
+            if !existing.contains(GENERATED_MARKER) {
+                fs::create_dir_all(dir.join(APP_DIR)).map_err(|e| io_error("create", &dir.join(APP_DIR), e))?;
+                fs::rename(&index, &app_index).map_err(|e| io_error("move", &index, e))?;
+                report.moved_app = true;
+            }
+        }
+
+        let files = self.render_pages(dir.join(APP_DIR).join("index.html").exists());
+        for section in ["posts", "tags"] {
+            let Ok(entries) = fs::read_dir(dir.join(section)) else { continue };
+            for entry in entries.flatten() {
+                let page_dir = entry.path();
+                let relative = format!("{}/{}/index.html", section, entry.file_name().to_string_lossy());
+                let generated = fs::read_to_string(page_dir.join("index.html"))
+                    .is_ok_and(|page| page.contains(GENERATED_MARKER));
+                if generated && !files.iter().any(|(path, _)| *path == relative) {
+                    fs::remove_dir_all(&page_dir).map_err(|e| io_error("remove", &page_dir, e))?;
+                    report.removed.push(PathBuf::from(section).join(entry.file_name()));
+                }
+            }
+        }
+
+        for (path, contents) in files {
+            let target = dir.join(&path);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|e| io_error("create", parent, e))?;
+            }
+            fs::write(&target, contents).map_err(|e| io_error("write", &target, e))?;
+            report.written.push(PathBuf::from(path));
+        }
+        Ok(report)
+    }
+}
+
+const STYLE: &str = "body{margin:0;background:#000;color:#fff;font-family:'JetBrains Mono','IBM Plex Mono',Consolas,monospace;line-height:1.6}
+header{max-width:48rem;margin:0 auto;padding:1rem;display:flex;justify-content:space-between;border-bottom:1px solid #1f2937}
+header a{color:#fff;text-decoration:none}nav a{color:#9ca3af;margin-left:1rem;text-transform:uppercase;font-size:.8rem}
+main{max-width:48rem;margin:0 auto;padding:2rem 1rem}a{color:#93c5fd}.lead,.meta,li p{color:#9ca3af}
+.meta{font-size:.8rem;text-transform:uppercase}.tag{margin-right:.5rem}.cover,article img{max-width:100%}
+ul.posts{list-style:none;padding:0}ul.posts li{border-bottom:1px solid #1f2937;padding:1rem 0}
+pre{background:#111827;padding:1rem;overflow-x:auto}time{color:#6b7280;margin-right:.5rem}
+";
+
+impl BlogClient {
+    /// Fetch `blog` and the body, tags and cover image of every post, and
+    /// render them as a static site served from `site_url`. Posts whose
+    /// body cannot be fetched are rendered from their on-chain summary.
+    pub async fn build_site(&self, blog_pubkey: Pubkey, site_url: &str) -> Result<StaticSite> {
+        let blog = self.get_blog(blog_pubkey).await?;
+        let posts = self.get_posts_by_blog(blog_pubkey).await?;
+
+        let mut site_posts = Vec::with_capacity(posts.len());
+        for post in posts {
+            let manifest = self.get_post_manifest(&post.arweave_hash).await.ok();
+            let body = match manifest.as_ref().and_then(|manifest| manifest.get(POST_INDEX_PATH)) {
+                Some(id) => self
+                    .content_store()
+                    .get(&ContentId::from(id))
+                    .await
+                    .ok()
+                    .map(|body| String::from_utf8_lossy(&body).replace("\r\n", "\n")),
+                None => None,
+            };
+            let cover = match manifest.as_ref().and_then(|manifest| manifest.get(POST_MEDIA_PATH)) {
+                Some(id) => match self.content_store().get(&ContentId::from(id)).await {
+                    Ok(data) => PostManifest::from_bytes(&data)
+                        .and_then(|media| media.media.into_iter().next())
+                        .and_then(|asset| asset.fallback().map(|variant| self.content_store().url(&variant.id))),
+                    Err(_) => None,
+                },
+                None => None,
+            };
+
+            let (frontmatter, markdown) = match body.as_deref().map(|body| (parse_frontmatter(body), body)) {
+                Some((Some((frontmatter, rest)), _)) => (Some(frontmatter), rest.to_string()),
+                Some((None, body)) => (None, body.to_string()),
+                None => (None, post.content.clone()),
+            };
+            let html = markdown_to_html(&markdown, |link| {
+                manifest
+                    .as_ref()
+                    .and_then(|manifest| self.resolve_post_link(manifest, POST_INDEX_PATH, link))
+            });
+            let description = frontmatter
+                .as_ref()
+                .and_then(|frontmatter| frontmatter.description.clone())
+                .filter(|description| !description.is_empty())
+                .unwrap_or_else(|| crate::sync::truncate(&post.content, DESCRIPTION_LENGTH).to_string());
+
+            site_posts.push(SitePost {
+                slug: slugify(&post.title),
+                description,
+                tags: frontmatter.map(|frontmatter| frontmatter.tags).unwrap_or_default(),
+                html,
+                image_url: cover,
+                source_url: self.get_arweave_url(&post.arweave_hash),
+                info: post,
+            });
+        }
+
+        Ok(StaticSite::new(blog, site_posts, site_url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(markdown: &str) -> String {
+        markdown_to_html(markdown, |link| Some(format!("https://arweave.net/{}", link)))
+    }
+
+    #[test]
+    fn raw_html_is_shown_as_text() {
+        let html = render("<script>alert(1)</script>\n\nHi <img src=x onerror=alert(1)>");
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("<img"));
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(html.contains("Hi &lt;img src=x onerror=alert(1)&gt;"));
+    }
+
+    #[test]
+    fn only_web_and_mail_links_are_kept() {
+        let html = render(
+            "[a](javascript:alert(1)) [b](JavaScript:alert(1)) [c](<java\tscript:alert(1)>) \
+             [d](data:text/html,hi) ![e](vbscript:x) [f](https://example.com) [g](mailto:me@example.com) \
+             [h](#notes) [i](files/notes.txt)",
+        );
+        for scheme in ["javascript", "JavaScript", "script:", "data:", "vbscript"] {
+            assert!(!html.contains(scheme), "{} kept in {}", scheme, html);
+        }
+        assert!(html.contains("<p>a b c d e "));
+        assert!(html.contains("<a href=\"https://example.com\">f</a>"));
+        assert!(html.contains("<a href=\"mailto:me@example.com\">g</a>"));
+        assert!(html.contains("<a href=\"#notes\">h</a>"));
+        assert!(html.contains("<a href=\"https://arweave.net/files/notes.txt\">i</a>"));
+    }
+
+    #[test]
+    fn schemes_are_read_as_browsers_read_them() {
+        assert_eq!(link_scheme("HTTPS://example.com").as_deref(), Some("https"));
+        assert_eq!(link_scheme(" java\nscript:x").as_deref(), Some("javascript"));
+        assert_eq!(link_scheme("files/a:b"), None);
+        assert_eq!(link_scheme("notes.txt"), None);
+        assert_eq!(link_scheme("1x:y"), None);
+    }
+}
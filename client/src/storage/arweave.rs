@@ -0,0 +1,69 @@
+use super::{ContentId, ContentStore, StorageCost};
+use crate::{
+    arweave::{ArweaveClient, ArweaveTag, UploadPrice},
+    types::Result,
+};
+use async_trait::async_trait;
+use solana_sdk::signature::Keypair;
+
+/// Permanent storage on Arweave through an `ArweaveClient`
+#[derive(Debug, Clone, Default)]
+pub struct ArweaveStore {
+    client: ArweaveClient,
+}
+
+impl ArweaveStore {
+    pub fn new(client: ArweaveClient) -> Self {
+        Self { client }
+    }
+
+    pub fn client(&self) -> &ArweaveClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl ContentStore for ArweaveStore {
+    async fn put(&self, data: &[u8], content_type: &str, tags: &[ArweaveTag]) -> Result<ContentId> {
+        self.client
+            .upload_tagged(data, content_type, tags, None)
+            .await
+            .map(ContentId)
+    }
+
+    async fn put_signed(
+        &self,
+        data: &[u8],
+        content_type: &str,
+        tags: &[ArweaveTag],
+        signer: &Keypair,
+    ) -> Result<ContentId> {
+        self.client
+            .upload_tagged(data, content_type, tags, Some(signer))
+            .await
+            .map(ContentId)
+    }
+
+    /// Bytes as uploaded, so `get_raw` needs no override: only those can
+    /// be checked against the signed header
+    async fn get(&self, id: &ContentId) -> Result<Vec<u8>> {
+        self.client.get_data(id.as_str()).await
+    }
+
+    fn url(&self, id: &ContentId) -> String {
+        self.client.get_url(id.as_str())
+    }
+
+    async fn storage_cost(&self, bytes: usize) -> Result<StorageCost> {
+        Ok(match self.client.upload_price(bytes).await? {
+            UploadPrice::Winston(winston) => StorageCost {
+                winston,
+                ..StorageCost::default()
+            },
+            UploadPrice::Lamports(bundler_lamports) => StorageCost {
+                bundler_lamports,
+                ..StorageCost::default()
+            },
+        })
+    }
+}
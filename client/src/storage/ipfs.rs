@@ -0,0 +1,129 @@
+use super::{ContentId, ContentStore};
+use crate::{
+    arweave::ArweaveTag,
+    types::{BlogClientError, Result},
+};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+
+/// Content on IPFS through a node's HTTP RPC API (Kubo `/api/v0`)
+#[derive(Debug, Clone)]
+pub struct IpfsStore {
+    client: Client,
+    api_url: String,
+    gateway_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AddResponse {
+    #[serde(rename = "Hash")]
+    hash: String,
+}
+
+impl IpfsStore {
+    /// `api_url` is the node's RPC address, e.g. `http://127.0.0.1:5001`
+    pub fn new(api_url: String) -> Self {
+        Self {
+            client: Client::new(),
+            api_url,
+            gateway_url: "https://ipfs.io".to_string(),
+        }
+    }
+
+    pub fn with_gateway_url(mut self, gateway_url: String) -> Self {
+        self.gateway_url = gateway_url;
+        self
+    }
+}
+
+#[async_trait]
+impl ContentStore for IpfsStore {
+    /// IPFS has no per-object metadata, so the content type and tags are not kept
+    async fn put(&self, data: &[u8], content_type: &str, _tags: &[ArweaveTag]) -> Result<ContentId> {
+        let form = reqwest::multipart::Form::new().part(
+            "file",
+            reqwest::multipart::Part::bytes(data.to_vec())
+                .mime_str(content_type)
+                .map_err(|e| BlogClientError {
+                    message: format!("Failed to create form part: {}", e),
+                })?,
+        );
+
+        let response = self
+            .client
+            .post(format!("{}/api/v0/add", self.api_url))
+            .query(&[("pin", "true"), ("cid-version", "1")])
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to upload to IPFS: {}", e),
+            })?;
+
+        if !response.status().is_success() {
+            return Err(BlogClientError {
+                message: format!("IPFS upload failed with status: {}", response.status()),
+            });
+        }
+
+        let added: AddResponse = response.json().await.map_err(|e| BlogClientError {
+            message: format!("Failed to parse IPFS response: {}", e),
+        })?;
+        Ok(ContentId(added.hash))
+    }
+
+    async fn get(&self, id: &ContentId) -> Result<Vec<u8>> {
+        let response = self
+            .client
+            .post(format!("{}/api/v0/cat", self.api_url))
+            .query(&[("arg", id.as_str())])
+            .send()
+            .await
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to fetch from IPFS: {}", e),
+            })?;
+
+        if !response.status().is_success() {
+            return Err(BlogClientError {
+                message: format!("IPFS fetch failed with status: {}", response.status()),
+            });
+        }
+
+        response
+            .bytes()
+            .await
+            .map(|data| data.to_vec())
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to read IPFS response: {}", e),
+            })
+    }
+
+    fn url(&self, id: &ContentId) -> String {
+        format!("{}/ipfs/{}", self.gateway_url, id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::Query, routing::post, Router};
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn ids_are_sent_as_a_query_parameter() {
+        let app = Router::new().route(
+            "/api/v0/cat",
+            post(|Query(query): Query<HashMap<String, String>>| async move {
+                query.get("arg").cloned().unwrap_or_default()
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let store = IpfsStore::new(url);
+        let id = ContentId("bafy/../x?arg=other&y=#z".to_string());
+        assert_eq!(store.get(&id).await.unwrap(), id.0.as_bytes());
+    }
+}
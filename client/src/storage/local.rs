@@ -0,0 +1,141 @@
+use super::{ContentId, ContentStore};
+use crate::{
+    arweave::ArweaveTag,
+    types::{BlogClientError, Result},
+};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// Content-addressed files in a local directory, for offline development and
+/// test runs. Objects are named by their base64url SHA-256 digest, which has
+/// the same 43-character shape as an Arweave id, with a `.json` sidecar
+/// holding the content type and tags.
+#[derive(Debug, Clone)]
+pub struct LocalStore {
+    root: PathBuf,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LocalMetadata {
+    content_type: String,
+    tags: Vec<ArweaveTag>,
+}
+
+impl LocalStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn path_for(&self, id: &ContentId) -> Result<PathBuf> {
+        // Ids are base64url digests; anything else could escape the store directory
+        if id.0.is_empty()
+            || !id.0.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(BlogClientError {
+                message: format!("Invalid local content id: {}", id),
+            });
+        }
+        Ok(self.root.join(&id.0))
+    }
+
+    /// Content type recorded when `id` was stored
+    pub async fn content_type(&self, id: &ContentId) -> Result<String> {
+        let path = self.path_for(id)?.with_extension("json");
+        let metadata = tokio::fs::read(&path).await.map_err(|e| BlogClientError {
+            message: format!("Failed to read {}: {}", path.display(), e),
+        })?;
+        let metadata: LocalMetadata = serde_json::from_slice(&metadata).map_err(|e| BlogClientError {
+            message: format!("Failed to parse {}: {}", path.display(), e),
+        })?;
+        Ok(metadata.content_type)
+    }
+}
+
+#[async_trait]
+impl ContentStore for LocalStore {
+    async fn put(&self, data: &[u8], content_type: &str, tags: &[ArweaveTag]) -> Result<ContentId> {
+        let id = ContentId(URL_SAFE_NO_PAD.encode(Sha256::digest(data)));
+        let path = self.path_for(&id)?;
+
+        tokio::fs::create_dir_all(&self.root).await.map_err(|e| BlogClientError {
+            message: format!("Failed to create {}: {}", self.root.display(), e),
+        })?;
+
+        tokio::fs::write(&path, data).await.map_err(|e| BlogClientError {
+            message: format!("Failed to write {}: {}", path.display(), e),
+        })?;
+
+        let metadata = serde_json::to_vec_pretty(&LocalMetadata {
+            content_type: content_type.to_string(),
+            tags: tags.to_vec(),
+        })
+        .map_err(|e| BlogClientError {
+            message: format!("Failed to serialize metadata: {}", e),
+        })?;
+        tokio::fs::write(path.with_extension("json"), metadata)
+            .await
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to write metadata for {}: {}", id, e),
+            })?;
+
+        Ok(id)
+    }
+
+    async fn get(&self, id: &ContentId) -> Result<Vec<u8>> {
+        let path = self.path_for(id)?;
+        tokio::fs::read(&path).await.map_err(|e| BlogClientError {
+            message: format!("Failed to read {}: {}", path.display(), e),
+        })
+    }
+
+    fn url(&self, id: &ContentId) -> String {
+        format!("file://{}", self.root.join(&id.0).display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stored_content_reads_back_by_digest() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalStore::new(dir.path().join("store"));
+        let tags = [ArweaveTag::new("Post", "post")];
+
+        let id = store.put(b"# Hello", "text/markdown", &tags).await.unwrap();
+        assert_eq!(id.as_str().len(), 43);
+        assert_eq!(store.put(b"# Hello", "text/markdown", &tags).await.unwrap(), id);
+        assert_eq!(store.get(&id).await.unwrap(), b"# Hello");
+        assert_eq!(store.content_type(&id).await.unwrap(), "text/markdown");
+        assert_eq!(store.get_raw(&id).await.unwrap(), b"# Hello");
+
+        let missing = ContentId::from("A".repeat(43));
+        assert!(store.get(&missing).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn ids_cannot_leave_the_store_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("secret"), "not stored content").unwrap();
+        let store = LocalStore::new(dir.path().join("store"));
+
+        for id in ["../secret", "..", "a/b", "", "%2e%2e"] {
+            let error = store.get(&ContentId::from(id)).await.unwrap_err();
+            assert_eq!(error.message, format!("Invalid local content id: {}", id));
+        }
+    }
+
+    #[test]
+    fn urls_point_into_the_store_directory() {
+        let store = LocalStore::new("/var/blog");
+        assert_eq!(store.url(&ContentId::from("abc_-1")), "file:///var/blog/abc_-1");
+    }
+}
@@ -0,0 +1,111 @@
+pub mod arweave;
+pub mod ipfs;
+pub mod local;
+
+use crate::{arweave::ArweaveTag, types::Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::Keypair;
+use std::path::Path;
+
+pub use self::arweave::ArweaveStore;
+pub use ipfs::IpfsStore;
+pub use local::LocalStore;
+
+/// Identifier a store hands back for stored content: an Arweave transaction
+/// id, an IPFS CID, or a base64url SHA-256 digest for the local store
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ContentId(pub String);
+
+impl ContentId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for ContentId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<String> for ContentId {
+    fn from(id: String) -> Self {
+        Self(id)
+    }
+}
+
+impl From<&str> for ContentId {
+    fn from(id: &str) -> Self {
+        Self(id.to_string())
+    }
+}
+
+/// Price of storing some bytes, split by who pays it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageCost {
+    /// Paid by an Arweave wallet
+    pub winston: u64,
+    /// Charged by a bundler against the uploader's funded balance
+    pub bundler_lamports: u64,
+}
+
+/// Where post bodies and media live. The id returned by `put` is what gets
+/// stored on-chain in a post's `arweave_hash`.
+#[async_trait]
+pub trait ContentStore: Send + Sync {
+    async fn put(&self, data: &[u8], content_type: &str, tags: &[ArweaveTag]) -> Result<ContentId>;
+
+    /// Store content on behalf of `signer`. Stores that can attribute uploads
+    /// to a Solana key (Arweave bundles) override this; others ignore it.
+    async fn put_signed(
+        &self,
+        data: &[u8],
+        content_type: &str,
+        tags: &[ArweaveTag],
+        _signer: &Keypair,
+    ) -> Result<ContentId> {
+        self.put(data, content_type, tags).await
+    }
+
+    async fn get(&self, id: &ContentId) -> Result<Vec<u8>>;
+
+    /// Bytes of `id` exactly as stored. Differs from `get` only for stores
+    /// that resolve path manifests to their index when serving them.
+    async fn get_raw(&self, id: &ContentId) -> Result<Vec<u8>> {
+        self.get(id).await
+    }
+
+    fn url(&self, id: &ContentId) -> String;
+
+    /// Price to store `bytes` bytes the way `put_signed` would; zero for
+    /// stores with no per-upload cost
+    async fn storage_cost(&self, _bytes: usize) -> Result<StorageCost> {
+        Ok(StorageCost::default())
+    }
+}
+
+/// Content type for a file, guessed from its extension
+pub fn content_type_for_path(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("md" | "markdown") => "text/markdown",
+        Some("txt") => "text/plain",
+        Some("html" | "htm") => "text/html",
+        Some("css") => "text/css",
+        Some("json") => "application/json",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        Some("pdf") => "application/pdf",
+        Some("mp4") => "video/mp4",
+        Some("mp3") => "audio/mpeg",
+        _ => "application/octet-stream",
+    }
+}
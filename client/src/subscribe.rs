@@ -0,0 +1,354 @@
+use crate::{
+    blog_client::BlogClient,
+    types::{BlogClientError, PostInfo, Result},
+};
+use borsh::BorshDeserialize;
+use futures_util::{Stream, StreamExt};
+use solana_account_decoder::UiAccountEncoding;
+use solana_blog_program::state::{Blog, BlogPost};
+use solana_client::{
+    nonblocking::{pubsub_client::PubsubClient, rpc_client::RpcClient},
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+    rpc_request::MAX_MULTIPLE_ACCOUNTS,
+};
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::{
+    collections::HashSet,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
+
+/// A change to one of a blog's posts
+#[derive(Debug, Clone, PartialEq)]
+pub enum PostEvent {
+    Created(PostInfo),
+    Updated(PostInfo),
+    Deleted(Pubkey),
+}
+
+/// Websocket URL of the validator serving `rpc_url`. Its websocket port
+/// is one above its RPC port.
+pub fn derive_ws_url(rpc_url: &str) -> String {
+    let url = if let Some(rest) = rpc_url.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = rpc_url.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        rpc_url.to_string()
+    };
+    url.replace(":8899", ":8900")
+}
+
+/// Live post events for one blog. Ends after the first error, which is
+/// yielded; dropping it closes the websocket.
+pub struct BlogSubscription {
+    events: mpsc::UnboundedReceiver<Result<PostEvent>>,
+    task: JoinHandle<()>,
+}
+
+impl BlogSubscription {
+    pub async fn next(&mut self) -> Option<Result<PostEvent>> {
+        self.events.recv().await
+    }
+}
+
+impl Stream for BlogSubscription {
+    type Item = Result<PostEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.events.poll_recv(cx)
+    }
+}
+
+impl Drop for BlogSubscription {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn subscription_error(context: &str, e: impl std::fmt::Display) -> BlogClientError {
+    BlogClientError {
+        message: format!("{}: {}", context, e),
+    }
+}
+
+fn account_config() -> RpcAccountInfoConfig {
+    RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        ..Default::default()
+    }
+}
+
+fn post_info(pubkey: Pubkey, data: &[u8]) -> Option<PostInfo> {
+    BlogPost::try_from_slice(data).ok().map(|post| PostInfo::new(pubkey, post))
+}
+
+/// Report the posts in `known` that no longer exist, when the blog counts
+/// fewer posts than are known. Returns `false` once the receiver is gone.
+async fn remove_deleted(
+    rpc_client: &RpcClient,
+    known: &mut HashSet<Pubkey>,
+    post_count: u64,
+    events: &mpsc::UnboundedSender<Result<PostEvent>>,
+) -> Result<bool> {
+    if known.len() as u64 <= post_count {
+        return Ok(true);
+    }
+    let pubkeys: Vec<Pubkey> = known.iter().copied().collect();
+    // Nodes refuse to look up more accounts than this in one request
+    for pubkeys in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let accounts = rpc_client
+            .get_multiple_accounts(pubkeys)
+            .await
+            .map_err(|e| subscription_error("Failed to check for deleted posts", e))?;
+        for (pubkey, account) in pubkeys.iter().zip(accounts) {
+            if account.is_some_and(|account| !account.data.is_empty()) {
+                continue;
+            }
+            known.remove(pubkey);
+            if events.send(Ok(PostEvent::Deleted(*pubkey))).is_err() {
+                return Ok(false);
+            }
+        }
+    }
+    Ok(true)
+}
+
+/// Follow `blog` until the websocket closes or the receiver is dropped.
+/// Post accounts are watched with `programSubscribe`, filtered on their
+/// blog field. A deleted post's data is cleared, so it no longer matches
+/// that filter; instead the blog account is watched with
+/// `accountSubscribe`, and whenever it counts fewer posts than have been
+/// seen, those are checked for the ones that are gone. Created posts are
+/// checked the same way, since a post created and deleted between two
+/// blog notifications never lowers the count.
+async fn follow_blog(
+    ws_url: String,
+    rpc_url: String,
+    program_id: Pubkey,
+    blog: Pubkey,
+    ready: oneshot::Sender<Result<()>>,
+    events: mpsc::UnboundedSender<Result<PostEvent>>,
+) {
+    let pubsub = match PubsubClient::new(&ws_url).await {
+        Ok(pubsub) => pubsub,
+        Err(e) => {
+            let _ = ready.send(Err(subscription_error(&format!("Failed to connect to {}", ws_url), e)));
+            return;
+        }
+    };
+    let filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+        BlogPost::BLOG_OFFSET,
+        blog.as_ref(),
+    ))];
+    let config = RpcProgramAccountsConfig {
+        filters: Some(filters),
+        account_config: account_config(),
+        ..Default::default()
+    };
+
+    let (mut post_updates, unsubscribe_posts) = match pubsub.program_subscribe(&program_id, Some(config.clone())).await {
+        Ok(subscription) => subscription,
+        Err(e) => {
+            let _ = ready.send(Err(subscription_error("Failed to subscribe to posts", e)));
+            return;
+        }
+    };
+    let (mut blog_updates, unsubscribe_blog) = match pubsub.account_subscribe(&blog, Some(account_config())).await {
+        Ok(subscription) => subscription,
+        Err(e) => {
+            let _ = ready.send(Err(subscription_error("Failed to subscribe to blog", e)));
+            return;
+        }
+    };
+
+    // Subscribed first, so nothing is missed between listing and following
+    let rpc_client = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+    let mut known: HashSet<Pubkey> = match rpc_client.get_program_accounts_with_config(&program_id, config).await {
+        Ok(accounts) => accounts.into_iter().map(|(pubkey, _)| pubkey).collect(),
+        Err(e) => {
+            let _ = ready.send(Err(subscription_error("Failed to list posts", e)));
+            return;
+        }
+    };
+    let mut post_count = match rpc_client.get_account_data(&blog).await {
+        Ok(data) => match Blog::try_from_slice(&data) {
+            Ok(state) => state.post_count,
+            Err(e) => {
+                let _ = ready.send(Err(subscription_error("Invalid blog account", e)));
+                return;
+            }
+        },
+        Err(e) => {
+            let _ = ready.send(Err(subscription_error("Failed to get blog", e)));
+            return;
+        }
+    };
+    if ready.send(Ok(())).is_err() {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            update = post_updates.next() => {
+                let Some(update) = update else { break };
+                let Ok(pubkey) = update.value.pubkey.parse::<Pubkey>() else { continue };
+                let Some(account) = update.value.account.decode::<Account>() else { continue };
+                let Some(post) = post_info(pubkey, &account.data) else { continue };
+                let created = known.insert(pubkey);
+                let event = if created { PostEvent::Created(post) } else { PostEvent::Updated(post) };
+                if events.send(Ok(event)).is_err() {
+                    break;
+                }
+                if !created {
+                    continue;
+                }
+            }
+            update = blog_updates.next() => {
+                let Some(update) = update else { break };
+                let Some(account) = update.value.decode::<Account>() else { continue };
+                let Ok(state) = Blog::try_from_slice(&account.data) else { continue };
+                post_count = state.post_count;
+            }
+            _ = events.closed() => break,
+        }
+
+        match remove_deleted(&rpc_client, &mut known, post_count, &events).await {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => {
+                let _ = events.send(Err(e));
+                break;
+            }
+        }
+    }
+
+    unsubscribe_posts().await;
+    unsubscribe_blog().await;
+    let _ = events.send(Err(BlogClientError {
+        message: "Blog subscription closed".to_string(),
+    }));
+}
+
+impl BlogClient {
+    /// Follow `blog_pubkey`'s posts as they are created, updated and
+    /// deleted. Returns once both subscriptions are open.
+    pub async fn subscribe_blog(&self, blog_pubkey: Pubkey) -> Result<BlogSubscription> {
+        let (ready_sender, ready) = oneshot::channel();
+        let (sender, events) = mpsc::unbounded_channel();
+        let task = tokio::spawn(follow_blog(
+            self.ws_url(),
+            self.rpc_url(),
+            self.program_id(),
+            blog_pubkey,
+            ready_sender,
+            sender,
+        ));
+
+        let subscription = BlogSubscription { events, task };
+        match ready.await {
+            Ok(Ok(())) => Ok(subscription),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(BlogClientError {
+                message: "Blog subscription task ended".to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Json, Router};
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use serde_json::{json, Value};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    /// An RPC node where `live` posts exist and every other account is
+    /// closed, counting the `getMultipleAccounts` calls it serves
+    async fn serve(live: Vec<Pubkey>, calls: Arc<AtomicUsize>) -> RpcClient {
+        let app = Router::new().route(
+            "/",
+            post(move |Json(request): Json<Value>| async move {
+                if request["method"] == "getVersion" {
+                    let version = json!({ "solana-core": "1.18.0" });
+                    return Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": version }));
+                }
+                calls.fetch_add(1, Ordering::SeqCst);
+                let pubkeys = request["params"][0].as_array().unwrap();
+                if pubkeys.len() > MAX_MULTIPLE_ACCOUNTS {
+                    let error = json!({ "code": -32602, "message": "Too many inputs provided" });
+                    return Json(json!({ "jsonrpc": "2.0", "id": request["id"], "error": error }));
+                }
+                let accounts: Vec<Value> = pubkeys
+                    .iter()
+                    .map(|pubkey| match live.iter().any(|live| pubkey == &live.to_string()) {
+                        true => json!({
+                            "lamports": 1,
+                            "data": [STANDARD.encode([1u8; 8]), "base64"],
+                            "owner": Pubkey::default().to_string(),
+                            "executable": false,
+                            "rentEpoch": 0,
+                        }),
+                        false => Value::Null,
+                    })
+                    .collect();
+                Json(json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "result": { "context": { "slot": 1 }, "value": accounts },
+                }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        RpcClient::new(url)
+    }
+
+    #[tokio::test]
+    async fn posts_beyond_the_blog_count_are_checked_for_deletion() {
+        let (kept, deleted) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let rpc_client = serve(vec![kept], calls.clone()).await;
+        let (sender, mut events) = mpsc::unbounded_channel();
+        let mut known = HashSet::from([kept, deleted]);
+
+        // As many posts as the blog counts, so nothing is asked for
+        assert!(remove_deleted(&rpc_client, &mut known, 2, &sender).await.unwrap());
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        // A post created and deleted between blog notifications leaves the
+        // count where it was, one below what has been seen
+        assert!(remove_deleted(&rpc_client, &mut known, 1, &sender).await.unwrap());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(known, HashSet::from([kept]));
+        assert_eq!(events.try_recv().unwrap().unwrap(), PostEvent::Deleted(deleted));
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn large_blogs_are_checked_in_batches() {
+        let live: Vec<Pubkey> = (0..250).map(|_| Pubkey::new_unique()).collect();
+        let deleted = Pubkey::new_unique();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let rpc_client = serve(live.clone(), calls.clone()).await;
+        let (sender, mut events) = mpsc::unbounded_channel();
+        let mut known: HashSet<Pubkey> = live.iter().copied().chain([deleted]).collect();
+
+        assert!(remove_deleted(&rpc_client, &mut known, 250, &sender).await.unwrap());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(known, live.into_iter().collect());
+        assert_eq!(events.try_recv().unwrap().unwrap(), PostEvent::Deleted(deleted));
+        assert!(events.try_recv().is_err());
+    }
+}
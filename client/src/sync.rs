@@ -0,0 +1,766 @@
+use crate::{
+    arweave::{manifest::PathManifest, ArweaveTag},
+    blog_client::{BlogClient, POST_IMAGES_DIR, POST_MEDIA_PATH},
+    send::SendResult,
+    storage::{content_type_for_path, ContentId},
+    types::{BlogClientError, Result},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use solana_blog_program::state::BlogPost;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Lockfile kept in a synced directory, mapping files to their posts
+pub const LOCKFILE_NAME: &str = "blog.lock";
+const LOCKFILE_VERSION: u32 = 1;
+/// Manifest directory for files linked from a post body
+const POST_FILES_DIR: &str = "files/";
+
+/// Frontmatter in the format `ArweaveService::format_post_content` writes,
+/// plus an optional `image` path for the post's cover image
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Frontmatter {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub created_at: Option<String>,
+    pub author: Option<String>,
+    pub image: Option<String>,
+    pub draft: bool,
+}
+
+/// Split `text` into its frontmatter and body, or `None` if it has no
+/// frontmatter block
+pub fn parse_frontmatter(text: &str) -> Option<(Frontmatter, &str)> {
+    let rest = text.strip_prefix("---\n")?;
+    let (block, body) = match rest.find("\n---\n") {
+        Some(end) => (&rest[..end], &rest[end + "\n---\n".len()..]),
+        None => (rest.strip_suffix("\n---")?, ""),
+    };
+
+    let mut frontmatter = Frontmatter::default();
+    for line in block.lines() {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let value = value.trim();
+        let text = unquote(value);
+        let non_empty = (!text.is_empty()).then(|| text.to_string());
+        match key.trim() {
+            "title" => frontmatter.title = non_empty,
+            "description" => frontmatter.description = non_empty,
+            "created_at" => frontmatter.created_at = non_empty,
+            "author" => frontmatter.author = non_empty,
+            "image" => frontmatter.image = non_empty,
+            "draft" => frontmatter.draft = text == "true",
+            "tags" => {
+                // Written as a JSON array, so tags may contain commas
+                frontmatter.tags = serde_json::from_str::<Vec<String>>(value).unwrap_or_else(|_| {
+                    value
+                        .trim_start_matches('[')
+                        .trim_end_matches(']')
+                        .split(',')
+                        .map(|tag| unquote(tag.trim()).into_owned())
+                        .collect()
+                });
+                frontmatter.tags.retain(|tag| !tag.is_empty());
+            }
+            _ => {}
+        }
+    }
+    Some((frontmatter, body.trim_start_matches('\n')))
+}
+
+/// A frontmatter value without its quotes. Values are written as JSON
+/// strings, so their escapes are decoded; hand-written ones are taken as is.
+fn unquote(value: &str) -> Cow<'_, str> {
+    if let Ok(text) = serde_json::from_str::<String>(value) {
+        return Cow::Owned(text);
+    }
+    Cow::Borrowed(
+        value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .unwrap_or(value),
+    )
+}
+
+/// `key: value` frontmatter line with the value quoted for `parse_frontmatter`
+pub(crate) fn frontmatter_line(key: &str, value: &str) -> String {
+    format!("{}: {}\n", key, serde_json::Value::from(value))
+}
+
+/// What was last published for a file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedPost {
+    pub post: String,
+    /// Digest of the file, its cover image and its linked files
+    pub hash: String,
+    pub content_id: String,
+    /// Digest of the cover image, so an unchanged one is not re-uploaded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// Linked files by manifest path
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub assets: BTreeMap<String, LockedAsset>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedAsset {
+    pub hash: String,
+    pub id: String,
+}
+
+/// Contents of `blog.lock`, keyed by `/`-separated paths relative to the
+/// synced directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncLock {
+    pub version: u32,
+    pub blog: String,
+    pub files: BTreeMap<String, LockedPost>,
+}
+
+impl SyncLock {
+    /// Lockfile in `dir`, or an empty one for `blog` if there is none
+    pub fn load(dir: &Path, blog: Pubkey) -> Result<Self> {
+        let path = dir.join(LOCKFILE_NAME);
+        let lock: Self = match std::fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data).map_err(|e| BlogClientError {
+                message: format!("Failed to parse {}: {}", path.display(), e),
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self {
+                    version: LOCKFILE_VERSION,
+                    blog: blog.to_string(),
+                    files: BTreeMap::new(),
+                })
+            }
+            Err(e) => {
+                return Err(BlogClientError {
+                    message: format!("Failed to read {}: {}", path.display(), e),
+                })
+            }
+        };
+
+        if lock.blog != blog.to_string() {
+            return Err(BlogClientError {
+                message: format!("{} belongs to blog {}, not {}", path.display(), lock.blog, blog),
+            });
+        }
+        Ok(lock)
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let path = dir.join(LOCKFILE_NAME);
+        let mut data = serde_json::to_vec_pretty(self).map_err(|e| BlogClientError {
+            message: format!("Failed to serialize lockfile: {}", e),
+        })?;
+        data.push(b'\n');
+        // Write then rename so an interrupted sync never truncates the lockfile
+        let temp_path = path.with_extension("lock.tmp");
+        std::fs::write(&temp_path, data)
+            .and_then(|_| std::fs::rename(&temp_path, &path))
+            .map_err(|e| BlogClientError {
+                message: format!("Failed to save {}: {}", path.display(), e),
+            })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SyncOptions {
+    /// Delete posts whose files were removed from the directory
+    pub prune: bool,
+    /// Report what would change without uploading or sending anything
+    pub dry_run: bool,
+}
+
+/// A post created, updated or deleted by a sync. `post` is `None` only for
+/// posts a dry run would create; `result` is `None` on dry runs.
+#[derive(Debug, Clone)]
+pub struct SyncedPost {
+    pub path: String,
+    pub post: Option<Pubkey>,
+    pub content_id: Option<ContentId>,
+    pub result: Option<SendResult>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SyncReport {
+    pub created: Vec<SyncedPost>,
+    pub updated: Vec<SyncedPost>,
+    pub deleted: Vec<SyncedPost>,
+    pub unchanged: Vec<String>,
+    /// Markdown files without frontmatter, or marked `draft: true`
+    pub skipped: Vec<String>,
+    /// Locked files that are gone from the directory, left alone without `prune`
+    pub missing: Vec<String>,
+}
+
+/// A file linked from a post body, uploaded under `files/`
+#[derive(Debug, Clone)]
+struct LocalAsset {
+    path: String,
+    data: Vec<u8>,
+    content_type: String,
+    hash: String,
+}
+
+#[derive(Debug, Clone)]
+struct LocalImage {
+    data: Vec<u8>,
+    hash: String,
+}
+
+/// A markdown file ready to publish, with links to local files rewritten to
+/// their manifest paths
+#[derive(Debug, Clone)]
+struct LocalPost {
+    path: String,
+    title: String,
+    summary: String,
+    text: String,
+    image: Option<LocalImage>,
+    assets: Vec<LocalAsset>,
+    hash: String,
+}
+
+fn digest(data: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(data))
+}
+
+fn read(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).map_err(|e| BlogClientError {
+        message: format!("Failed to read {}: {}", path.display(), e),
+    })
+}
+
+/// `text` cut to at most `max` bytes on a character boundary
+pub(crate) fn truncate(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+/// Markdown files under `dir` as `/`-separated relative paths, sorted,
+/// skipping hidden files and directories
+fn markdown_files(dir: &Path) -> Result<Vec<String>> {
+    let mut files = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(relative) = pending.pop() {
+        let entries = std::fs::read_dir(dir.join(&relative)).map_err(|e| BlogClientError {
+            message: format!("Failed to read directory {}: {}", dir.join(&relative).display(), e),
+        })?;
+        for entry in entries {
+            let entry = entry.map_err(|e| BlogClientError {
+                message: format!("Failed to read directory entry: {}", e),
+            })?;
+            let name = entry.file_name();
+            let Some(name) = name.to_str() else { continue };
+            if name.starts_with('.') {
+                continue;
+            }
+            let path = relative.join(name);
+            if entry.path().is_dir() {
+                pending.push(path);
+            } else if content_type_for_path(&path) == "text/markdown" {
+                let components: Vec<&str> = path.iter().filter_map(|part| part.to_str()).collect();
+                files.push(components.join("/"));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Whether a link target could be a file next to the post
+fn is_local_link(target: &str) -> bool {
+    !target.is_empty()
+        && !target.starts_with('#')
+        && !target.starts_with('/')
+        && !target.contains("://")
+        && !target.starts_with("mailto:")
+        && content_type_for_path(Path::new(target)) != "text/markdown"
+}
+
+/// `target`, relative to `base`, as a canonical path inside `root`, or
+/// `None` if nothing is there. Paths that resolve outside `root` (through
+/// `..`, an absolute path or a symlink) are an error, so a post can't
+/// publish files from elsewhere on disk.
+fn resolve_local(root: &Path, base: &Path, post: &str, target: &str) -> Result<Option<PathBuf>> {
+    let Ok(path) = base.join(target).canonicalize() else { return Ok(None) };
+    if !path.starts_with(root) {
+        return Err(BlogClientError {
+            message: format!("{}: {} is outside the synced directory", post, target),
+        });
+    }
+    Ok(Some(path))
+}
+
+/// `body` with `rewrite` applied to the file part of every local link
+/// target. Fenced code blocks and inline code are copied unchanged.
+fn rewrite_links(
+    body: &str,
+    mut rewrite: impl FnMut(&str) -> Result<Option<String>>,
+) -> Result<String> {
+    let mut rewritten = String::with_capacity(body.len());
+    let mut fence = None;
+    for line in body.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let marker = ["```", "~~~"].into_iter().find(|marker| trimmed.starts_with(marker));
+        if let Some(open) = fence {
+            if marker == Some(open) {
+                fence = None;
+            }
+            rewritten.push_str(line);
+            continue;
+        }
+        if marker.is_some() {
+            fence = marker;
+            rewritten.push_str(line);
+            continue;
+        }
+
+        // Odd segments between backticks are inline code
+        for (index, segment) in line.split('`').enumerate() {
+            if index > 0 {
+                rewritten.push('`');
+            }
+            if index % 2 == 1 {
+                rewritten.push_str(segment);
+                continue;
+            }
+            let mut rest = segment;
+            while let Some(start) = rest.find("](") {
+                let (before, after) = rest.split_at(start + 2);
+                rewritten.push_str(before);
+                let end = after
+                    .find(|c: char| c == ')' || c.is_whitespace())
+                    .unwrap_or(after.len());
+                let target = &after[..end];
+                let file = target.split(['#', '?']).next().unwrap_or_default();
+                match is_local_link(target).then(|| rewrite(file)).transpose()?.flatten() {
+                    Some(replacement) => {
+                        rewritten.push_str(&replacement);
+                        rewritten.push_str(&target[file.len()..]);
+                    }
+                    None => rewritten.push_str(target),
+                }
+                rest = &after[end..];
+            }
+            rewritten.push_str(rest);
+        }
+    }
+    Ok(rewritten)
+}
+
+/// Read and prepare `relative` for publishing, or `None` if it should be
+/// skipped
+fn load_post(dir: &Path, relative: &str) -> Result<Option<LocalPost>> {
+    let path = dir.join(relative);
+    let text = String::from_utf8(read(&path)?).map_err(|e| BlogClientError {
+        message: format!("{} is not UTF-8: {}", path.display(), e),
+    })?;
+    let text = text.replace("\r\n", "\n");
+    let Some((frontmatter, body)) = parse_frontmatter(&text) else { return Ok(None) };
+    if frontmatter.draft {
+        return Ok(None);
+    }
+    let root = dir.canonicalize().map_err(|e| BlogClientError {
+        message: format!("Failed to resolve {}: {}", dir.display(), e),
+    })?;
+    let base = path.parent().unwrap_or(dir);
+
+    let title = frontmatter
+        .title
+        .clone()
+        .or_else(|| {
+            body.lines()
+                .find_map(|line| line.strip_prefix("# "))
+                .map(|heading| heading.trim().to_string())
+        })
+        .unwrap_or_else(|| {
+            Path::new(relative)
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or(relative)
+                .to_string()
+        });
+    if title.len() > BlogPost::MAX_TITLE_LENGTH {
+        return Err(BlogClientError {
+            message: format!(
+                "{}: title is {} bytes, the limit is {}",
+                relative,
+                title.len(),
+                BlogPost::MAX_TITLE_LENGTH
+            ),
+        });
+    }
+
+    // The full body lives on Arweave; on-chain content is a summary
+    let summary = frontmatter
+        .description
+        .clone()
+        .unwrap_or_else(|| body.trim().to_string());
+    let summary = truncate(&summary, BlogPost::MAX_CONTENT_LENGTH).to_string();
+
+    let image = match &frontmatter.image {
+        Some(image) => {
+            let path = resolve_local(&root, base, relative, image)?.ok_or_else(|| BlogClientError {
+                message: format!("{}: image {} does not exist", relative, image),
+            })?;
+            let data = read(&path)?;
+            let hash = digest(&data);
+            Some(LocalImage { data, hash })
+        }
+        None => None,
+    };
+
+    // Copy linked local files under `files/`, at their path in the synced
+    // directory, and point the links there
+    let mut assets: Vec<LocalAsset> = Vec::new();
+    let links = rewrite_links(body, |file| {
+        let Some(local) = resolve_local(&root, base, relative, file)? else { return Ok(None) };
+        if !local.is_file() {
+            return Ok(None);
+        }
+        let name: Vec<&str> = local
+            .strip_prefix(&root)
+            .unwrap_or(&local)
+            .iter()
+            .filter_map(|part| part.to_str())
+            .collect();
+        let manifest_path = format!("{}{}", POST_FILES_DIR, name.join("/"));
+        if !assets.iter().any(|asset| asset.path == manifest_path) {
+            let data = read(&local)?;
+            assets.push(LocalAsset {
+                path: manifest_path.clone(),
+                hash: digest(&data),
+                content_type: content_type_for_path(&local).to_string(),
+                data,
+            });
+        }
+        Ok(Some(manifest_path))
+    })?;
+    // `body` is the tail of `text`, after the frontmatter
+    let rewritten = format!("{}{}", &text[..text.len() - body.len()], links);
+
+    let mut hasher = Sha256::new();
+    hasher.update(rewritten.as_bytes());
+    if let Some(image) = &image {
+        hasher.update(b"\0image\0");
+        hasher.update(image.hash.as_bytes());
+    }
+    for asset in &assets {
+        hasher.update(b"\0");
+        hasher.update(asset.path.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(asset.hash.as_bytes());
+    }
+
+    Ok(Some(LocalPost {
+        path: relative.to_string(),
+        title,
+        summary,
+        text: rewritten,
+        image,
+        assets,
+        hash: URL_SAFE_NO_PAD.encode(hasher.finalize()),
+    }))
+}
+
+impl BlogClient {
+    /// Publish the markdown files in `dir` to `blog`. Each file needs a
+    /// frontmatter block; files whose content, cover image and linked files
+    /// are unchanged since the last sync (per `blog.lock`) are left alone.
+    /// The lockfile is saved after every post, so an interrupted sync picks
+    /// up where it stopped without creating duplicates.
+    pub async fn sync_directory(
+        &self,
+        author: &Keypair,
+        blog_pubkey: Pubkey,
+        dir: &Path,
+        options: &SyncOptions,
+    ) -> Result<SyncReport> {
+        let mut lock = SyncLock::load(dir, blog_pubkey)?;
+        let mut report = SyncReport::default();
+
+        let mut local_posts = Vec::new();
+        for relative in markdown_files(dir)? {
+            match load_post(dir, &relative)? {
+                Some(post) => local_posts.push(post),
+                None => report.skipped.push(relative),
+            }
+        }
+
+        for local in &local_posts {
+            let locked = lock.files.get(&local.path).cloned();
+            let existing = match &locked {
+                Some(locked) if locked.hash == local.hash => {
+                    report.unchanged.push(local.path.clone());
+                    continue;
+                }
+                Some(locked) => Some((parse_pubkey(&locked.post)?, locked)),
+                None => None,
+            };
+
+            if options.dry_run {
+                let synced = SyncedPost {
+                    path: local.path.clone(),
+                    post: existing.map(|(post, _)| post),
+                    content_id: None,
+                    result: None,
+                };
+                match existing {
+                    Some(_) => report.updated.push(synced),
+                    None => report.created.push(synced),
+                }
+                continue;
+            }
+
+            let (entry, post_pubkey, result) =
+                self.publish_file(author, blog_pubkey, local, existing).await?;
+            let synced = SyncedPost {
+                path: local.path.clone(),
+                post: Some(post_pubkey),
+                content_id: Some(ContentId::from(entry.content_id.clone())),
+                result: Some(result),
+            };
+            lock.files.insert(local.path.clone(), entry);
+            lock.save(dir)?;
+            match existing {
+                Some(_) => report.updated.push(synced),
+                None => report.created.push(synced),
+            }
+        }
+
+        let removed: Vec<String> = lock
+            .files
+            .keys()
+            .filter(|path| !local_posts.iter().any(|local| &local.path == *path))
+            .cloned()
+            .collect();
+        for path in removed {
+            if !options.prune {
+                report.missing.push(path);
+                continue;
+            }
+            let post_pubkey = parse_pubkey(&lock.files[&path].post)?;
+            let result = if options.dry_run {
+                None
+            } else {
+                let result = self.delete_post(author, post_pubkey).await?;
+                lock.files.remove(&path);
+                lock.save(dir)?;
+                Some(result)
+            };
+            report.deleted.push(SyncedPost {
+                path,
+                post: Some(post_pubkey),
+                content_id: None,
+                result,
+            });
+        }
+
+        Ok(report)
+    }
+
+    /// Upload `local` and create its post, or update the post in `existing`.
+    /// Linked files and the cover image are reused from the last sync when
+    /// their digests match.
+    async fn publish_file(
+        &self,
+        author: &Keypair,
+        blog_pubkey: Pubkey,
+        local: &LocalPost,
+        existing: Option<(Pubkey, &LockedPost)>,
+    ) -> Result<(LockedPost, Pubkey, SendResult)> {
+        let post_keypair = existing.is_none().then(Keypair::new);
+        let post_pubkey = match (&post_keypair, existing) {
+            (Some(keypair), _) => keypair.pubkey(),
+            (None, Some((post, _))) => post,
+            (None, None) => unreachable!("new posts get a keypair"),
+        };
+
+        let tags = vec![
+            ArweaveTag::new("Blog", blog_pubkey.to_string()),
+            ArweaveTag::new("Post", post_pubkey.to_string()),
+            ArweaveTag::new("Post-Title", local.title.clone()),
+        ];
+
+        let mut base = PathManifest::new();
+        let mut image = local
+            .image
+            .as_ref()
+            .map(|image| (image.data.as_slice(), local.title.as_str()));
+        if let (Some(local_image), Some((post, locked))) = (&local.image, existing) {
+            if locked.image.as_ref() == Some(&local_image.hash) {
+                let current = self.get_post(post).await?;
+                let previous = self.get_post_manifest(&current.arweave_hash).await?;
+                for (path, id) in previous.paths() {
+                    if path.starts_with(POST_IMAGES_DIR) || path == POST_MEDIA_PATH {
+                        base.insert(path, id)?;
+                    }
+                }
+                image = None;
+            }
+        }
+
+        let mut assets = BTreeMap::new();
+        for asset in &local.assets {
+            let reused = existing
+                .and_then(|(_, locked)| locked.assets.get(&asset.path))
+                .filter(|locked| locked.hash == asset.hash);
+            let id = match reused {
+                Some(locked) => locked.id.clone(),
+                None => {
+                    self.content_store()
+                        .put_signed(&asset.data, &asset.content_type, &tags, author)
+                        .await?
+                        .0
+                }
+            };
+            base.insert(&asset.path, id.clone())?;
+            assets.insert(
+                asset.path.clone(),
+                LockedAsset {
+                    hash: asset.hash.clone(),
+                    id,
+                },
+            );
+        }
+
+        let content_id = self
+            .upload_post_files(author, &tags, base, Some(&local.text), image, &[])
+            .await?;
+
+        let result = match &post_keypair {
+            Some(post_keypair) => {
+                let instruction = self.create_post_instruction(
+                    author.pubkey(),
+                    post_pubkey,
+                    blog_pubkey,
+                    local.title.clone(),
+                    local.summary.clone(),
+                    content_id.0.clone(),
+                )?;
+                self.send_instructions(&[instruction], author, &[author, post_keypair])
+                    .await?
+            }
+            None => {
+                let instruction = self.update_post_instruction(
+                    author.pubkey(),
+                    post_pubkey,
+                    Some(local.title.clone()),
+                    Some(local.summary.clone()),
+                    Some(content_id.0.clone()),
+                )?;
+                self.send_instructions(&[instruction], author, &[author])
+                    .await?
+            }
+        };
+
+        let entry = LockedPost {
+            post: post_pubkey.to_string(),
+            hash: local.hash.clone(),
+            content_id: content_id.0,
+            image: local.image.as_ref().map(|image| image.hash.clone()),
+            assets,
+        };
+        Ok((entry, post_pubkey, result))
+    }
+}
+
+fn parse_pubkey(value: &str) -> Result<Pubkey> {
+    Pubkey::from_str(value).map_err(|e| BlogClientError {
+        message: format!("Invalid pubkey {} in lockfile: {}", value, e),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, path: &str, contents: &str) {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn frontmatter_is_split_from_the_body() {
+        let text = "---\ntitle: \"Quoted: \\\"yes\\\"\"\ndescription: plain text\ntags: [rust, \"web\"]\n\
+            draft: false\nunknown: ignored\n---\n\n# Heading\n";
+        let (frontmatter, body) = parse_frontmatter(text).unwrap();
+        assert_eq!(frontmatter.title.as_deref(), Some("Quoted: \"yes\""));
+        assert_eq!(frontmatter.description.as_deref(), Some("plain text"));
+        assert_eq!(frontmatter.tags, ["rust", "web"]);
+        assert!(!frontmatter.draft);
+        assert_eq!(frontmatter.author, None);
+        assert_eq!(body, "# Heading\n");
+
+        let (frontmatter, body) = parse_frontmatter("---\ndraft: true\n---").unwrap();
+        assert!(frontmatter.draft);
+        assert_eq!(body, "");
+
+        assert!(parse_frontmatter("# No frontmatter\n").is_none());
+        assert!(parse_frontmatter("---\ntitle: unterminated\n").is_none());
+    }
+
+    #[test]
+    fn files_outside_the_directory_are_refused() {
+        let parent = tempfile::tempdir().unwrap();
+        write(parent.path(), "secret.txt", "not for publishing");
+        let dir = parent.path().join("blog");
+
+        write(&dir, "post.md", "---\ntitle: Post\n---\n\n[secret](../secret.txt)\n");
+        let error = load_post(&dir, "post.md").unwrap_err();
+        assert_eq!(error.message, "post.md: ../secret.txt is outside the synced directory");
+
+        write(&dir, "post.md", "---\ntitle: Post\nimage: ../secret.txt\n---\n\nBody\n");
+        let error = load_post(&dir, "post.md").unwrap_err();
+        assert_eq!(error.message, "post.md: ../secret.txt is outside the synced directory");
+
+        write(&dir, "post.md", "---\ntitle: Post\nimage: missing.png\n---\n\nBody\n");
+        let error = load_post(&dir, "post.md").unwrap_err();
+        assert_eq!(error.message, "post.md: image missing.png does not exist");
+    }
+
+    #[test]
+    fn only_links_in_the_body_text_are_rewritten() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "posts/notes.txt", "notes");
+        write(dir.path(), "images/photo.png", "png");
+        write(
+            dir.path(),
+            "posts/post.md",
+            "---\ntitle: Post\ndescription: see [notes](notes.txt)\n---\n\n\
+            [notes](notes.txt) and `[notes](notes.txt)`\n\n\
+            ```\n[notes](notes.txt)\n```\n\n\
+            ![photo](../images/photo.png#top) [elsewhere](https://example.com/notes.txt)\n",
+        );
+
+        let post = load_post(dir.path(), "posts/post.md").unwrap().unwrap();
+        assert_eq!(
+            post.text,
+            "---\ntitle: Post\ndescription: see [notes](notes.txt)\n---\n\n\
+            [notes](files/posts/notes.txt) and `[notes](notes.txt)`\n\n\
+            ```\n[notes](notes.txt)\n```\n\n\
+            ![photo](files/images/photo.png#top) [elsewhere](https://example.com/notes.txt)\n"
+        );
+        let assets: Vec<&str> = post.assets.iter().map(|asset| asset.path.as_str()).collect();
+        assert_eq!(assets, ["files/posts/notes.txt", "files/images/photo.png"]);
+    }
+}
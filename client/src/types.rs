@@ -1,36 +1,26 @@
 use serde::{Deserialize, Serialize};
-use solana_sdk::pubkey::Pubkey;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct BlogInfo {
-    pub pubkey: Pubkey,
-    pub authority: Pubkey,
-    pub title: String,
-    pub description: String,
-    pub post_count: u64,
-    pub created_at: i64,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct PostInfo {
-    pub pubkey: Pubkey,
-    pub author: Pubkey,
-    pub blog: Pubkey,
-    pub title: String,
-    pub content: String,
-    pub arweave_hash: String,
-    pub created_at: i64,
-    pub updated_at: i64,
-}
+pub use solana_blog_types::{BlogInfo, PostInfo};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreatePostRequest {
     pub title: String,
     pub content: String,
     pub image_data: Option<Vec<u8>>, // Optional image to upload to Arweave
+    /// Declared type of `image_data`; posting fails when the image is not
+    /// of this type. Images are re-encoded, so it does not pick the format.
     pub image_content_type: Option<String>,
 }
 
+/// Extra file stored with a post under `path` in its path manifest, such as
+/// `files/notes.txt` or `files/slides.pdf`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostAttachment {
+    pub path: String,
+    pub data: Vec<u8>,
+    pub content_type: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct BlogClientError {
     pub message: String,
@@ -1,11 +1,6 @@
 use dioxus::prelude::*;
 use std::collections::HashMap;
-#[cfg(not(target_arch = "wasm32"))]
-use solana_blog_client::{BlogInfo, PostInfo};
-#[cfg(target_arch = "wasm32")]
 use crate::services::{BlogInfo, PostInfo, BlogService, WalletService};
-#[cfg(not(target_arch = "wasm32"))]
-use crate::services::{BlogService, WalletService};
 use crate::pages::{Home, About, BlogView, CreatePost, PostView, Admin};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -1,6 +1,3 @@
-#[cfg(not(target_arch = "wasm32"))]
-use solana_blog_client::PostInfo;
-#[cfg(target_arch = "wasm32")]
 use crate::services::PostInfo;
 use dioxus::prelude::*;
 
@@ -122,4 +119,36 @@ pub fn LoadingSpinner(cx: Scope<LoadingSpinnerProps>) -> Element {
             }
         }
     })
-} 
\ No newline at end of file
+} 
+
+#[derive(Props, PartialEq)]
+pub struct ProgressBarProps {
+    pub message: String,
+    /// 0 to 100
+    pub percent: u32,
+    pub detail: String,
+}
+
+pub fn ProgressBar(cx: Scope<ProgressBarProps>) -> Element {
+    let width = cx.props.percent.min(100);
+    cx.render(rsx! {
+        div {
+            class: "flex flex-col items-center justify-center py-12 w-full max-w-md",
+            p {
+                class: "text-gray-400 text-sm uppercase tracking-wider mb-4",
+                "{cx.props.message}"
+            }
+            div {
+                class: "w-full h-2 border border-gray-700",
+                div {
+                    class: "h-full bg-white transition-all",
+                    style: "width: {width}%;"
+                }
+            }
+            p {
+                class: "text-gray-500 text-xs mt-2 font-mono",
+                "{cx.props.detail}"
+            }
+        }
+    })
+}
@@ -1,5 +1,26 @@
 pub const DEVNET_RPC_URL: &str = "https://api.devnet.solana.com";
+pub const DEVNET_WS_URL: &str = "wss://api.devnet.solana.com";
+
+// Cluster to read from, set with `BLOG_RPC_URL`/`BLOG_WS_URL` at build time
+pub const RPC_URL: &str = match option_env!("BLOG_RPC_URL") {
+    Some(url) => url,
+    None => DEVNET_RPC_URL,
+};
+pub const WS_URL: &str = match option_env!("BLOG_WS_URL") {
+    Some(url) => url,
+    None => DEVNET_WS_URL,
+};
 pub const PROGRAM_ID: &str = "BmqUqrFGJA7C9nzv787SrC59PUs2kSUvgdhDru6NQFmQ";
 
+// Base URL of a `blog-api` server, set with `BLOG_API_URL` at build time.
+// Without it the web build reads accounts straight from `RPC_URL`.
+pub const API_URL: Option<&str> = option_env!("BLOG_API_URL");
+
 // Mock blog pubkey for demo purposes (valid base58 format)
-pub const DEMO_BLOG_PUBKEY: &str = "11111111111111111111111111111112"; 
\ No newline at end of file
+pub const DEMO_BLOG_PUBKEY: &str = "11111111111111111111111111111112";
+
+// Blog shown on the home page, set with `BLOG_PUBKEY` at build time
+pub const BLOG_PUBKEY: &str = match option_env!("BLOG_PUBKEY") {
+    Some(pubkey) => pubkey,
+    None => DEMO_BLOG_PUBKEY,
+};
@@ -80,10 +80,6 @@ pub fn Admin(cx: Scope<AdminProps>) -> Element {
                             app_state.set(state);
                             success_msg.set(Some(format!("Wallet connected successfully! Address: {}", 
                                 crate::utils::truncate_pubkey(&public_key))));
-                            
-                            // Log for debugging
-                            #[cfg(target_arch = "wasm32")]
-                            web_sys::console::log_1(&format!("✅ Wallet connected: {}", public_key).into());
                         }
                         Err(err) => {
                             error_msg.set(Some(err));
@@ -111,7 +107,7 @@ pub fn Admin(cx: Scope<AdminProps>) -> Element {
         let app_state = cx.props.app_state.clone();
         move |_| {
             let mut new_state = app_state.get().clone();
-            new_state.current_route = Route::CreatePost(crate::config::DEMO_BLOG_PUBKEY.to_string());
+            new_state.current_route = Route::CreatePost(crate::config::BLOG_PUBKEY.to_string());
             app_state.set(new_state);
         }
     };
@@ -140,8 +136,6 @@ pub fn Admin(cx: Scope<AdminProps>) -> Element {
             match arweave_service.store_arweave_key(&key) {
                 Ok(_) => {
                     success_msg.set(Some("Arweave wallet configured successfully!".to_string()));
-                    #[cfg(target_arch = "wasm32")]
-                    web_sys::console::log_1(&"✅ Arweave wallet stored securely".into());
                 }
                 Err(err) => {
                     error_msg.set(Some(format!("Failed to store Arweave wallet: {}", err)));
@@ -166,12 +160,6 @@ pub fn Admin(cx: Scope<AdminProps>) -> Element {
         }
     };
 
-    // Debug logging
-    #[cfg(target_arch = "wasm32")]
-    {
-        web_sys::console::log_1(&format!("Admin: wallet_connected={}, is_logged_in={}", wallet_connected, is_logged_in).into());
-    }
-
     // If wallet is connected and user is admin, show admin panel
     if wallet_connected && is_logged_in {
         cx.render(rsx! {
@@ -471,7 +459,7 @@ pub fn Admin(cx: Scope<AdminProps>) -> Element {
                                                             let post_id = post.id.clone();
                                                             move |_| {
                                                                 let mut new_state = app_state.get().clone();
-                                                                new_state.current_route = Route::CreatePost(crate::config::DEMO_BLOG_PUBKEY.to_string());
+                                                                new_state.current_route = Route::CreatePost(crate::config::BLOG_PUBKEY.to_string());
                                                                 app_state.set(new_state);
                                                             }
                                                         },
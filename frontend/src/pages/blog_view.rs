@@ -1,10 +1,8 @@
 use dioxus::prelude::*;
-#[cfg(not(target_arch = "wasm32"))]
-use solana_blog_client::{BlogInfo, PostInfo};
-#[cfg(target_arch = "wasm32")]
 use crate::services::{BlogInfo, PostInfo};
 use crate::app::{AppState, Route};
 use crate::components::LoadingSpinner;
+use crate::services::PostEvent;
 
 #[derive(Props, PartialEq)]
 pub struct BlogViewProps {
@@ -16,97 +14,63 @@ pub fn BlogView(cx: Scope<BlogViewProps>) -> Element {
     let blog_info = use_state(cx, || None::<BlogInfo>);
     let posts = use_state(cx, || Vec::<PostInfo>::new());
     let loading = use_state(cx, || true);
+    let load_error = use_state(cx, || None::<String>);
 
     // Load blog data
     use_effect(cx, &cx.props.blog_pubkey, {
         let blog_info = blog_info.clone();
         let posts = posts.clone();
         let loading = loading.clone();
+        let load_error = load_error.clone();
+        let blog_service = cx.props.app_state.get().blog_service.clone();
         let blog_pubkey = cx.props.blog_pubkey.clone();
         
         move |_| async move {
-            // Simulate API delay
-            #[cfg(target_arch = "wasm32")]
-            {
-                let _ = gloo_timers::future::TimeoutFuture::new(1000).await;
-            }
-            #[cfg(not(target_arch = "wasm32"))]
-            {
-                use std::time::Duration;
-                tokio::time::sleep(Duration::from_millis(1000)).await;
+            loading.set(true);
+            load_error.set(None);
+            let loaded = async {
+                let blog = blog_service.get_blog(&blog_pubkey).await?;
+                let blog_posts = blog_service.get_posts(&blog_pubkey).await?;
+                Ok::<_, String>((blog, blog_posts))
+            };
+            match loaded.await {
+                Ok((blog, blog_posts)) => {
+                    blog_info.set(Some(blog));
+                    posts.set(blog_posts);
+                }
+                Err(e) => {
+                    blog_info.set(None);
+                    posts.set(Vec::new());
+                    load_error.set(Some(e));
+                }
             }
+            loading.set(false);
+        }
+    });
 
-            // Mock blog data
-            if blog_pubkey == crate::config::DEMO_BLOG_PUBKEY {
-                let mock_blog = BlogInfo {
-                    #[cfg(target_arch = "wasm32")]
-                    pubkey: blog_pubkey.clone(),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    pubkey: blog_pubkey.parse().unwrap(),
-                    #[cfg(target_arch = "wasm32")]
-                    authority: "11111111111111111111111111111114".to_string(),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    authority: "11111111111111111111111111111114".parse().unwrap(),
-                    title: "Solana verystochastic".to_string(),
-                    description: "Decentralized finance disasters and lessons from the blockchain".to_string(),
-                    post_count: 2,
-                    created_at: 1699123456,
-                };
-
-                let mock_posts = vec![
-                    PostInfo {
-                        #[cfg(target_arch = "wasm32")]
-                        pubkey: "11111111111111111111111111111115".to_string(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        pubkey: "11111111111111111111111111111115".parse().unwrap(),
-                        #[cfg(target_arch = "wasm32")]
-                        blog: blog_pubkey.clone(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        blog: blog_pubkey.parse().unwrap(),
-                        #[cfg(target_arch = "wasm32")]
-                        author: "11111111111111111111111111111116".to_string(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        author: "11111111111111111111111111111116".parse().unwrap(),
-                        title: "GMX - verystochastic".to_string(),
-                        content: "The largest GMX exploit in DeFi history. Over $50M drained from liquidity pools due to a price manipulation attack on Arbitrum.".to_string(),
-                        created_at: 1699789012,
-                        #[cfg(target_arch = "wasm32")]
-                        image_url: Some("/api/placeholder/600/300".to_string()),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        arweave_hash: "mock_arweave_hash_1".to_string(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        updated_at: 1699789012,
-                    },
-                    PostInfo {
-                        #[cfg(target_arch = "wasm32")]
-                        pubkey: "11111111111111111111111111111117".to_string(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        pubkey: "11111111111111111111111111111117".parse().unwrap(),
-                        #[cfg(target_arch = "wasm32")]
-                        blog: blog_pubkey.clone(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        blog: blog_pubkey.parse().unwrap(),
-                        #[cfg(target_arch = "wasm32")]
-                        author: "11111111111111111111111111111116".to_string(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        author: "11111111111111111111111111111116".parse().unwrap(),
-                        title: "Solana Validator - verystochastic".to_string(),
-                        content: "Major Solana validator cluster went down for 17 hours due to a botched network upgrade.".to_string(),
-                        created_at: 1699702345,
-                        #[cfg(target_arch = "wasm32")]
-                        image_url: Some("/api/placeholder/600/300".to_string()),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        arweave_hash: "mock_arweave_hash_2".to_string(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        updated_at: 1699702345,
-                    },
-                ];
+    // Keep the post list current while the page is open
+    use_effect(cx, &cx.props.blog_pubkey, {
+        let posts = posts.clone();
+        let service = cx.props.app_state.get().blog_service.clone();
+        let blog_pubkey = cx.props.blog_pubkey.clone();
 
-                blog_info.set(Some(mock_blog));
-                posts.set(mock_posts);
+        move |_| async move {
+            let mut watch = match service.watch_blog(&blog_pubkey).await {
+                Ok(watch) => watch,
+                // Without a websocket the list simply stays as loaded
+                Err(_) => return,
+            };
+            while let Some(event) = watch.next().await {
+                #[cfg(not(target_arch = "wasm32"))]
+                posts.with_mut(|posts| apply_post_event(posts, event));
+                #[cfg(target_arch = "wasm32")]
+                {
+                    let PostEvent::Changed = event;
+                    if let Ok(latest) = service.get_posts(&blog_pubkey).await {
+                        posts.set(latest);
+                    }
+                }
             }
-
-            loading.set(false);
         }
     });
 
@@ -224,7 +188,17 @@ pub fn BlogView(cx: Scope<BlogViewProps>) -> Element {
                                 "ALL POSTS"
                             }
                             
-                            if posts.is_empty() {
+                            if let Some(error) = load_error.get() {
+                                rsx! {
+                                    div {
+                                        class: "text-center py-12",
+                                        p {
+                                            class: "text-red-400 text-sm",
+                                            "Failed to load blog: {error}"
+                                        }
+                                    }
+                                }
+                            } else if posts.is_empty() {
                                 rsx! {
                                     div {
                                         class: "text-center py-12",
@@ -294,4 +268,20 @@ pub fn BlogView(cx: Scope<BlogViewProps>) -> Element {
             }
         }
     })
-} 
\ No newline at end of file
+}
+
+/// Fold a live event into the list, which is kept newest first
+#[cfg(not(target_arch = "wasm32"))]
+fn apply_post_event(posts: &mut Vec<PostInfo>, event: PostEvent) {
+    match event {
+        PostEvent::Created(post) | PostEvent::Updated(post) => {
+            posts.retain(|existing| existing.pubkey != post.pubkey);
+            let index = posts
+                .iter()
+                .position(|existing| existing.created_at < post.created_at)
+                .unwrap_or(posts.len());
+            posts.insert(index, post);
+        }
+        PostEvent::Deleted(pubkey) => posts.retain(|post| post.pubkey != pubkey),
+    }
+}
@@ -1,7 +1,17 @@
 use dioxus::prelude::*;
 use crate::app::{AppState, Route};
-use crate::components::LoadingSpinner;
-use crate::services::{ArweaveService, PostContent};
+use crate::components::{LoadingSpinner, ProgressBar};
+use crate::services::{ArweaveService, CreatePostRequest, PostContent, UploadProgress, WalletError};
+use solana_blog_types::state::BlogPost;
+
+// Longest prefix of `text` that fits in `max` bytes
+fn truncate(text: &str, max: usize) -> &str {
+    let mut end = text.len().min(max);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
 
 #[derive(Props, PartialEq)]
 pub struct CreatePostProps {
@@ -14,6 +24,7 @@ pub fn CreatePost(cx: Scope<CreatePostProps>) -> Element {
     let loading = use_state(cx, || false);
     let error = use_state(cx, || None::<String>);
     let success = use_state(cx, || false);
+    let upload_progress = use_state(cx, || None::<UploadProgress>);
     
     // Form state
     let title = use_state(cx, || String::new());
@@ -80,8 +91,11 @@ pub fn CreatePost(cx: Scope<CreatePostProps>) -> Element {
         let loading = loading.clone();
         let error = error.clone();
         let success = success.clone();
+        let upload_progress = upload_progress.clone();
         let arweave_service = arweave_service;
         let wallet_public_key = wallet_public_key.clone();
+        let app_state = cx.props.app_state.clone();
+        let blog_pubkey = blog_pubkey.clone();
         
         move |_| {
             // Check if wallet is connected
@@ -94,8 +108,14 @@ pub fn CreatePost(cx: Scope<CreatePostProps>) -> Element {
                 error.set(Some("Title and content are required".to_string()));
                 return;
             }
+
+            if title.get().len() > BlogPost::MAX_TITLE_LENGTH {
+                error.set(Some(format!("Title must be at most {} bytes", BlogPost::MAX_TITLE_LENGTH)));
+                return;
+            }
             
             error.set(None);
+            upload_progress.set(None);
             loading.set(true);
             
             cx.spawn({
@@ -108,6 +128,9 @@ pub fn CreatePost(cx: Scope<CreatePostProps>) -> Element {
                 let content = content.get().clone();
                 let tags_str = tags.get().clone();
                 let wallet_public_key = wallet_public_key.clone().unwrap_or_default();
+                let blog_service = app_state.get().blog_service.clone();
+                let wallet = app_state.get().wallet_service.clone();
+                let blog_pubkey = blog_pubkey.clone();
                 
                 async move {
                     // Parse tags
@@ -124,15 +147,16 @@ pub fn CreatePost(cx: Scope<CreatePostProps>) -> Element {
                         content: content.clone(),
                         tags,
                         created_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-                        author: "verystochastic".to_string(),
+                        author: wallet_public_key.clone(),
                     };
                     
-                    // Check wallet balance first
-                    match arweave_service.check_wallet_balance(&wallet_public_key).await {
+                    // Refuse to start an upload the Arweave wallet can't pay for
+                    let content_size = arweave_service.format_post_content(&post).len();
+                    match arweave_service.check_wallet_balance(content_size).await {
                         Ok(has_balance) => {
                             if !has_balance {
                                 loading.set(false);
-                                error.set(Some("Insufficient wallet balance for Arweave upload".to_string()));
+                                error.set(Some("Insufficient AR balance for Arweave upload".to_string()));
                                 return;
                             }
                         }
@@ -143,22 +167,38 @@ pub fn CreatePost(cx: Scope<CreatePostProps>) -> Element {
                         }
                     }
                     
-                    // Get upload cost estimate
-                    let content_size = content.len();
                     match arweave_service.get_upload_cost(content_size).await {
-                        Ok(cost) => {
-                            #[cfg(target_arch = "wasm32")]
-                            web_sys::console::log_1(&format!("💰 Estimated upload cost: ${:.2}", cost).into());
-                        }
-                        Err(err) => {
-                            #[cfg(target_arch = "wasm32")]
-                            web_sys::console::log_1(&format!("⚠️ Could not estimate cost: {}", err).into());
-                        }
+                        Ok(cost) => crate::utils::log(&format!("Estimated upload cost: {:.6} AR", cost)),
+                        Err(err) => crate::utils::log(&format!("Could not estimate upload cost: {}", err)),
                     }
                     
                     // Upload to Arweave with wallet authentication
-                    match arweave_service.upload_post(post, &wallet_public_key).await {
+                    let on_progress = {
+                        let upload_progress = upload_progress.clone();
+                        move |progress| upload_progress.set(Some(progress))
+                    };
+                    match arweave_service.upload_post(post, &wallet_public_key, on_progress).await {
                         Ok(tx_id) => {
+                            // The chain keeps a short summary; the full post is on Arweave
+                            let summary = if description.is_empty() { &content } else { &description };
+                            let request = CreatePostRequest {
+                                title: title.clone(),
+                                content: truncate(summary, BlogPost::MAX_CONTENT_LENGTH).to_string(),
+                                arweave_hash: tx_id,
+                            };
+                            match blog_service.create_post(&wallet, &blog_pubkey, request).await {
+                                Ok(post_pubkey) => crate::utils::log(&format!("Published post {}", post_pubkey)),
+                                Err(err) => {
+                                    loading.set(false);
+                                    error.set(Some(match err {
+                                        WalletError::Rejected => "Publishing cancelled: the transaction was rejected in your wallet. The content is already on Arweave, so publishing again uploads it again.".to_string(),
+                                        WalletError::Network(message) => format!("Could not reach the Solana network: {}", message),
+                                        err => format!("Failed to publish post: {}", err),
+                                    }));
+                                    return;
+                                }
+                            }
+
                             // Clear draft from localStorage
                             #[cfg(target_arch = "wasm32")]
                             {
@@ -219,6 +259,26 @@ pub fn CreatePost(cx: Scope<CreatePostProps>) -> Element {
     }
 
     if *loading.get() {
+        if let Some(progress) = upload_progress.get() {
+            return cx.render(rsx! {
+                div {
+                    class: "min-h-screen bg-black text-white flex items-center justify-center",
+                    style: "font-family: 'JetBrains Mono', 'IBM Plex Mono', Consolas, monospace;",
+                    ProgressBar {
+                        message: "Publishing to Arweave...".to_string(),
+                        percent: progress.percent(),
+                        detail: format!(
+                            "chunk {}/{} · {} / {} bytes",
+                            progress.chunks_uploaded,
+                            progress.total_chunks,
+                            progress.bytes_uploaded,
+                            progress.total_bytes
+                        )
+                    }
+                }
+            });
+        }
+
         return cx.render(rsx! {
             div {
                 class: "min-h-screen bg-black text-white flex items-center justify-center",
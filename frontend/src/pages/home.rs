@@ -1,11 +1,9 @@
 use dioxus::prelude::*;
-#[cfg(not(target_arch = "wasm32"))]
-use solana_blog_client::{BlogInfo, PostInfo};
-#[cfg(target_arch = "wasm32")]
 use crate::services::{BlogInfo, PostInfo};
+use crate::services::{SearchFilters, SearchResult};
 use crate::app::{AppState, Route};
 use crate::components::LoadingSpinner;
-use crate::config::DEMO_BLOG_PUBKEY;
+use crate::config::BLOG_PUBKEY;
 
 #[derive(Props, PartialEq)]
 pub struct HomeProps {
@@ -16,114 +14,37 @@ pub fn Home(cx: Scope<HomeProps>) -> Element {
     let blogs = use_state(cx, || Vec::<BlogInfo>::new());
     let recent_posts = use_state(cx, || Vec::<PostInfo>::new());
     let loading = use_state(cx, || true);
+    let load_error = use_state(cx, || None::<String>);
+    let search_text = use_state(cx, String::new);
+    let search_tag = use_state(cx, String::new);
+    // Dates as the date inputs give them, `YYYY-MM-DD`
+    let search_from = use_state(cx, String::new);
+    let search_to = use_state(cx, String::new);
+    // `None` shows the latest posts; `Some` shows results for the last search
+    let search_results = use_state(cx, || None::<Vec<SearchResult>>);
+    let search_error = use_state(cx, || None::<String>);
+    let searching = use_state(cx, || false);
 
-    // Simulate loading data
+    // Load the blog and its latest posts
     use_effect(cx, (), {
         let blogs = blogs.clone();
         let recent_posts = recent_posts.clone();
         let loading = loading.clone();
+        let load_error = load_error.clone();
+        let blog_service = cx.props.app_state.get().blog_service.clone();
         move |_| async move {
-            // Simulate API delay
-            #[cfg(target_arch = "wasm32")]
-            {
-                let _ = gloo_timers::future::TimeoutFuture::new(1000).await;
-            }
-            #[cfg(not(target_arch = "wasm32"))]
-            {
-                use std::time::Duration;
-                tokio::time::sleep(Duration::from_millis(1000)).await;
-            }
-
-            // Mock data
-            let mock_blog = BlogInfo {
-                #[cfg(target_arch = "wasm32")]
-                pubkey: DEMO_BLOG_PUBKEY.to_string(),
-                #[cfg(not(target_arch = "wasm32"))]
-                pubkey: DEMO_BLOG_PUBKEY.parse().unwrap(),
-                #[cfg(target_arch = "wasm32")]
-                authority: "11111111111111111111111111111114".to_string(),
-                #[cfg(not(target_arch = "wasm32"))]
-                authority: "11111111111111111111111111111114".parse().unwrap(),
-                title: "Solana verystochastic".to_string(),
-                description: "Decentralized finance disasters and lessons from the blockchain".to_string(),
-                post_count: 5,
-                created_at: 1699123456,
+            let loaded = async {
+                let blog = blog_service.get_blog(BLOG_PUBKEY).await?;
+                let posts = blog_service.get_posts(BLOG_PUBKEY).await?;
+                Ok::<_, String>((blog, posts))
             };
-
-            let mock_posts = vec![
-                PostInfo {
-                    #[cfg(target_arch = "wasm32")]
-                    pubkey: "11111111111111111111111111111115".to_string(),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    pubkey: "11111111111111111111111111111115".parse().unwrap(),
-                    #[cfg(target_arch = "wasm32")]
-                    blog: DEMO_BLOG_PUBKEY.to_string(),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    blog: DEMO_BLOG_PUBKEY.parse().unwrap(),
-                    #[cfg(target_arch = "wasm32")]
-                    author: "11111111111111111111111111111116".to_string(),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    author: "11111111111111111111111111111116".parse().unwrap(),
-                    title: "GMX - verystochastic".to_string(),
-                    content: "The largest GMX exploit in DeFi history. Over $50M drained from liquidity pools due to a price manipulation attack on Arbitrum. The attacker exploited the GMX price feed oracle by creating massive positions with borrowed funds, manipulating the underlying asset prices, and profiting from the price discrepancy. This incident highlights the risks of insufficient oracle protection and the importance of robust price validation mechanisms in perpetual trading protocols.".to_string(),
-                    created_at: 1699789012,
-                    #[cfg(target_arch = "wasm32")]
-                    image_url: Some("/api/placeholder/600/300".to_string()),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    arweave_hash: "mock_arweave_hash_1".to_string(),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    updated_at: 1699789012,
-                },
-                PostInfo {
-                    #[cfg(target_arch = "wasm32")]
-                    pubkey: "11111111111111111111111111111117".to_string(),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    pubkey: "11111111111111111111111111111117".parse().unwrap(),
-                    #[cfg(target_arch = "wasm32")]
-                    blog: DEMO_BLOG_PUBKEY.to_string(),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    blog: DEMO_BLOG_PUBKEY.parse().unwrap(),
-                    #[cfg(target_arch = "wasm32")]
-                    author: "11111111111111111111111111111116".to_string(),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    author: "11111111111111111111111111111116".parse().unwrap(),
-                    title: "Solana Validator - verystochastic".to_string(),
-                    content: "Major Solana validator cluster went down for 17 hours due to a botched network upgrade. The incident occurred during a routine protocol update that introduced a consensus bug, causing 80% of validators to halt block production. Recovery required emergency coordination between core developers and validator operators to roll back the problematic update and restore network stability.".to_string(),
-                    created_at: 1699702345,
-                    #[cfg(target_arch = "wasm32")]
-                    image_url: Some("/api/placeholder/600/300".to_string()),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    arweave_hash: "mock_arweave_hash_2".to_string(),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    updated_at: 1699702345,
-                },
-                PostInfo {
-                    #[cfg(target_arch = "wasm32")]
-                    pubkey: "11111111111111111111111111111118".to_string(),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    pubkey: "11111111111111111111111111111118".parse().unwrap(),
-                    #[cfg(target_arch = "wasm32")]
-                    blog: DEMO_BLOG_PUBKEY.to_string(),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    blog: DEMO_BLOG_PUBKEY.parse().unwrap(),
-                    #[cfg(target_arch = "wasm32")]
-                    author: "11111111111111111111111111111116".to_string(),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    author: "11111111111111111111111111111116".parse().unwrap(),
-                    title: "DeFi Bridge - verystochastic".to_string(),
-                    content: "Cross-chain bridge exploit leads to $80M drainage from Solana-Ethereum bridge. Attackers exploited a verification bug in the bridge's smart contract, allowing them to mint unlimited wrapped tokens on Ethereum using fake Solana transaction proofs. The vulnerability existed for months before discovery, with the protocol's multi-signature security being bypassed through a sophisticated social engineering attack on bridge validators.".to_string(),
-                    created_at: 1699615678,
-                    #[cfg(target_arch = "wasm32")]
-                    image_url: Some("/api/placeholder/600/300".to_string()),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    arweave_hash: "mock_arweave_hash_3".to_string(),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    updated_at: 1699615678,
-                },
-            ];
-
-            blogs.set(vec![mock_blog]);
-            recent_posts.set(mock_posts);
+            match loaded.await {
+                Ok((blog, posts)) => {
+                    blogs.set(vec![blog]);
+                    recent_posts.set(posts);
+                }
+                Err(e) => load_error.set(Some(e)),
+            }
             loading.set(false);
         }
     });
@@ -132,11 +53,69 @@ pub fn Home(cx: Scope<HomeProps>) -> Element {
         let app_state = cx.props.app_state.clone();
         move |_| {
             let mut new_state = app_state.get().clone();
-            new_state.current_route = Route::Blog(DEMO_BLOG_PUBKEY.to_string());
+            new_state.current_route = Route::Blog(BLOG_PUBKEY.to_string());
             app_state.set(new_state);
         }
     };
 
+    let handle_search = {
+        let blog_service = cx.props.app_state.get().blog_service.clone();
+        let search_text = search_text.clone();
+        let search_tag = search_tag.clone();
+        let search_from = search_from.clone();
+        let search_to = search_to.clone();
+        let search_results = search_results.clone();
+        let search_error = search_error.clone();
+        let searching = searching.clone();
+        move |_| {
+            let text = search_text.get().trim().to_string();
+            search_error.set(None);
+            if text.is_empty() {
+                search_results.set(None);
+                return;
+            }
+            let tag = search_tag.get().trim().trim_start_matches('#');
+            let filters = SearchFilters {
+                tag: (!tag.is_empty()).then(|| tag.to_string()),
+                from: crate::utils::parse_date(search_from.get()),
+                // Inclusive of the chosen day
+                to: crate::utils::parse_date(search_to.get()).map(|to| to + 24 * 60 * 60),
+            };
+
+            searching.set(true);
+            cx.spawn({
+                let blog_service = blog_service.clone();
+                let search_results = search_results.clone();
+                let search_error = search_error.clone();
+                let searching = searching.clone();
+                async move {
+                    match blog_service.search(&text, &filters).await {
+                        Ok(results) => search_results.set(Some(results)),
+                        Err(e) => search_error.set(Some(e)),
+                    }
+                    searching.set(false);
+                }
+            });
+        }
+    };
+
+    let handle_clear_search = {
+        let search_text = search_text.clone();
+        let search_tag = search_tag.clone();
+        let search_from = search_from.clone();
+        let search_to = search_to.clone();
+        let search_results = search_results.clone();
+        let search_error = search_error.clone();
+        move |_| {
+            search_text.set(String::new());
+            search_tag.set(String::new());
+            search_from.set(String::new());
+            search_to.set(String::new());
+            search_results.set(None);
+            search_error.set(None);
+        }
+    };
+
     let handle_about_click = {
         let app_state = cx.props.app_state.clone();
         move |_| {
@@ -175,6 +154,44 @@ pub fn Home(cx: Scope<HomeProps>) -> Element {
                         }
                     }
                     
+                    // Search
+                    form {
+                        class: "flex-1 max-w-sm mx-8 space-y-2",
+                        prevent_default: "onsubmit",
+                        onsubmit: handle_search,
+                        input {
+                            class: "w-full bg-gray-900 border border-gray-700 text-white px-3 py-2 text-sm focus:border-blue-500 focus:outline-none",
+                            r#type: "search",
+                            placeholder: "Search posts...",
+                            value: "{search_text.get()}",
+                            oninput: move |e| search_text.set(e.value.clone())
+                        }
+                        div {
+                            class: "flex space-x-2",
+                            input {
+                                class: "w-1/3 bg-gray-900 border border-gray-700 text-white px-2 py-1 text-xs focus:border-blue-500 focus:outline-none",
+                                r#type: "text",
+                                placeholder: "#tag",
+                                value: "{search_tag.get()}",
+                                oninput: move |e| search_tag.set(e.value.clone())
+                            }
+                            input {
+                                class: "w-1/3 bg-gray-900 border border-gray-700 text-gray-300 px-2 py-1 text-xs focus:border-blue-500 focus:outline-none",
+                                r#type: "date",
+                                title: "Posted on or after",
+                                value: "{search_from.get()}",
+                                oninput: move |e| search_from.set(e.value.clone())
+                            }
+                            input {
+                                class: "w-1/3 bg-gray-900 border border-gray-700 text-gray-300 px-2 py-1 text-xs focus:border-blue-500 focus:outline-none",
+                                r#type: "date",
+                                title: "Posted on or before",
+                                value: "{search_to.get()}",
+                                oninput: move |e| search_to.set(e.value.clone())
+                            }
+                        }
+                    }
+
                     // Navigation
                     nav {
                         class: "flex items-center space-x-8",
@@ -235,7 +252,96 @@ pub fn Home(cx: Scope<HomeProps>) -> Element {
                     }
                 }
 
-                if *loading.get() {
+                if let Some(message) = search_error.get() {
+                    rsx! {
+                        div {
+                            class: "border border-red-800 text-red-400 text-sm px-4 py-3 mb-8",
+                            "{message}"
+                        }
+                    }
+                }
+
+                if *searching.get() {
+                    rsx! {
+                        div {
+                            class: "flex justify-center py-12",
+                            LoadingSpinner { message: "Searching...".to_string() }
+                        }
+                    }
+                } else if let Some(results) = search_results.get() {
+                    rsx! {
+                        div {
+                            class: "space-y-8",
+
+                            div {
+                                class: "flex items-center justify-between mb-6 border-b border-gray-800 pb-2",
+                                h2 {
+                                    class: "text-xl font-bold text-white uppercase",
+                                    "{results.len()} RESULTS FOR \"{search_text.get()}\""
+                                }
+                                button {
+                                    class: "text-sm text-gray-500 hover:text-white uppercase tracking-wider",
+                                    onclick: handle_clear_search,
+                                    "clear ×"
+                                }
+                            }
+
+                            div {
+                                class: "space-y-6",
+                                for result in results.iter() {
+                                    {
+                                        let result_blog = result.blog.clone();
+                                        let result_post = result.post.clone();
+                                        let app_state_clone = cx.props.app_state.clone();
+                                        let title = crate::utils::highlight_segments(&result.title_html);
+                                        let snippet = crate::utils::highlight_segments(&result.snippet_html);
+
+                                        rsx! {
+                                            article {
+                                                key: "{result.post}",
+                                                class: "border-b border-gray-800 pb-6 cursor-pointer hover:bg-gray-900 hover:bg-opacity-30 transition-colors p-4 -mx-4",
+                                                onclick: move |_| {
+                                                    let mut new_state = app_state_clone.get().clone();
+                                                    new_state.current_route = Route::Post(result_blog.clone(), result_post.clone());
+                                                    app_state_clone.set(new_state);
+                                                },
+
+                                                div {
+                                                    class: "flex items-center text-xs text-gray-500 mb-3 font-mono uppercase tracking-wider",
+                                                    span { "{crate::utils::format_timestamp(result.created_at)}" }
+                                                    for tag in result.tags.iter() {
+                                                        span { class: "ml-2 text-gray-400", "#{tag}" }
+                                                    }
+                                                }
+
+                                                h3 {
+                                                    class: "text-lg font-bold mb-3 text-white uppercase",
+                                                    for (text, marked) in title.into_iter() {
+                                                        if marked {
+                                                            rsx! { mark { class: "bg-blue-900 text-white", "{text}" } }
+                                                        } else {
+                                                            rsx! { span { "{text}" } }
+                                                        }
+                                                    }
+                                                }
+                                                p {
+                                                    class: "text-gray-400 leading-relaxed",
+                                                    for (text, marked) in snippet.into_iter() {
+                                                        if marked {
+                                                            rsx! { mark { class: "bg-blue-900 text-white", "{text}" } }
+                                                        } else {
+                                                            rsx! { span { "{text}" } }
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                } else if *loading.get() {
                     rsx! {
                         div {
                             class: "flex justify-center py-12",
@@ -253,6 +359,22 @@ pub fn Home(cx: Scope<HomeProps>) -> Element {
                                 "LATEST INCIDENTS"
                             }
                             
+                            if let Some(error) = load_error.get() {
+                                rsx! {
+                                    p {
+                                        class: "text-red-400 text-sm",
+                                        "Failed to load posts: {error}"
+                                    }
+                                }
+                            } else if recent_posts.is_empty() {
+                                rsx! {
+                                    p {
+                                        class: "text-gray-500 text-lg",
+                                        "No posts yet."
+                                    }
+                                }
+                            }
+
                             div {
                                 class: "space-y-6",
                                 for (idx, post) in recent_posts.iter().enumerate() {
@@ -1,10 +1,6 @@
 use dioxus::prelude::*;
-#[cfg(not(target_arch = "wasm32"))]
-use solana_blog_client::{BlogInfo, PostInfo};
-#[cfg(target_arch = "wasm32")]
-use crate::services::{BlogInfo, PostInfo};
+use crate::services::{ArweaveService, BlogInfo, ImageSources, PostInfo};
 use crate::app::{AppState, Route};
-use crate::config::DEMO_BLOG_PUBKEY;
 
 #[derive(Props, PartialEq)]
 pub struct PostViewProps {
@@ -17,127 +13,39 @@ pub fn PostView(cx: Scope<PostViewProps>) -> Element {
     let post_info = use_state(cx, || None::<PostInfo>);
     let blog_info = use_state(cx, || None::<BlogInfo>);
     let loading = use_state(cx, || true);
+    let image = use_state(cx, || None::<ImageSources>);
     
     // Load post data on component mount
     use_effect(cx, (&cx.props.blog_pubkey, &cx.props.post_pubkey), {
         let post_info = post_info.clone();
         let blog_info = blog_info.clone();
         let loading = loading.clone();
+        let image = image.clone();
+        let blog_service = cx.props.app_state.get().blog_service.clone();
         let blog_pubkey = cx.props.blog_pubkey.clone();
         let post_pubkey = cx.props.post_pubkey.clone();
         
         move |_| async move {
-            // Simulate API delay
-            #[cfg(target_arch = "wasm32")]
-            {
-                let _ = gloo_timers::future::TimeoutFuture::new(1000).await;
-            }
-            #[cfg(not(target_arch = "wasm32"))]
-            {
-                use std::time::Duration;
-                tokio::time::sleep(Duration::from_millis(1000)).await;
-            }
-            
-            // Mock data
-            if blog_pubkey == DEMO_BLOG_PUBKEY {
-                let mock_blog = BlogInfo {
-                    #[cfg(target_arch = "wasm32")]
-                    pubkey: blog_pubkey.clone(),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    pubkey: blog_pubkey.parse().unwrap_or_default(),
-                    #[cfg(target_arch = "wasm32")]
-                    authority: "11111111111111111111111111111114".to_string(),
-                    #[cfg(not(target_arch = "wasm32"))]
-                    authority: "11111111111111111111111111111114".parse().unwrap(),
-                    title: "Solana verystochastic".to_string(),
-                    description: "Decentralized finance disasters and lessons from the blockchain".to_string(),
-                    post_count: 3,
-                    created_at: 1699123456,
-                };
-                
-                // Determine which post to show based on post_pubkey
-                let mock_post = if post_pubkey == "11111111111111111111111111111115" {
-                    PostInfo {
-                        #[cfg(target_arch = "wasm32")]
-                        pubkey: "11111111111111111111111111111115".to_string(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        pubkey: "11111111111111111111111111111115".parse().unwrap(),
-                        #[cfg(target_arch = "wasm32")]
-                        blog: blog_pubkey.clone(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        blog: blog_pubkey.parse().unwrap(),
-                        #[cfg(target_arch = "wasm32")]
-                        author: "11111111111111111111111111111116".to_string(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        author: "11111111111111111111111111111116".parse().unwrap(),
-                        title: "GMX - verystochastic".to_string(),
-                        content: "The largest GMX exploit in DeFi history. Over $50M drained from liquidity pools due to a price manipulation attack on Arbitrum. The attacker exploited the GMX price feed oracle by creating massive positions with borrowed funds, manipulating the underlying asset prices, and profiting from the price discrepancy. This incident highlights the risks of insufficient oracle protection and the importance of robust price validation mechanisms in perpetual trading protocols.".to_string(),
-                        created_at: 1699789012,
-                        #[cfg(target_arch = "wasm32")]
-                        image_url: Some("/api/placeholder/800/400".to_string()),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        arweave_hash: "mock_arweave_hash_1".to_string(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        updated_at: 1699789012,
-                    }
-                } else if post_pubkey == "11111111111111111111111111111117" {
-                    PostInfo {
-                        #[cfg(target_arch = "wasm32")]
-                        pubkey: "11111111111111111111111111111117".to_string(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        pubkey: "11111111111111111111111111111117".parse().unwrap(),
-                        #[cfg(target_arch = "wasm32")]
-                        blog: blog_pubkey.clone(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        blog: blog_pubkey.parse().unwrap(),
-                        #[cfg(target_arch = "wasm32")]
-                        author: "11111111111111111111111111111116".to_string(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        author: "11111111111111111111111111111116".parse().unwrap(),
-                        title: "Solana Validator - verystochastic".to_string(),
-                        content: "Major Solana validator cluster went down for 17 hours due to a botched network upgrade. The incident occurred during a routine protocol update that introduced a consensus bug, causing 80% of validators to halt block production. Recovery required emergency coordination between core developers and validator operators to roll back the problematic update and restore network stability.".to_string(),
-                        created_at: 1699702345,
-                        #[cfg(target_arch = "wasm32")]
-                        image_url: Some("/api/placeholder/800/400".to_string()),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        arweave_hash: "mock_arweave_hash_2".to_string(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        updated_at: 1699702345,
-                    }
-                } else {
-                    PostInfo {
-                        #[cfg(target_arch = "wasm32")]
-                        pubkey: "11111111111111111111111111111118".to_string(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        pubkey: "11111111111111111111111111111118".parse().unwrap(),
-                        #[cfg(target_arch = "wasm32")]
-                        blog: blog_pubkey.clone(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        blog: blog_pubkey.parse().unwrap(),
-                        #[cfg(target_arch = "wasm32")]
-                        author: "11111111111111111111111111111116".to_string(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        author: "11111111111111111111111111111116".parse().unwrap(),
-                        title: "DeFi Bridge - verystochastic".to_string(),
-                        content: "Cross-chain bridge exploit leads to $80M drainage from Solana-Ethereum bridge. Attackers exploited a verification bug in the bridge's smart contract, allowing them to mint unlimited wrapped tokens on Ethereum using fake Solana transaction proofs. The vulnerability existed for months before discovery, with the protocol's multi-signature security being bypassed through a sophisticated social engineering attack on bridge validators.".to_string(),
-                        created_at: 1699615678,
-                        #[cfg(target_arch = "wasm32")]
-                        image_url: Some("/api/placeholder/800/400".to_string()),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        arweave_hash: "mock_arweave_hash_3".to_string(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        updated_at: 1699615678,
-                    }
-                };
-                
-                blog_info.set(Some(mock_blog));
-                post_info.set(Some(mock_post));
-            } else {
-                blog_info.set(None);
-                post_info.set(None);
-            }
-            
+            loading.set(true);
+            let blog = blog_service.get_blog(&blog_pubkey).await.ok();
+            // A post from another blog is treated as missing
+            let post = blog_service
+                .get_post(&post_pubkey)
+                .await
+                .ok()
+                .filter(|post| post.blog.to_string() == blog_pubkey);
+            let arweave_hash = post.as_ref().map(|post| post.arweave_hash.clone());
+            blog_info.set(blog);
+            post_info.set(post);
+            image.set(None);
             loading.set(false);
+
+            // The image is optional, so it loads after the text is shown
+            if let Some(arweave_hash) = arweave_hash {
+                if let Ok(sources) = ArweaveService::new().get_post_image(&arweave_hash).await {
+                    image.set(sources);
+                }
+            }
         }
     });
     
@@ -216,26 +124,20 @@ pub fn PostView(cx: Scope<PostViewProps>) -> Element {
                                 }
                             }
                             
-                            // Post image - only show for WASM builds that have image_url
-                            {
-                                #[cfg(target_arch = "wasm32")]
-                                if let Some(image_url) = &post.image_url {
-                                    rsx! {
-                                        div {
-                                            class: "mb-8",
-                                            img {
-                                                src: "{image_url}",
-                                                alt: "Post image",
-                                                class: "w-full h-64 md:h-96 object-cover border border-gray-700"
-                                            }
+                            // Cover image from the post's media manifest
+                            if let Some(image) = image.get() {
+                                rsx! {
+                                    div {
+                                        class: "mb-8",
+                                        img {
+                                            src: "{image.src}",
+                                            srcset: "{image.srcset}",
+                                            sizes: "(min-width: 768px) 768px, 100vw",
+                                            alt: "{image.alt}",
+                                            class: "w-full h-64 md:h-96 object-cover border border-gray-700"
                                         }
                                     }
-                                } else {
-                                    rsx! { div {} }
                                 }
-                                
-                                #[cfg(not(target_arch = "wasm32"))]
-                                rsx! { div {} }
                             }
                             
                             // Post content
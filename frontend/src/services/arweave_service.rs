@@ -2,6 +2,13 @@ use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 use web_sys::console;
 use gloo_storage::{LocalStorage, Storage};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use sha2::{Digest, Sha256};
+use solana_blog_types::arweave::merkle::TransactionChunks;
+use solana_blog_types::WINSTON_PER_AR;
+
+/// Path of the media list inside a post's path manifest
+const POST_MEDIA_PATH: &str = "data.json";
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PostContent {
@@ -24,6 +31,74 @@ pub struct PostMetadata {
     pub author: String,
 }
 
+/// Progress of a post upload, in Arweave chunks
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UploadProgress {
+    pub chunks_uploaded: usize,
+    pub total_chunks: usize,
+    pub bytes_uploaded: usize,
+    pub total_bytes: usize,
+}
+
+impl UploadProgress {
+    /// Progress once the first `chunks_uploaded` chunks have been accepted
+    fn after(chunks: &TransactionChunks, chunks_uploaded: usize) -> Self {
+        let byte_end = |count: usize| {
+            count
+                .checked_sub(1)
+                .and_then(|last| chunks.chunks.get(last))
+                .map_or(0, |chunk| chunk.max_byte_range)
+        };
+        Self {
+            chunks_uploaded,
+            total_chunks: chunks.chunks.len(),
+            bytes_uploaded: byte_end(chunks_uploaded),
+            total_bytes: byte_end(chunks.chunks.len()),
+        }
+    }
+
+    pub fn percent(&self) -> u32 {
+        if self.total_bytes == 0 {
+            100
+        } else {
+            (self.bytes_uploaded * 100 / self.total_bytes) as u32
+        }
+    }
+}
+
+/// One uploaded copy of a post image, as listed in a post manifest
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MediaVariant {
+    pub id: String,
+    pub width: u32,
+    pub height: u32,
+    pub content_type: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MediaAsset {
+    pub alt: String,
+    pub width: u32,
+    pub height: u32,
+    pub variants: Vec<MediaVariant>,
+}
+
+/// Contents of a post's `data.json`: the body's id plus every image variant
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PostManifest {
+    pub version: u32,
+    pub content: Option<String>,
+    pub media: Vec<MediaAsset>,
+}
+
+/// `src` and `srcset` values for rendering a `MediaAsset`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageSources {
+    pub src: String,
+    pub srcset: String,
+    pub alt: String,
+}
+
 #[derive(Clone, Debug)]
 pub struct ArweaveService {
     gateway_url: String,
@@ -74,8 +149,14 @@ impl ArweaveService {
         }
     }
 
-    /// Upload post content to Arweave with wallet authentication
-    pub async fn upload_post(&self, post: PostContent, wallet_public_key: &str) -> Result<String, String> {
+    /// Upload post content to Arweave with wallet authentication, reporting
+    /// progress after each chunk
+    pub async fn upload_post(
+        &self,
+        post: PostContent,
+        wallet_public_key: &str,
+        on_progress: impl Fn(UploadProgress),
+    ) -> Result<String, String> {
         // Check if wallet is connected
         if wallet_public_key.is_empty() {
             return Err("Wallet not connected. Please connect your wallet first.".to_string());
@@ -89,54 +170,178 @@ impl ArweaveService {
 
         #[cfg(target_arch = "wasm32")]
         {
-            // Log the upload attempt
             console::log_1(&format!("🔄 Starting Arweave upload for post: {}", post.title).into());
-            console::log_1(&format!("💰 Using Arweave wallet: {}", arweave_key.as_ref().unwrap()[..8].to_string()).into());
-            
-            // Simulate network delay for real upload
-            let _ = gloo_timers::future::TimeoutFuture::new(3000).await;
-            
-            // In a real implementation, this would:
-            // 1. Use the stored Arweave key to sign the transaction
-            // 2. Create a transaction to Arweave with the post content
-            // 3. Submit to Arweave network
-            // 4. Return the actual transaction ID
-            
-            // For now, we'll simulate the process with the actual Arweave key
-            let tx_id = format!("arweave_{}_{}", 
-                arweave_key.unwrap()[..8].to_string(), 
-                uuid::Uuid::new_v4().to_string().replace("-", "")
-            );
-            
+
+            let jwk: serde_json::Value = serde_json::from_str(&arweave_key.unwrap_or_default())
+                .map_err(|e| format!("Invalid Arweave wallet key: {}", e))?;
+
+            // The post is a path manifest: `index.md` holds the body, which is
+            // where the upload time goes, so progress follows its chunks
+            let body = self.format_post_content(&post).into_bytes();
+            let body_id = self
+                .upload_transaction(&jwk, &body, "text/markdown", &on_progress)
+                .await?;
+
+            let manifest = serde_json::json!({
+                "manifest": "arweave/paths",
+                "version": "0.1.0",
+                "index": { "path": "index.md" },
+                "paths": { "index.md": { "id": body_id } },
+            });
+            let tx_id = self
+                .upload_transaction(
+                    &jwk,
+                    manifest.to_string().as_bytes(),
+                    "application/x.arweave-manifest+json",
+                    &|_| {},
+                )
+                .await?;
+
             console::log_1(&format!("✅ Post uploaded to Arweave with tx_id: {}", tx_id).into());
-            console::log_1(&format!("📝 Post content size: {} bytes", post.content.len()).into());
-            
             Ok(tx_id)
         }
         
         #[cfg(not(target_arch = "wasm32"))]
         {
-            // Server-side implementation would use actual Arweave SDK
+            // Desktop builds upload through the client instead
+            let _ = (post, arweave_key, on_progress);
             Err("Arweave upload not implemented for server-side".to_string())
         }
     }
 
-    /// Get post content from Arweave
+    /// Sign `data` with the stored JWK and post it as a format 2
+    /// transaction: in the header when it fits one chunk, otherwise one
+    /// `/chunk` request per chunk after the header
+    #[cfg(target_arch = "wasm32")]
+    async fn upload_transaction(
+        &self,
+        jwk: &serde_json::Value,
+        data: &[u8],
+        content_type: &str,
+        on_progress: &impl Fn(UploadProgress),
+    ) -> Result<String, String> {
+        use solana_blog_types::arweave::{merkle, SignedHeader};
+
+        let modulus = jwk["n"]
+            .as_str()
+            .ok_or("Arweave wallet key has no modulus")?;
+        let owner = URL_SAFE_NO_PAD
+            .decode(modulus)
+            .map_err(|e| format!("Invalid Arweave wallet key: {}", e))?;
+
+        let chunks = merkle::generate_transaction_chunks(data);
+        on_progress(UploadProgress::after(&chunks, 0));
+
+        let anchor = self.get_text(&format!("{}/tx_anchor", self.gateway_url)).await?;
+        let last_tx = URL_SAFE_NO_PAD
+            .decode(anchor.trim())
+            .map_err(|e| format!("Invalid transaction anchor '{}': {}", anchor.trim(), e))?;
+        let reward = self.get_price_winston(data.len()).await?.to_string();
+        let data_size = data.len().to_string();
+        let tags = vec![
+            (b"Content-Type".to_vec(), content_type.as_bytes().to_vec()),
+            (b"App-Name".to_vec(), b"Solana-Blog".to_vec()),
+        ];
+
+        let signature_data = SignedHeader {
+            owner: &owner,
+            target: &[],
+            quantity: "0",
+            reward: &reward,
+            last_tx: &last_tx,
+            tags: &tags,
+            data_size: &data_size,
+            data_root: &chunks.data_root,
+        }
+        .signature_data();
+        let signature = sign_rsa_pss(jwk, &signature_data).await?;
+        let id = URL_SAFE_NO_PAD.encode(Sha256::digest(&signature));
+
+        let in_header = data.len() <= merkle::MAX_CHUNK_SIZE;
+        let header = serde_json::json!({
+            "format": 2,
+            "id": id,
+            "last_tx": anchor.trim(),
+            "owner": modulus,
+            "tags": tags.iter().map(|(name, value)| serde_json::json!({
+                "name": URL_SAFE_NO_PAD.encode(name),
+                "value": URL_SAFE_NO_PAD.encode(value),
+            })).collect::<Vec<_>>(),
+            "target": "",
+            "quantity": "0",
+            "data": if in_header { URL_SAFE_NO_PAD.encode(data) } else { String::new() },
+            "data_size": data_size,
+            "data_root": URL_SAFE_NO_PAD.encode(&chunks.data_root),
+            "reward": reward,
+            "signature": URL_SAFE_NO_PAD.encode(&signature),
+        });
+        self.post_json("tx", &header).await?;
+
+        if in_header {
+            on_progress(UploadProgress::after(&chunks, chunks.chunks.len()));
+            return Ok(id);
+        }
+        for (index, proof) in chunks.proofs.iter().enumerate() {
+            let chunk = serde_json::json!({
+                "data_root": URL_SAFE_NO_PAD.encode(&chunks.data_root),
+                "data_size": data_size,
+                "data_path": URL_SAFE_NO_PAD.encode(&proof.proof),
+                "offset": proof.offset.to_string(),
+                "chunk": URL_SAFE_NO_PAD.encode(chunks.chunk_bytes(data, index)),
+            });
+            self.post_json("chunk", &chunk).await?;
+            on_progress(UploadProgress::after(&chunks, index + 1));
+        }
+        Ok(id)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    async fn post_json(&self, path: &str, body: &serde_json::Value) -> Result<(), String> {
+        use wasm_bindgen::JsCast;
+        use wasm_bindgen_futures::JsFuture;
+
+        let init = web_sys::RequestInit::new();
+        init.set_method("POST");
+        init.set_body(&JsValue::from_str(&body.to_string()));
+        let url = format!("{}/{}", self.gateway_url, path);
+        let request = web_sys::Request::new_with_str_and_init(&url, &init)
+            .map_err(|e| format!("Failed to build Arweave request: {:?}", e))?;
+        request
+            .headers()
+            .set("Content-Type", "application/json")
+            .map_err(|e| format!("Failed to build Arweave request: {:?}", e))?;
+
+        let window = web_sys::window().ok_or("No window object")?;
+        let response = JsFuture::from(window.fetch_with_request(&request))
+            .await
+            .map_err(|e| format!("Arweave request failed: {:?}", e))?;
+        let response: web_sys::Response = response.unchecked_into();
+        if !response.ok() {
+            return Err(format!("Arweave /{} failed with status: {}", path, response.status()));
+        }
+        Ok(())
+    }
+
+    /// Get post content from Arweave, served from the browser cache when possible
     pub async fn get_post(&self, tx_id: &str) -> Result<PostContent, String> {
         #[cfg(target_arch = "wasm32")]
         {
-            // Simulate fetching from Arweave
-            let _ = gloo_timers::future::TimeoutFuture::new(1000).await;
-            
-            // In real implementation, this would fetch from Arweave gateway
-            Ok(PostContent {
-                title: "Post from Arweave".to_string(),
-                description: "This post was fetched from Arweave".to_string(),
-                content: format!("# Post from Arweave\n\nTransaction ID: {}\n\nThis is content fetched from Arweave.", tx_id),
-                tags: vec!["arweave".to_string(), "blockchain".to_string()],
-                created_at: "2024-01-20".to_string(),
-                author: "verystochastic".to_string(),
-            })
+            let cache = super::content_cache::ContentCache::new(self.gateway_url.clone());
+            // Gateways serve a path manifest's `index.md` for the bare id;
+            // posts from before path manifests are a bare body or, with
+            // images, a media list pointing at the body
+            let mut data = cache.fetch(tx_id).await?;
+            if let Ok(PostManifest { content: Some(body_id), .. }) = serde_json::from_slice(&data) {
+                data = cache.fetch(&body_id).await?;
+            }
+            let stats = cache.stats();
+            console::log_1(&format!(
+                "Arweave cache: {} hits, {} misses, {} entries ({} bytes)",
+                stats.hits, stats.misses, stats.entries, stats.total_bytes
+            ).into());
+
+            let text = String::from_utf8(data).map_err(|e| format!("Post is not valid UTF-8: {}", e))?;
+            Ok(self.parse_post_content(&text))
         }
         
         #[cfg(not(target_arch = "wasm32"))]
@@ -145,16 +350,124 @@ impl ArweaveService {
         }
     }
 
+    /// Parse content written by `format_post_content`. Content without
+    /// frontmatter is returned as the body with empty metadata.
+    pub fn parse_post_content(&self, text: &str) -> PostContent {
+        let mut post = PostContent {
+            title: String::new(),
+            description: String::new(),
+            content: text.to_string(),
+            tags: Vec::new(),
+            created_at: String::new(),
+            author: String::new(),
+        };
+
+        let Some(rest) = text.strip_prefix("---\n") else { return post };
+        let Some(end) = rest.find("\n---\n") else { return post };
+
+        for line in rest[..end].lines() {
+            let Some((key, value)) = line.split_once(": ") else { continue };
+            // Values are JSON strings; older posts wrote them unquoted
+            let text = || serde_json::from_str::<String>(value).unwrap_or_else(|_| value.to_string());
+            match key {
+                "title" => post.title = text(),
+                "description" => post.description = text(),
+                "created_at" => post.created_at = text(),
+                "author" => post.author = text(),
+                "tags" => {
+                    post.tags = serde_json::from_str::<Vec<String>>(value).unwrap_or_else(|_| {
+                        value
+                            .trim_start_matches('[')
+                            .trim_end_matches(']')
+                            .split(',')
+                            .map(|tag| tag.trim().trim_matches('"').to_string())
+                            .collect()
+                    });
+                    post.tags.retain(|tag| !tag.is_empty());
+                }
+                _ => {}
+            }
+        }
+        post.content = rest[end + "\n---\n".len()..].trim_start_matches('\n').to_string();
+        post
+    }
+
+    /// Image sources for `asset`. WebP variants are only uploaded when they
+    /// are smaller than the JPEG/PNG copy of the same size, so each width
+    /// contributes whichever copy exists in the smallest encoding.
+    pub fn image_sources(&self, asset: &MediaAsset) -> Option<ImageSources> {
+        let mut by_width: Vec<&MediaVariant> = Vec::new();
+        for variant in &asset.variants {
+            match by_width.iter_mut().find(|existing| existing.width == variant.width) {
+                Some(existing) if variant.content_type == "image/webp" => *existing = variant,
+                Some(_) => {}
+                None => by_width.push(variant),
+            }
+        }
+        by_width.sort_by_key(|variant| variant.width);
+
+        let src = asset
+            .variants
+            .iter()
+            .filter(|variant| variant.content_type != "image/webp")
+            .max_by_key(|variant| variant.width)?;
+
+        Some(ImageSources {
+            src: format!("{}/{}", self.gateway_url, src.id),
+            srcset: by_width
+                .iter()
+                .map(|variant| format!("{}/{} {}w", self.gateway_url, variant.id, variant.width))
+                .collect::<Vec<_>>()
+                .join(", "),
+            alt: asset.alt.clone(),
+        })
+    }
+
+    /// Image sources for the first image of the post at `tx_id`, or `None`
+    /// when the post has no media manifest
+    pub async fn get_post_image(&self, tx_id: &str) -> Result<Option<ImageSources>, String> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            let cache = super::content_cache::ContentCache::new(self.gateway_url.clone());
+            // Posts are path manifests with the media list at `data.json`;
+            // posts without images have no such path. Posts from before
+            // path manifests are the media list itself, or a bare body.
+            let raw = cache.fetch(&format!("raw/{}", tx_id)).await?;
+            let manifest = match serde_json::from_slice::<serde_json::Value>(&raw) {
+                Ok(manifest) if manifest["manifest"] == "arweave/paths" => {
+                    let Some(media_id) = manifest["paths"][POST_MEDIA_PATH]["id"].as_str() else {
+                        return Ok(None);
+                    };
+                    let data = cache.fetch(media_id).await?;
+                    serde_json::from_slice::<PostManifest>(&data)
+                        .map_err(|e| format!("Invalid media list for post {}: {}", tx_id, e))?
+                }
+                _ => match serde_json::from_slice::<PostManifest>(&raw) {
+                    Ok(manifest) => manifest,
+                    Err(_) => return Ok(None),
+                },
+            };
+            Ok(manifest.media.first().and_then(|asset| self.image_sources(asset)))
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let _ = tx_id;
+            Err("Arweave fetch not implemented for server-side".to_string())
+        }
+    }
+
     /// Format post content for Arweave storage
     pub fn format_post_content(&self, post: &PostContent) -> String {
-        // Create frontmatter
+        // Create frontmatter, with values as JSON so quotes, newlines and
+        // commas in tags survive parsing
         let frontmatter = format!(
-            "---\ntitle: {}\ndescription: {}\ntags: {:?}\ncreated_at: {}\nauthor: {}\n---\n\n",
-            post.title,
-            post.description,
-            post.tags,
-            post.created_at,
-            post.author
+            "---\ntitle: {}\ndescription: {}\ntags: {}\ncreated_at: {}\nauthor: {}\n---\n\n",
+            serde_json::Value::from(post.title.as_str()),
+            serde_json::Value::from(post.description.as_str()),
+            serde_json::Value::from(post.tags.clone()),
+            serde_json::Value::from(post.created_at.as_str()),
+            serde_json::Value::from(post.author.as_str()),
         );
 
         // Combine frontmatter with content
@@ -173,62 +486,150 @@ impl ArweaveService {
         format!("{}-{}.md", date, slug)
     }
 
-    /// Verify wallet has sufficient balance for Arweave upload
-    pub async fn check_wallet_balance(&self, _wallet_public_key: &str) -> Result<bool, String> {
+    /// Verify the stored Arweave wallet can pay for uploading `content_size` bytes
+    pub async fn check_wallet_balance(&self, content_size: usize) -> Result<bool, String> {
         #[cfg(target_arch = "wasm32")]
         {
-            // Check if Arweave key is stored
-            let arweave_key = self.get_arweave_key();
-            if arweave_key.is_none() {
-                return Err("Arweave wallet not configured".to_string());
-            }
+            let address = self
+                .get_arweave_address()
+                .ok_or_else(|| "Arweave wallet not configured".to_string())?;
+
+            let balance = self.get_balance(&address).await?;
+            let price = self.get_price_winston(content_size).await?;
 
-            console::log_1(&format!("💰 Checking Arweave wallet balance").into());
-            
-            // Simulate balance check
-            let _ = gloo_timers::future::TimeoutFuture::new(500).await;
-            
-            // In real implementation, this would check AR balance on Arweave
-            Ok(true) // Assume sufficient balance
+            console::log_1(&format!(
+                "💰 Arweave upload costs {:.6} AR, wallet {} holds {:.6} AR",
+                price as f64 / WINSTON_PER_AR as f64,
+                address,
+                balance as f64 / WINSTON_PER_AR as f64
+            ).into());
+
+            Ok(balance >= price)
         }
-        
+
+        // Desktop builds have no stored wallet; they upload through the
+        // client, which checks the paying wallet itself
         #[cfg(not(target_arch = "wasm32"))]
         {
+            let _ = content_size;
             Ok(true)
         }
     }
 
-    /// Get estimated upload cost
+    /// Get estimated upload cost in AR
     pub async fn get_upload_cost(&self, content_size: usize) -> Result<f64, String> {
-        // Arweave costs are typically around $0.50 per MB
-        let cost_per_mb = 0.5;
-        let size_mb = content_size as f64 / (1024.0 * 1024.0);
-        let estimated_cost = size_mb * cost_per_mb;
-        
-        Ok(estimated_cost)
+        let winston = self.get_price_winston(content_size).await?;
+        Ok(winston as f64 / WINSTON_PER_AR as f64)
     }
 
-    /// Get Arweave wallet address
-    pub fn get_arweave_address(&self) -> Option<String> {
+    /// Price in winston from the gateway `/price` endpoint
+    async fn get_price_winston(&self, content_size: usize) -> Result<u64, String> {
+        self.get_winston(&format!("{}/price/{}", self.gateway_url, content_size))
+            .await
+    }
+
+    /// Wallet balance in winston
+    pub async fn get_balance(&self, address: &str) -> Result<u64, String> {
+        self.get_winston(&format!("{}/wallet/{}/balance", self.gateway_url, address))
+            .await
+    }
+
+    async fn get_winston(&self, url: &str) -> Result<u64, String> {
+        let text = self.get_text(url).await?;
+        text.trim()
+            .parse()
+            .map_err(|e| format!("Invalid winston amount '{}': {}", text.trim(), e))
+    }
+
+    async fn get_text(&self, url: &str) -> Result<String, String> {
         #[cfg(target_arch = "wasm32")]
         {
-            if let Some(key) = self.get_arweave_key() {
-                // In real implementation, derive address from key
-                // For now, return a placeholder
-                Some(format!("arweave_{}", key[..8].to_string()))
-            } else {
-                None
+            use wasm_bindgen::JsCast;
+            use wasm_bindgen_futures::JsFuture;
+
+            let window = web_sys::window().ok_or("No window object")?;
+            let response = JsFuture::from(window.fetch_with_str(url))
+                .await
+                .map_err(|e| format!("Arweave request failed: {:?}", e))?;
+            let response: web_sys::Response = response.unchecked_into();
+            if !response.ok() {
+                return Err(format!("Arweave request failed with status: {}", response.status()));
             }
+            let text = response
+                .text()
+                .map_err(|e| format!("Failed to read Arweave response: {:?}", e))?;
+            Ok(JsFuture::from(text)
+                .await
+                .map_err(|e| format!("Failed to read Arweave response: {:?}", e))?
+                .as_string()
+                .unwrap_or_default())
         }
+
         #[cfg(not(target_arch = "wasm32"))]
         {
-            None
+            let _ = url;
+            Err("Arweave requests not implemented for server-side".to_string())
         }
     }
+
+    /// Get Arweave wallet address: base64url SHA-256 of the JWK modulus
+    pub fn get_arweave_address(&self) -> Option<String> {
+        let key = self.get_arweave_key()?;
+        let jwk: serde_json::Value = serde_json::from_str(&key).ok()?;
+        let modulus = URL_SAFE_NO_PAD.decode(jwk.get("n")?.as_str()?).ok()?;
+        Some(URL_SAFE_NO_PAD.encode(Sha256::digest(&modulus)))
+    }
 }
 
 impl Default for ArweaveService {
     fn default() -> Self {
         Self::new()
     }
-} 
\ No newline at end of file
+} 
+
+/// RSA-PSS (SHA-256, 32 byte salt) signature of `message` with an Arweave
+/// JWK, through the browser's SubtleCrypto
+#[cfg(target_arch = "wasm32")]
+async fn sign_rsa_pss(jwk: &serde_json::Value, message: &[u8]) -> Result<Vec<u8>, String> {
+    use js_sys::{Array, Function, Object, Promise, Reflect, Uint8Array};
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+
+    let fail = |e: JsValue| format!("Failed to sign Arweave transaction: {:?}", e);
+    let window = web_sys::window().ok_or("No window object")?;
+    let crypto = Reflect::get(&window, &"crypto".into()).map_err(fail)?;
+    let subtle = Reflect::get(&crypto, &"subtle".into()).map_err(fail)?;
+    let method = |name: &str| -> Result<Function, String> {
+        Reflect::get(&subtle, &name.into())
+            .map_err(fail)?
+            .dyn_into()
+            .map_err(|_| format!("SubtleCrypto has no {}", name))
+    };
+
+    let key_data = js_sys::JSON::parse(&jwk.to_string()).map_err(fail)?;
+    let import_algorithm = Object::new();
+    Reflect::set(&import_algorithm, &"name".into(), &"RSA-PSS".into()).map_err(fail)?;
+    Reflect::set(&import_algorithm, &"hash".into(), &"SHA-256".into()).map_err(fail)?;
+    let import = method("importKey")?.apply(
+        &subtle,
+        &Array::of5(
+            &"jwk".into(),
+            &key_data,
+            &import_algorithm,
+            &JsValue::FALSE,
+            &Array::of1(&"sign".into()),
+        ),
+    );
+    let key = JsFuture::from(Promise::from(import.map_err(fail)?))
+        .await
+        .map_err(fail)?;
+
+    let sign_algorithm = Object::new();
+    Reflect::set(&sign_algorithm, &"name".into(), &"RSA-PSS".into()).map_err(fail)?;
+    Reflect::set(&sign_algorithm, &"saltLength".into(), &JsValue::from(32)).map_err(fail)?;
+    let sign = method("sign")?.call3(&subtle, &sign_algorithm, &key, &Uint8Array::from(message));
+    let signature = JsFuture::from(Promise::from(sign.map_err(fail)?))
+        .await
+        .map_err(fail)?;
+    Ok(Uint8Array::new(&signature).to_vec())
+}
@@ -0,0 +1,189 @@
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/// Cache API bucket holding gateway responses
+const CACHE_NAME: &str = "arweave-content-v1";
+/// localStorage key for sizes, access times and counters, which the Cache
+/// API does not track itself
+const INDEX_KEY: &str = "arweave_cache_index";
+pub const DEFAULT_MAX_BYTES: u64 = 50 * 1024 * 1024;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct IndexEntry {
+    size: u64,
+    last_access: f64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct CacheIndex {
+    entries: HashMap<String, IndexEntry>,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+    pub total_bytes: u64,
+}
+
+/// Browser cache for Arweave content keyed by transaction id. Arweave data is
+/// immutable, so entries are never revalidated; the least recently used ones
+/// are evicted once the cache grows past `max_bytes`.
+#[derive(Clone, Debug)]
+pub struct ContentCache {
+    gateway_url: String,
+    max_bytes: u64,
+}
+
+fn js_error(context: &str, error: JsValue) -> String {
+    format!("{}: {}", context, error.as_string().unwrap_or_else(|| format!("{:?}", error)))
+}
+
+impl ContentCache {
+    pub fn new(gateway_url: String) -> Self {
+        Self {
+            gateway_url,
+            max_bytes: DEFAULT_MAX_BYTES,
+        }
+    }
+
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    fn url(&self, tx_id: &str) -> String {
+        format!("{}/{}", self.gateway_url, tx_id)
+    }
+
+    fn load_index() -> CacheIndex {
+        LocalStorage::get(INDEX_KEY).unwrap_or_default()
+    }
+
+    fn save_index(index: &CacheIndex) {
+        let _ = LocalStorage::set(INDEX_KEY, index);
+    }
+
+    async fn open_cache() -> Result<web_sys::Cache, String> {
+        let window = web_sys::window().ok_or("No window object")?;
+        let caches = window
+            .caches()
+            .map_err(|e| js_error("Cache API unavailable", e))?;
+        let cache = JsFuture::from(caches.open(CACHE_NAME))
+            .await
+            .map_err(|e| js_error("Failed to open cache", e))?;
+        Ok(cache.unchecked_into())
+    }
+
+    async fn response_bytes(response: &web_sys::Response) -> Result<Vec<u8>, String> {
+        let buffer = response
+            .array_buffer()
+            .map_err(|e| js_error("Failed to read response", e))?;
+        let buffer = JsFuture::from(buffer)
+            .await
+            .map_err(|e| js_error("Failed to read response", e))?;
+        Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+    }
+
+    /// Bytes of `tx_id`, from the cache when present, otherwise from the
+    /// gateway (and then cached)
+    pub async fn fetch(&self, tx_id: &str) -> Result<Vec<u8>, String> {
+        let url = self.url(tx_id);
+        let cache = Self::open_cache().await?;
+        let mut index = Self::load_index();
+
+        let cached = JsFuture::from(cache.match_with_str(&url))
+            .await
+            .map_err(|e| js_error("Cache lookup failed", e))?;
+        if !cached.is_undefined() {
+            let response: web_sys::Response = cached.unchecked_into();
+            let data = Self::response_bytes(&response).await?;
+            index.hits += 1;
+            index.entries.insert(
+                tx_id.to_string(),
+                IndexEntry {
+                    size: data.len() as u64,
+                    last_access: js_sys::Date::now(),
+                },
+            );
+            Self::save_index(&index);
+            return Ok(data);
+        }
+
+        index.misses += 1;
+        let window = web_sys::window().ok_or("No window object")?;
+        let response = JsFuture::from(window.fetch_with_str(&url))
+            .await
+            .map_err(|e| js_error("Failed to fetch from Arweave", e))?;
+        let response: web_sys::Response = response.unchecked_into();
+        if !response.ok() {
+            Self::save_index(&index);
+            return Err(format!("Arweave fetch failed with status: {}", response.status()));
+        }
+
+        // The body can only be read once, so cache a clone
+        let for_cache = response
+            .clone()
+            .map_err(|e| js_error("Failed to clone response", e))?;
+        let data = Self::response_bytes(&response).await?;
+
+        if (data.len() as u64) <= self.max_bytes {
+            let _ = JsFuture::from(cache.put_with_str(&url, &for_cache)).await;
+            index.entries.insert(
+                tx_id.to_string(),
+                IndexEntry {
+                    size: data.len() as u64,
+                    last_access: js_sys::Date::now(),
+                },
+            );
+            self.evict(&cache, &mut index).await;
+        }
+        Self::save_index(&index);
+
+        Ok(data)
+    }
+
+    async fn evict(&self, cache: &web_sys::Cache, index: &mut CacheIndex) {
+        let mut total: u64 = index.entries.values().map(|entry| entry.size).sum();
+        if total <= self.max_bytes {
+            return;
+        }
+
+        let mut by_age: Vec<(String, f64)> = index
+            .entries
+            .iter()
+            .map(|(tx_id, entry)| (tx_id.clone(), entry.last_access))
+            .collect();
+        by_age.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        for (tx_id, _) in by_age {
+            if total <= self.max_bytes {
+                break;
+            }
+            if let Some(entry) = index.entries.remove(&tx_id) {
+                total -= entry.size;
+                index.evictions += 1;
+                let _ = JsFuture::from(cache.delete_with_str(&self.url(&tx_id))).await;
+            }
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let index = Self::load_index();
+        CacheStats {
+            hits: index.hits,
+            misses: index.misses,
+            evictions: index.evictions,
+            entries: index.entries.len(),
+            total_bytes: index.entries.values().map(|entry| entry.size).sum(),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+// Live updates for a blog's post list over the RPC node's websocket
+#[cfg(not(target_arch = "wasm32"))]
+pub use solana_blog_client::PostEvent;
+
+#[cfg(target_arch = "wasm32")]
+use solana_blog_types::state::BlogPost;
+#[cfg(target_arch = "wasm32")]
+use std::{cell::RefCell, rc::Rc};
+#[cfg(target_arch = "wasm32")]
+use tokio::sync::mpsc;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::{closure::Closure, JsCast};
+
+// Notifications are not decoded in the browser; one only says the blog's
+// posts changed and should be reloaded, which also picks up deletions
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug, Clone, PartialEq)]
+pub enum PostEvent {
+    Changed,
+}
+
+pub struct BlogWatch {
+    #[cfg(not(target_arch = "wasm32"))]
+    subscription: solana_blog_client::BlogSubscription,
+    #[cfg(target_arch = "wasm32")]
+    socket: web_sys::WebSocket,
+    #[cfg(target_arch = "wasm32")]
+    events: mpsc::UnboundedReceiver<PostEvent>,
+    #[cfg(target_arch = "wasm32")]
+    _callbacks: (
+        Closure<dyn FnMut(web_sys::Event)>,
+        Closure<dyn FnMut(web_sys::MessageEvent)>,
+        Closure<dyn FnMut(web_sys::Event)>,
+    ),
+}
+
+impl BlogWatch {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn open(client: &solana_blog_client::BlogClient, blog_pubkey: &str) -> Result<Self, String> {
+        let pubkey = blog_pubkey.parse().map_err(|e| format!("Invalid pubkey: {}", e))?;
+        let subscription = client
+            .subscribe_blog(pubkey)
+            .await
+            .map_err(|e| format!("Failed to subscribe to blog: {}", e))?;
+        Ok(Self { subscription })
+    }
+
+    // Subscribe to the blog's post accounts and to the blog account itself,
+    // whose post count changes when a post is deleted
+    #[cfg(target_arch = "wasm32")]
+    pub async fn open(ws_url: &str, program_id: &str, blog_pubkey: &str) -> Result<Self, String> {
+        let socket = web_sys::WebSocket::new(ws_url)
+            .map_err(|e| format!("Failed to connect to {}: {:?}", ws_url, e))?;
+        let (sender, events) = mpsc::unbounded_channel();
+        let sender = Rc::new(RefCell::new(Some(sender)));
+
+        let requests = [
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "programSubscribe",
+                "params": [program_id, {
+                    "encoding": "base64",
+                    "commitment": "confirmed",
+                    "filters": [{ "memcmp": { "offset": BlogPost::BLOG_OFFSET, "bytes": blog_pubkey } }],
+                }],
+            }),
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "accountSubscribe",
+                "params": [blog_pubkey, { "encoding": "base64", "commitment": "confirmed" }],
+            }),
+        ];
+        let on_open = {
+            let socket = socket.clone();
+            Closure::<dyn FnMut(web_sys::Event)>::new(move |_| {
+                for request in &requests {
+                    let _ = socket.send_with_str(&request.to_string());
+                }
+            })
+        };
+
+        let on_message = {
+            let sender = sender.clone();
+            Closure::<dyn FnMut(web_sys::MessageEvent)>::new(move |event: web_sys::MessageEvent| {
+                let Some(text) = event.data().as_string() else { return };
+                let Ok(message) = serde_json::from_str::<serde_json::Value>(&text) else { return };
+                // Replies to the subscribe requests carry an id instead
+                let is_notification = message["method"]
+                    .as_str()
+                    .is_some_and(|method| method.ends_with("Notification"));
+                if let (true, Some(sender)) = (is_notification, sender.borrow().as_ref()) {
+                    let _ = sender.send(PostEvent::Changed);
+                }
+            })
+        };
+
+        // Dropping the sender ends the event stream
+        let on_close = Closure::<dyn FnMut(web_sys::Event)>::new(move |_| {
+            sender.borrow_mut().take();
+        });
+
+        socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+        socket.set_onerror(Some(on_close.as_ref().unchecked_ref()));
+
+        Ok(Self {
+            socket,
+            events,
+            _callbacks: (on_open, on_message, on_close),
+        })
+    }
+
+    /// Next change to the blog's posts; `None` once the connection is lost
+    pub async fn next(&mut self) -> Option<PostEvent> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.subscription.next().await?.ok()
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.events.recv().await
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Drop for BlogWatch {
+    fn drop(&mut self) {
+        self.socket.set_onopen(None);
+        self.socket.set_onmessage(None);
+        self.socket.set_onclose(None);
+        self.socket.set_onerror(None);
+        let _ = self.socket.close();
+    }
+}
@@ -1,59 +1,217 @@
 #[cfg(not(target_arch = "wasm32"))]
-use solana_blog_client::{BlogInfo, PostInfo, BlogClient, CreatePostRequest};
+use solana_blog_client::BlogClient;
+#[cfg(not(target_arch = "wasm32"))]
+pub use solana_blog_client::SearchResult;
 #[cfg(target_arch = "wasm32")]
 use serde::{Deserialize, Serialize};
 
 // Include Arweave service
 pub mod arweave_service;
-pub use arweave_service::{ArweaveService, PostContent, PostMetadata};
+pub use arweave_service::{ArweaveService, ImageSources, PostContent, PostMetadata, UploadProgress};
 
-// Mock types for WASM builds
 #[cfg(target_arch = "wasm32")]
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct BlogInfo {
-    pub pubkey: String,
-    pub authority: String,
-    pub title: String,
-    pub description: String,
-    pub post_count: u32,
-    pub created_at: i64,
-}
+pub mod content_cache;
+
+#[cfg(target_arch = "wasm32")]
+pub mod rpc;
+#[cfg(target_arch = "wasm32")]
+pub use rpc::RpcClient;
+
+#[cfg(any(target_arch = "wasm32", test))]
+pub mod transaction;
+#[cfg(target_arch = "wasm32")]
+pub use transaction::{Keypair, Transaction};
+
+pub mod live;
+pub use live::{BlogWatch, PostEvent};
+
+// Shared with the client, so both builds see the same account types
+pub use solana_blog_types::{BlogInfo, PostInfo};
+#[cfg(target_arch = "wasm32")]
+use solana_blog_types::Pubkey;
 
 #[cfg(target_arch = "wasm32")]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct PostInfo {
-    pub pubkey: String,
+pub struct SearchResult {
+    pub post: String,
     pub blog: String,
-    pub author: String,
     pub title: String,
-    pub content: String,
+    /// Escaped HTML with matches wrapped in `<mark>`
+    pub title_html: String,
+    pub snippet_html: String,
+    pub tags: Vec<String>,
     pub created_at: i64,
-    pub image_url: Option<String>,
+    pub score: f64,
+}
+
+/// Optional narrowing of a search; dates are unix timestamps compared
+/// against the post's creation time
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchFilters {
+    pub tag: Option<String>,
+    /// Created at or after
+    pub from: Option<i64>,
+    /// Created before
+    pub to: Option<i64>,
 }
 
 #[cfg(target_arch = "wasm32")]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
+struct SearchResponse {
+    results: Vec<SearchResult>,
+}
+
+#[derive(Debug, Clone)]
 pub struct CreatePostRequest {
     pub title: String,
+    /// Summary stored on chain; the full post lives on Arweave
     pub content: String,
+    pub arweave_hash: String,
+}
+
+// Page of posts from the API
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug, Clone, Deserialize)]
+struct PostPage {
+    posts: Vec<PostInfo>,
+    next_cursor: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct BlogService {
-    // Mock service for WASM builds
+    // `blog-api` server to read from; without one, accounts are read over RPC
+    api_url: Option<String>,
 }
 
 impl BlogService {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            api_url: crate::config::API_URL.map(|url| url.trim_end_matches('/').to_string()),
+        }
+    }
+
+    pub fn with_api(mut self, api_url: &str) -> Self {
+        self.api_url = Some(api_url.trim_end_matches('/').to_string());
+        self
+    }
+
+    pub fn api_url(&self) -> Option<&str> {
+        self.api_url.as_deref()
+    }
+
+    /// GET a path from the API; `Ok(None)` on 404
+    #[cfg(target_arch = "wasm32")]
+    async fn api_get(&self, api_url: &str, path: &str) -> Result<Option<String>, String> {
+        use wasm_bindgen::JsCast;
+        use wasm_bindgen_futures::JsFuture;
+
+        let url = format!("{}{}", api_url, path);
+        let window = web_sys::window().ok_or("No window object")?;
+        let response = JsFuture::from(window.fetch_with_str(&url))
+            .await
+            .map_err(|e| format!("API request failed: {:?}", e))?;
+        let response: web_sys::Response = response.unchecked_into();
+        if response.status() == 404 {
+            return Ok(None);
+        }
+        if !response.ok() {
+            return Err(format!("API request failed with status: {}", response.status()));
+        }
+        let text = response
+            .text()
+            .map_err(|e| format!("Failed to read API response: {:?}", e))?;
+        let text = JsFuture::from(text)
+            .await
+            .map_err(|e| format!("Failed to read API response: {:?}", e))?
+            .as_string()
+            .unwrap_or_default();
+        Ok(Some(text))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    async fn api_get_json<T: serde::de::DeserializeOwned>(&self, api_url: &str, path: &str) -> Result<Option<T>, String> {
+        match self.api_get(api_url, path).await? {
+            Some(text) => serde_json::from_str(&text)
+                .map(Some)
+                .map_err(|e| format!("Invalid API response: {}", e)),
+            None => Ok(None),
+        }
+    }
+
+    /// Ranked full-text search over post titles and bodies
+    pub async fn search(&self, text: &str, filters: &SearchFilters) -> Result<Vec<SearchResult>, String> {
+        let api_url = self
+            .api_url
+            .as_deref()
+            .ok_or("Search needs a blog API server (BLOG_API_URL)")?;
+        #[cfg(target_arch = "wasm32")]
+        {
+            let mut path = format!("/search?q={}", js_sys::encode_uri_component(text));
+            if let Some(tag) = &filters.tag {
+                path.push_str(&format!("&tag={}", js_sys::encode_uri_component(tag)));
+            }
+            if let Some(from) = filters.from {
+                path.push_str(&format!("&from={}", from));
+            }
+            if let Some(to) = filters.to {
+                path.push_str(&format!("&to={}", to));
+            }
+            let response: Option<SearchResponse> = self.api_get_json(api_url, &path).await?;
+            Ok(response.map(|response| response.results).unwrap_or_default())
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let mut query = solana_blog_client::SearchQuery::new(text).with_dates(filters.from, filters.to);
+            if let Some(tag) = &filters.tag {
+                query = query.with_tag(tag);
+            }
+            solana_blog_client::SearchClient::new(api_url)
+                .search(&query)
+                .await
+                .map_err(|e| format!("Search failed: {}", e))
+        }
+    }
+
+    /// Markdown body of a post, if the API has fetched it
+    pub async fn get_post_body(&self, post_pubkey: &str) -> Result<Option<String>, String> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            match self.api_url.as_deref() {
+                Some(api_url) => self.api_get(api_url, &format!("/posts/{}/body", post_pubkey)).await,
+                None => Ok(None),
+            }
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let _ = post_pubkey;
+            Ok(None)
+        }
     }
 
     #[cfg(not(target_arch = "wasm32"))]
     fn create_client(&self) -> Result<BlogClient, String> {
-        BlogClient::new("https://api.devnet.solana.com", crate::config::PROGRAM_ID)
+        BlogClient::new(crate::config::RPC_URL, crate::config::PROGRAM_ID)
+            .map(|client| client.with_ws_url(crate::config::WS_URL))
             .map_err(|e| format!("Failed to create client: {}", e))
     }
 
+    #[cfg(target_arch = "wasm32")]
+    fn rpc(&self) -> RpcClient {
+        RpcClient::new(crate::config::RPC_URL)
+    }
+
+    /// Follow changes to a blog's posts as they land on chain
+    pub async fn watch_blog(&self, blog_pubkey: &str) -> Result<BlogWatch, String> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            BlogWatch::open(&self.create_client()?, blog_pubkey).await
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            BlogWatch::open(crate::config::WS_URL, crate::config::PROGRAM_ID, blog_pubkey).await
+        }
+    }
+
     pub async fn get_blog(&self, blog_pubkey: &str) -> Result<BlogInfo, String> {
         #[cfg(not(target_arch = "wasm32"))]
         {
@@ -66,74 +224,141 @@ impl BlogService {
         }
         #[cfg(target_arch = "wasm32")]
         {
-            // Mock implementation for WASM
-            if blog_pubkey == crate::config::DEMO_BLOG_PUBKEY {
-                Ok(BlogInfo {
-                    pubkey: blog_pubkey.to_string(),
-                    authority: "11111111111111111111111111111114".to_string(),
-                    title: "Solana verystochastic".to_string(),
-                    description: "Decentralized finance disasters and lessons from the blockchain".to_string(),
-                    post_count: 3,
-                    created_at: 1699123456,
-                })
-            } else {
-                Err("Blog not found".to_string())
-            }
+            let blog = match self.api_url.as_deref() {
+                Some(api_url) => self.api_get_json(api_url, &format!("/blogs/{}", blog_pubkey)).await?,
+                None => self.rpc().get_blog(blog_pubkey).await?,
+            };
+            blog.ok_or_else(|| "Blog not found".to_string())
         }
     }
 
-    pub async fn get_posts(&self, blog_pubkey: &str) -> Result<Vec<PostInfo>, String> {
+    pub async fn get_post(&self, post_pubkey: &str) -> Result<PostInfo, String> {
         #[cfg(not(target_arch = "wasm32"))]
         {
-            // For now, return empty list as get_posts doesn't exist in client
-            // TODO: Implement get_posts in blog client
-            Ok(vec![])
+            let client = self.create_client()?;
+            let pubkey = post_pubkey.parse()
+                .map_err(|e| format!("Invalid pubkey: {}", e))?;
+
+            client.get_post(pubkey).await
+                .map_err(|e| format!("Failed to get post: {}", e))
         }
         #[cfg(target_arch = "wasm32")]
         {
-            // Mock implementation for WASM
-            if blog_pubkey == crate::config::DEMO_BLOG_PUBKEY {
-                Ok(vec![
-                    PostInfo {
-                        pubkey: "11111111111111111111111111111115".to_string(),
-                        blog: blog_pubkey.to_string(),
-                        author: "11111111111111111111111111111116".to_string(),
-                        title: "GMX - verystochastic".to_string(),
-                        content: "The largest GMX exploit in DeFi history. Over $50M drained from liquidity pools due to a price manipulation attack on Arbitrum.".to_string(),
-                        created_at: 1699789012,
-                        image_url: Some("/api/placeholder/600/300".to_string()),
-                    },
-                    PostInfo {
-                        pubkey: "11111111111111111111111111111117".to_string(),
-                        blog: blog_pubkey.to_string(),
-                        author: "11111111111111111111111111111116".to_string(),
-                        title: "Solana Validator - verystochastic".to_string(),
-                        content: "Major Solana validator cluster went down for 17 hours due to a botched network upgrade.".to_string(),
-                        created_at: 1699702345,
-                        image_url: Some("/api/placeholder/600/300".to_string()),
-                    },
-                ])
-            } else {
-                Ok(vec![])
-            }
+            let post = match self.api_url.as_deref() {
+                Some(api_url) => self.api_get_json(api_url, &format!("/posts/{}", post_pubkey)).await?,
+                None => self.rpc().get_post(post_pubkey).await?,
+            };
+            post.ok_or_else(|| "Post not found".to_string())
         }
     }
 
-    pub async fn create_post(&self, _blog_pubkey: &str, _request: CreatePostRequest) -> Result<String, String> {
+    /// A blog's posts, newest first
+    pub async fn get_posts(&self, blog_pubkey: &str) -> Result<Vec<PostInfo>, String> {
         #[cfg(not(target_arch = "wasm32"))]
         {
-            // Real implementation would go here
-            Err("Not implemented for desktop".to_string())
+            let client = self.create_client()?;
+            let pubkey = blog_pubkey.parse()
+                .map_err(|e| format!("Invalid pubkey: {}", e))?;
+
+            client.get_posts_by_blog(pubkey).await
+                .map_err(|e| format!("Failed to get posts: {}", e))
         }
         #[cfg(target_arch = "wasm32")]
         {
-            // Mock implementation for WASM
-            // Simulate API delay
-            let _ = gloo_timers::future::TimeoutFuture::new(2000).await;
-            
-            // Return a mock post pubkey
-            Ok("11111111111111111111111111111119".to_string())
+            let Some(api_url) = self.api_url.as_deref() else {
+                return self.rpc().get_posts(crate::config::PROGRAM_ID, blog_pubkey).await;
+            };
+
+            let mut posts = Vec::new();
+            let mut cursor: Option<String> = None;
+            loop {
+                let path = match &cursor {
+                    Some(cursor) => format!("/blogs/{}/posts?limit=100&cursor={}", blog_pubkey, cursor),
+                    None => format!("/blogs/{}/posts?limit=100", blog_pubkey),
+                };
+                let Some(page) = self.api_get_json::<PostPage>(api_url, &path).await? else {
+                    return Err("Blog not found".to_string());
+                };
+                posts.extend(page.posts);
+                match page.next_cursor {
+                    Some(next) => cursor = Some(next),
+                    None => return Ok(posts),
+                }
+            }
+        }
+    }
+
+    /// Create a post on chain, signed by `wallet` as its author. The post
+    /// account is a new keypair that signs locally and is then discarded.
+    /// Returns the post's pubkey.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn create_post(
+        &self,
+        _wallet: &WalletService,
+        _blog_pubkey: &str,
+        _request: CreatePostRequest,
+    ) -> Result<String, WalletError> {
+        Err(WalletError::Failed("Publishing needs a browser wallet; use blogctl on desktop".to_string()))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub async fn create_post(
+        &self,
+        wallet: &WalletService,
+        blog_pubkey: &str,
+        request: CreatePostRequest,
+    ) -> Result<String, WalletError> {
+        use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+        use solana_blog_types::instruction::BlogInstruction;
+        use solana_program::{
+            instruction::{AccountMeta, Instruction},
+            system_program,
+        };
+
+        // Only publish posts whose content really is on Arweave
+        if URL_SAFE_NO_PAD.decode(&request.arweave_hash).map_or(true, |id| id.len() != 32) {
+            return Err(WalletError::Failed(format!(
+                "'{}' is not an Arweave transaction id",
+                request.arweave_hash
+            )));
         }
+
+        let program_id: Pubkey = crate::config::PROGRAM_ID
+            .parse()
+            .map_err(|e| WalletError::Failed(format!("Invalid program id: {:?}", e)))?;
+        let blog: Pubkey = blog_pubkey
+            .parse()
+            .map_err(|e| WalletError::Failed(format!("Invalid pubkey: {:?}", e)))?;
+        let author: Pubkey = wallet
+            .public_key
+            .as_deref()
+            .ok_or(WalletError::NotConnected)?
+            .parse()
+            .map_err(|e| WalletError::Failed(format!("Invalid wallet pubkey: {:?}", e)))?;
+
+        let data = borsh::to_vec(&BlogInstruction::CreatePost {
+            title: request.title,
+            content: request.content,
+            arweave_hash: request.arweave_hash,
+        })
+        .map_err(|e| WalletError::Failed(format!("Failed to serialize instruction: {}", e)))?;
+        let post = Keypair::generate().map_err(WalletError::Failed)?;
+        let instruction = Instruction::new_with_bytes(
+            program_id,
+            &data,
+            vec![
+                AccountMeta::new(author, true),
+                AccountMeta::new(post.pubkey(), true),
+                AccountMeta::new(blog, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        );
+
+        let blockhash = self.rpc().get_latest_blockhash().await.map_err(WalletError::Network)?;
+        let mut transaction = Transaction::new(&[instruction], &author, &blockhash).map_err(WalletError::Failed)?;
+        transaction.sign(&post).map_err(WalletError::Failed)?;
+        wallet.sign_and_send(transaction).await?;
+        Ok(post.pubkey().to_string())
     }
 
     pub async fn initialize_blog(&self, _title: String, _description: String) -> Result<String, String> {
@@ -154,6 +379,47 @@ impl BlogService {
     }
 }
 
+/// Why a wallet request did not go through
+#[derive(Debug, Clone, PartialEq)]
+pub enum WalletError {
+    NotConnected,
+    /// The user declined the request in their wallet
+    Rejected,
+    /// The cluster could not be reached, or did not accept the transaction
+    Network(String),
+    /// The wallet, or the transaction on chain, failed
+    Failed(String),
+}
+
+impl std::fmt::Display for WalletError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WalletError::NotConnected => write!(f, "Wallet not connected"),
+            WalletError::Rejected => write!(f, "Request rejected in wallet"),
+            WalletError::Network(message) => write!(f, "Network error: {}", message),
+            WalletError::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+// Phantom rejects with EIP-1193 style errors; 4001 is the user declining
+#[cfg(target_arch = "wasm32")]
+fn wallet_error(error: wasm_bindgen::JsValue) -> WalletError {
+    use wasm_bindgen::JsValue;
+
+    let code = js_sys::Reflect::get(&error, &JsValue::from_str("code"))
+        .ok()
+        .and_then(|code| code.as_f64());
+    if code == Some(4001.0) {
+        return WalletError::Rejected;
+    }
+    let message = js_sys::Reflect::get(&error, &JsValue::from_str("message"))
+        .ok()
+        .and_then(|message| message.as_string())
+        .unwrap_or_else(|| format!("{:?}", error));
+    WalletError::Failed(format!("Wallet error: {}", message))
+}
+
 // Wallet service for handling Solana wallet connections
 #[derive(Debug, Clone)]
 pub struct WalletService {
@@ -226,6 +492,113 @@ impl WalletService {
         Ok("11111111111111111111111111111116".to_string())
     }
 
+    #[cfg(target_arch = "wasm32")]
+    fn provider(&self) -> Result<wasm_bindgen::JsValue, WalletError> {
+        use wasm_bindgen::JsValue;
+
+        if !self.connected {
+            return Err(WalletError::NotConnected);
+        }
+        let window = web_sys::window().ok_or_else(|| WalletError::Failed("No window object".to_string()))?;
+        js_sys::Reflect::get(&window, &JsValue::from_str("solana"))
+            .ok()
+            .filter(|phantom| !phantom.is_undefined())
+            .ok_or(WalletError::NotConnected)
+    }
+
+    // Call `method` on the wallet provider and wait for its promise
+    #[cfg(target_arch = "wasm32")]
+    async fn call_provider(&self, method: &str, args: &js_sys::Array) -> Result<wasm_bindgen::JsValue, WalletError> {
+        use wasm_bindgen::{JsCast, JsValue};
+        use wasm_bindgen_futures::JsFuture;
+
+        let provider = self.provider()?;
+        let function = js_sys::Reflect::get(&provider, &JsValue::from_str(method))
+            .ok()
+            .and_then(|function| function.dyn_into::<js_sys::Function>().ok())
+            .ok_or_else(|| WalletError::Failed(format!("Wallet does not support {}", method)))?;
+        let promise = function.apply(&provider, args).map_err(wallet_error)?;
+        JsFuture::from(js_sys::Promise::from(promise)).await.map_err(wallet_error)
+    }
+
+    /// Have the wallet sign an arbitrary message, such as a login challenge
+    #[cfg(target_arch = "wasm32")]
+    pub async fn sign_message(&self, message: &[u8]) -> Result<Vec<u8>, WalletError> {
+        use wasm_bindgen::JsValue;
+
+        let args = js_sys::Array::of2(&js_sys::Uint8Array::from(message).into(), &JsValue::from_str("utf8"));
+        let result = self.call_provider("signMessage", &args).await?;
+        let signature = js_sys::Reflect::get(&result, &JsValue::from_str("signature"))
+            .map_err(wallet_error)?;
+        Ok(js_sys::Uint8Array::new(&signature).to_vec())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn sign_message(&self, _message: &[u8]) -> Result<Vec<u8>, WalletError> {
+        Err(WalletError::Failed("Browser wallets are only available on the web".to_string()))
+    }
+
+    /// Have the wallet sign `transaction` as its fee payer, then submit it
+    /// and wait for it to be confirmed. Any other signers must already
+    /// have signed. Returns the transaction signature.
+    #[cfg(target_arch = "wasm32")]
+    pub async fn sign_and_send(&self, mut transaction: Transaction) -> Result<String, WalletError> {
+        use base58::{FromBase58, ToBase58};
+        use wasm_bindgen::JsValue;
+
+        let public_key = self.public_key.as_deref().ok_or(WalletError::NotConnected)?;
+        let payer = transaction.fee_payer();
+        if payer.to_string() != public_key {
+            return Err(WalletError::Failed(format!("Transaction must be paid for by the connected wallet, not {}", payer)));
+        }
+
+        // The wallet takes the whole serialized transaction, with a zeroed
+        // slot for its own signature
+        let params = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&params, &JsValue::from_str("message"), &JsValue::from_str(&transaction.serialize().to_base58()));
+        let request = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&request, &JsValue::from_str("method"), &JsValue::from_str("signTransaction"));
+        let _ = js_sys::Reflect::set(&request, &JsValue::from_str("params"), &params);
+        let result = self.call_provider("request", &js_sys::Array::of1(&request)).await?;
+
+        // Depending on the version, the answer is the signed transaction or
+        // just the fee payer's signature, both base58
+        let field = |name: &str| {
+            js_sys::Reflect::get(&result, &JsValue::from_str(name))
+                .ok()
+                .and_then(|value| value.as_string())
+                .and_then(|value| value.from_base58().ok())
+        };
+        let signature = match (field("transaction"), field("signature")) {
+            (Some(signed), _) => transaction.signature_in(&signed, &payer).map_err(WalletError::Failed)?,
+            (None, Some(signature)) => <[u8; 64]>::try_from(signature)
+                .map_err(|_| WalletError::Failed("Wallet returned an invalid signature".to_string()))?,
+            (None, None) => return Err(WalletError::Failed("Wallet returned no signature".to_string())),
+        };
+        transaction.add_signature(&payer, signature).map_err(WalletError::Failed)?;
+
+        let rpc = RpcClient::new(crate::config::RPC_URL);
+        let signature = rpc
+            .send_transaction(&transaction.serialize())
+            .await
+            .map_err(WalletError::Network)?;
+
+        // Confirmation usually takes a couple of seconds
+        for _ in 0..30 {
+            gloo_timers::future::TimeoutFuture::new(1000).await;
+            let Some(status) = rpc.get_signature_status(&signature).await.map_err(WalletError::Network)? else {
+                continue;
+            };
+            if let Some(err) = status.err {
+                return Err(WalletError::Failed(format!("Transaction {} failed: {}", signature, err)));
+            }
+            if matches!(status.confirmation_status.as_deref(), Some("confirmed" | "finalized")) {
+                return Ok(signature);
+            }
+        }
+        Err(WalletError::Network(format!("Transaction {} was not confirmed in time", signature)))
+    }
+
     pub fn disconnect(&mut self) {
         self.connected = false;
         self.public_key = None;
@@ -237,11 +610,6 @@ impl WalletService {
         if let Some(public_key) = &self.public_key {
             // Updated with your actual wallet address
             const OWNER_WALLET: &str = "FzdG9aXQN9fZpDyZvbqMu2zG1PzmdyLzX6nQnDRQRZL7";
-            
-            // For development, you can temporarily log the connected wallet
-            #[cfg(target_arch = "wasm32")]
-            web_sys::console::log_1(&format!("Connected wallet: {}", public_key).into());
-            
             public_key == OWNER_WALLET && self.connected
         } else {
             false
@@ -0,0 +1,197 @@
+// JSON-RPC over `fetch` for the web build, which cannot use solana-client.
+// Accounts are decoded with the shared state types, as the native client
+// does.
+use super::{BlogInfo, PostInfo};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use solana_blog_types::{
+    state::{BlogPost, ProgramAccount},
+    Pubkey,
+};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UiAccount {
+    // `[data, encoding]`
+    data: (String, String),
+}
+
+#[derive(Debug, Deserialize)]
+struct WithContext<T> {
+    value: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyedAccount {
+    pubkey: String,
+    account: UiAccount,
+}
+
+#[derive(Debug, Deserialize)]
+struct LatestBlockhash {
+    blockhash: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureStatus {
+    /// Why the transaction failed, if it did
+    pub err: Option<Value>,
+    pub confirmation_status: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcClient {
+    url: String,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> Self {
+        Self { url: url.to_string() }
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T, String> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+
+        let init = web_sys::RequestInit::new();
+        init.set_method("POST");
+        init.set_body(&JsValue::from_str(&body.to_string()));
+        let request = web_sys::Request::new_with_str_and_init(&self.url, &init)
+            .map_err(|e| format!("Failed to build RPC request: {:?}", e))?;
+        request
+            .headers()
+            .set("Content-Type", "application/json")
+            .map_err(|e| format!("Failed to build RPC request: {:?}", e))?;
+
+        let window = web_sys::window().ok_or("No window object")?;
+        let response = JsFuture::from(window.fetch_with_request(&request))
+            .await
+            .map_err(|e| format!("RPC request failed: {:?}", e))?;
+        let response: web_sys::Response = response.unchecked_into();
+        if !response.ok() {
+            return Err(format!("RPC request failed with status: {}", response.status()));
+        }
+        let text = response
+            .text()
+            .map_err(|e| format!("Failed to read RPC response: {:?}", e))?;
+        let text = JsFuture::from(text)
+            .await
+            .map_err(|e| format!("Failed to read RPC response: {:?}", e))?
+            .as_string()
+            .unwrap_or_default();
+
+        let response: RpcResponse<T> =
+            serde_json::from_str(&text).map_err(|e| format!("Invalid RPC response: {}", e))?;
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(format!("{} failed ({}): {}", method, error.code, error.message)),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(format!("{} returned no result", method)),
+        }
+    }
+
+    fn decode(account: &UiAccount) -> Result<Vec<u8>, String> {
+        STANDARD
+            .decode(&account.data.0)
+            .map_err(|e| format!("Invalid account data: {}", e))
+    }
+
+    /// Data of an account; `None` if it does not exist
+    pub async fn get_account_data(&self, pubkey: &str) -> Result<Option<Vec<u8>>, String> {
+        let response: WithContext<Option<UiAccount>> = self
+            .call(
+                "getAccountInfo",
+                json!([pubkey, { "encoding": "base64", "commitment": "confirmed" }]),
+            )
+            .await?;
+        response.value.as_ref().map(Self::decode).transpose()
+    }
+
+    /// Accounts owned by `program_id` that match every memcmp `filters` entry
+    pub async fn get_program_accounts(&self, program_id: &str, filters: Value) -> Result<Vec<(String, Vec<u8>)>, String> {
+        let accounts: Vec<KeyedAccount> = self
+            .call(
+                "getProgramAccounts",
+                json!([program_id, { "encoding": "base64", "commitment": "confirmed", "filters": filters }]),
+            )
+            .await?;
+        accounts
+            .into_iter()
+            .map(|keyed| Ok((keyed.pubkey.clone(), Self::decode(&keyed.account)?)))
+            .collect()
+    }
+
+    pub async fn get_latest_blockhash(&self) -> Result<String, String> {
+        let response: WithContext<LatestBlockhash> = self
+            .call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))
+            .await?;
+        Ok(response.value.blockhash)
+    }
+
+    /// Submit a signed transaction, returning its signature. The node
+    /// simulates it first, so most program errors show up here.
+    pub async fn send_transaction(&self, transaction: &[u8]) -> Result<String, String> {
+        self.call(
+            "sendTransaction",
+            json!([STANDARD.encode(transaction), { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+        )
+        .await
+    }
+
+    /// Status of a transaction; `None` until a node has seen it
+    pub async fn get_signature_status(&self, signature: &str) -> Result<Option<SignatureStatus>, String> {
+        let response: WithContext<Vec<Option<SignatureStatus>>> = self
+            .call("getSignatureStatuses", json!([[signature]]))
+            .await?;
+        Ok(response.value.into_iter().next().flatten())
+    }
+
+    pub async fn get_blog(&self, blog_pubkey: &str) -> Result<Option<BlogInfo>, String> {
+        let pubkey = parse_pubkey(blog_pubkey)?;
+        match self.get_account_data(blog_pubkey).await?.as_deref().and_then(ProgramAccount::decode) {
+            Some(ProgramAccount::Blog(blog)) => Ok(Some(BlogInfo::new(pubkey, blog))),
+            _ => Ok(None),
+        }
+    }
+
+    pub async fn get_post(&self, post_pubkey: &str) -> Result<Option<PostInfo>, String> {
+        let pubkey = parse_pubkey(post_pubkey)?;
+        match self.get_account_data(post_pubkey).await?.as_deref().and_then(ProgramAccount::decode) {
+            Some(ProgramAccount::Post(post)) => Ok(Some(PostInfo::new(pubkey, post))),
+            _ => Ok(None),
+        }
+    }
+
+    /// A blog's posts, newest first
+    pub async fn get_posts(&self, program_id: &str, blog_pubkey: &str) -> Result<Vec<PostInfo>, String> {
+        let filters = json!([{ "memcmp": { "offset": BlogPost::BLOG_OFFSET, "bytes": blog_pubkey } }]);
+        let mut posts: Vec<PostInfo> = self
+            .get_program_accounts(program_id, filters)
+            .await?
+            .into_iter()
+            .filter_map(|(pubkey, data)| match ProgramAccount::decode(&data) {
+                Some(ProgramAccount::Post(post)) => Some(PostInfo::new(pubkey.parse().ok()?, post)),
+                _ => None,
+            })
+            .collect();
+        posts.sort_by_key(|post| std::cmp::Reverse(post.created_at));
+        Ok(posts)
+    }
+}
+
+fn parse_pubkey(pubkey: &str) -> Result<Pubkey, String> {
+    pubkey.parse().map_err(|e| format!("Invalid pubkey: {:?}", e))
+}
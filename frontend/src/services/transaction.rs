@@ -0,0 +1,191 @@
+// Legacy transactions built without solana-sdk, which does not build for
+// wasm. The wallet signs as fee payer; other signers, such as a new post
+// account, sign locally.
+use ed25519_dalek::{PublicKey, SecretKey, Signer};
+use solana_blog_types::Pubkey;
+use solana_program::{hash::Hash, instruction::Instruction, message::Message};
+
+/// Throwaway ed25519 keypair, for accounts created by a transaction
+pub struct Keypair(ed25519_dalek::Keypair);
+
+impl Keypair {
+    pub fn generate() -> Result<Self, String> {
+        let mut seed = [0u8; 32];
+        getrandom::getrandom(&mut seed).map_err(|e| format!("Failed to generate keypair: {}", e))?;
+        Self::from_seed(&seed)
+    }
+
+    fn from_seed(seed: &[u8; 32]) -> Result<Self, String> {
+        let secret = SecretKey::from_bytes(seed).map_err(|e| format!("Failed to generate keypair: {}", e))?;
+        let public = PublicKey::from(&secret);
+        Ok(Self(ed25519_dalek::Keypair { secret, public }))
+    }
+
+    pub fn pubkey(&self) -> Pubkey {
+        Pubkey::new_from_array(self.0.public.to_bytes())
+    }
+}
+
+pub struct Transaction {
+    message: Message,
+    // One per required signer, in account key order; zeroed until signed
+    signatures: Vec<[u8; 64]>,
+}
+
+impl Transaction {
+    pub fn new(instructions: &[Instruction], payer: &Pubkey, recent_blockhash: &str) -> Result<Self, String> {
+        let blockhash: Hash = recent_blockhash
+            .parse()
+            .map_err(|e| format!("Invalid blockhash: {:?}", e))?;
+        let message = Message::new_with_blockhash(instructions, Some(payer), &blockhash);
+        let signatures = vec![[0u8; 64]; message.header.num_required_signatures as usize];
+        Ok(Self { message, signatures })
+    }
+
+    pub fn fee_payer(&self) -> Pubkey {
+        self.message.account_keys[0]
+    }
+
+    /// Serialized message, which is what every signer signs
+    pub fn message_data(&self) -> Vec<u8> {
+        self.message.serialize()
+    }
+
+    pub fn sign(&mut self, keypair: &Keypair) -> Result<(), String> {
+        let signature = keypair.0.sign(&self.message_data()).to_bytes();
+        self.add_signature(&keypair.pubkey(), signature)
+    }
+
+    /// Add a signature made elsewhere, such as by a wallet
+    pub fn add_signature(&mut self, signer: &Pubkey, signature: [u8; 64]) -> Result<(), String> {
+        let index = self.signer_index(signer)?;
+        self.signatures[index] = signature;
+        Ok(())
+    }
+
+    /// `signer`'s signature in `data`, a serialized copy of this transaction
+    /// signed elsewhere. A copy whose message differs is refused, so a
+    /// wallet cannot swap in other instructions.
+    pub fn signature_in(&self, data: &[u8], signer: &Pubkey) -> Result<[u8; 64], String> {
+        let index = self.signer_index(signer)?;
+        let (count, length) = decode_length(data).ok_or("Signed transaction has an invalid signature count")?;
+        let signatures = data[length..]
+            .get(..count * 64)
+            .filter(|_| count == self.signatures.len())
+            .ok_or("Signed transaction has the wrong number of signatures")?;
+        if data[length + signatures.len()..] != self.message_data() {
+            return Err("Signed transaction does not match the one sent for signing".to_string());
+        }
+        let mut signature = [0u8; 64];
+        signature.copy_from_slice(&signatures[index * 64..(index + 1) * 64]);
+        Ok(signature)
+    }
+
+    fn signer_index(&self, signer: &Pubkey) -> Result<usize, String> {
+        self.message.account_keys[..self.signatures.len()]
+            .iter()
+            .position(|key| key == signer)
+            .ok_or_else(|| format!("{} is not a signer of this transaction", signer))
+    }
+
+    /// Wire format: compact-u16 signature count, signatures, message
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut count = self.signatures.len();
+        loop {
+            let byte = (count & 0x7f) as u8;
+            count >>= 7;
+            if count == 0 {
+                data.push(byte);
+                break;
+            }
+            data.push(byte | 0x80);
+        }
+        for signature in &self.signatures {
+            data.extend_from_slice(signature);
+        }
+        data.extend(self.message_data());
+        data
+    }
+}
+
+/// A compact-u16 at the start of `data` and the number of bytes it took
+fn decode_length(data: &[u8]) -> Option<(usize, usize)> {
+    let mut value = 0;
+    for (i, byte) in data.iter().take(3).enumerate() {
+        value |= ((byte & 0x7f) as usize) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::instruction::AccountMeta;
+    use solana_sdk::{signer::keypair::keypair_from_seed, transaction::Transaction as SdkTransaction};
+
+    fn instruction(signers: &[Pubkey]) -> Instruction {
+        let mut accounts: Vec<AccountMeta> = signers.iter().map(|signer| AccountMeta::new(*signer, true)).collect();
+        accounts.push(AccountMeta::new_readonly(Pubkey::new_unique(), false));
+        Instruction::new_with_bytes(Pubkey::new_unique(), &[1, 2, 3], accounts)
+    }
+
+    /// Sign with the same keys here and with solana-sdk, then compare the
+    /// wire bytes
+    fn assert_matches_sdk(seeds: &[[u8; 32]]) {
+        let keypairs: Vec<Keypair> = seeds.iter().map(|seed| Keypair::from_seed(seed).unwrap()).collect();
+        let sdk_keypairs: Vec<_> = seeds.iter().map(|seed| keypair_from_seed(seed).unwrap()).collect();
+        let signers: Vec<Pubkey> = keypairs.iter().map(Keypair::pubkey).collect();
+        let instructions = [instruction(&signers)];
+        let blockhash = Hash::new_unique();
+
+        let mut transaction = Transaction::new(&instructions, &signers[0], &blockhash.to_string()).unwrap();
+        let message = Message::new_with_blockhash(&instructions, Some(&signers[0]), &blockhash);
+        assert_eq!(transaction.message_data(), message.serialize());
+        let unsigned = SdkTransaction::new_unsigned(message);
+        assert_eq!(transaction.serialize(), bincode::serialize(&unsigned).unwrap());
+
+        for keypair in &keypairs {
+            transaction.sign(keypair).unwrap();
+        }
+        let sdk_keypairs: Vec<_> = sdk_keypairs.iter().collect();
+        let signed = SdkTransaction::new_signed_with_payer(&instructions, Some(&signers[0]), &sdk_keypairs, blockhash);
+        assert_eq!(transaction.serialize(), bincode::serialize(&signed).unwrap());
+    }
+
+    #[test]
+    fn serializes_like_solana_sdk_with_one_signer() {
+        assert_matches_sdk(&[[1; 32]]);
+    }
+
+    #[test]
+    fn serializes_like_solana_sdk_with_several_signers() {
+        assert_matches_sdk(&[[1; 32], [2; 32], [3; 32]]);
+    }
+
+    #[test]
+    fn wallet_signatures_are_read_from_the_signed_copy() {
+        let wallet = Keypair::from_seed(&[1; 32]).unwrap();
+        let post = Keypair::from_seed(&[2; 32]).unwrap();
+        let signers = [wallet.pubkey(), post.pubkey()];
+        let blockhash = Hash::new_unique().to_string();
+        let mut transaction = Transaction::new(&[instruction(&signers)], &signers[0], &blockhash).unwrap();
+        transaction.sign(&post).unwrap();
+
+        // What the wallet sends back: the same transaction with its slot filled
+        let mut signed = Transaction {
+            message: transaction.message.clone(),
+            signatures: transaction.signatures.clone(),
+        };
+        signed.sign(&wallet).unwrap();
+        let signature = transaction.signature_in(&signed.serialize(), &wallet.pubkey()).unwrap();
+        assert_eq!(signature, signed.signatures[0]);
+
+        let other = Transaction::new(&[instruction(&signers)], &signers[0], &blockhash).unwrap();
+        assert!(transaction.signature_in(&other.serialize(), &wallet.pubkey()).is_err());
+        assert!(transaction.signature_in(&[2], &wallet.pubkey()).is_err());
+    }
+}
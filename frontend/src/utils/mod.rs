@@ -32,6 +32,46 @@ pub fn format_timestamp(timestamp: i64) -> String {
     }
 }
 
+/// `YYYY-MM-DD`, as date inputs give it, to the unix time of its midnight UTC
+pub fn parse_date(value: &str) -> Option<i64> {
+    chrono::NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|midnight| midnight.and_utc().timestamp())
+}
+
+/// Split the search API's highlighted HTML into text runs, each flagged
+/// when it was a match. Only `<mark>` is taken from the markup; the rest
+/// is unescaped and rendered as text, so nothing from the API becomes HTML.
+pub fn highlight_segments(html: &str) -> Vec<(String, bool)> {
+    let mut segments = Vec::new();
+    let mut rest = html;
+    let mut marked = false;
+    loop {
+        let tag = if marked { "</mark>" } else { "<mark>" };
+        let (text, next) = match rest.find(tag) {
+            Some(index) => (&rest[..index], Some(&rest[index + tag.len()..])),
+            None => (rest, None),
+        };
+        if !text.is_empty() {
+            let text = text
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&#39;", "'")
+                .replace("&amp;", "&");
+            segments.push((text, marked));
+        }
+        match next {
+            Some(next) => {
+                rest = next;
+                marked = !marked;
+            }
+            None => return segments,
+        }
+    }
+}
+
 pub fn truncate_string(s: &str, max_length: usize) -> String {
     if s.len() <= max_length {
         s.to_string()
@@ -0,0 +1,310 @@
+use crate::{db::Database, Result};
+use axum::{
+    extract::{Path, Query, State},
+    http::{
+        header::{CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+        HeaderMap, HeaderValue, StatusCode,
+    },
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use solana_blog_client::search::{SearchQuery, SearchResponse};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, Mutex};
+use tower_http::cors::CorsLayer;
+
+/// Posts per page when the request does not say
+const DEFAULT_PAGE_SIZE: usize = 20;
+const MAX_PAGE_SIZE: usize = 100;
+/// Listings change with every indexed transaction, so are only briefly
+/// cacheable; the ETag makes revalidation cheap
+const JSON_CACHE_CONTROL: &str = "public, max-age=10";
+const BODY_CACHE_CONTROL: &str = "public, max-age=60";
+
+pub type SharedDatabase = Arc<Mutex<Database>>;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PostsQuery {
+    pub tag: Option<String>,
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Read-only JSON API over an index database:
+///
+/// - `GET /blogs`
+/// - `GET /blogs/{id}`
+/// - `GET /blogs/{id}/posts?tag=&cursor=&limit=`
+/// - `GET /posts/{id}`
+/// - `GET /posts/{id}/body` (markdown, once fetched by the indexer)
+/// - `GET /search?q=&blog=&tag=&from=&to=&limit=`
+pub fn router(db: SharedDatabase) -> Router {
+    Router::new()
+        .route("/blogs", get(list_blogs))
+        .route("/blogs/:id", get(get_blog))
+        .route("/blogs/:id/posts", get(list_posts))
+        .route("/posts/:id", get(get_post))
+        .route("/posts/:id/body", get(get_post_body))
+        .route("/search", get(search))
+        // The frontend is served from another origin
+        .layer(CorsLayer::permissive())
+        .with_state(db)
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, axum::Json(json!({ "error": message.into() }))).into_response()
+}
+
+fn not_found(what: &str, id: &str) -> Response {
+    error(StatusCode::NOT_FOUND, format!("{} {} not found", what, id))
+}
+
+/// Run a query against the shared connection
+fn query<T>(db: &SharedDatabase, f: impl FnOnce(&Database) -> Result<T>) -> std::result::Result<T, String> {
+    let db = db.lock().map_err(|_| "Database lock poisoned".to_string())?;
+    f(&db).map_err(|e| e.message)
+}
+
+fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|candidate| candidate.trim() == etag || candidate.trim() == "*"))
+}
+
+/// Respond with `body`, or 304 if the client already has this version
+fn cached(headers: &HeaderMap, etag: String, cache_control: &'static str, content_type: &'static str, body: Vec<u8>) -> Response {
+    let etag_value = HeaderValue::from_str(&etag).unwrap_or_else(|_| HeaderValue::from_static("\"\""));
+    let cache_headers = [(ETAG, etag_value), (CACHE_CONTROL, HeaderValue::from_static(cache_control))];
+    if if_none_match(headers, &etag) {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+    (cache_headers, [(CONTENT_TYPE, HeaderValue::from_static(content_type))], body).into_response()
+}
+
+/// JSON response tagged with a hash of its contents
+fn json_response(headers: &HeaderMap, value: &impl Serialize) -> Response {
+    let body = match serde_json::to_vec(value) {
+        Ok(body) => body,
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    };
+    let digest = Sha256::digest(&body);
+    let etag = format!(
+        "\"{}\"",
+        digest[..16].iter().map(|byte| format!("{:02x}", byte)).collect::<String>()
+    );
+    cached(headers, etag, JSON_CACHE_CONTROL, "application/json", body)
+}
+
+async fn list_blogs(State(db): State<SharedDatabase>, headers: HeaderMap) -> Response {
+    match query(&db, |db| db.blogs()) {
+        Ok(blogs) => json_response(&headers, &json!({ "blogs": blogs })),
+        Err(message) => error(StatusCode::INTERNAL_SERVER_ERROR, message),
+    }
+}
+
+async fn get_blog(State(db): State<SharedDatabase>, Path(id): Path<String>, headers: HeaderMap) -> Response {
+    match query(&db, |db| db.blog_row(&id)) {
+        Ok(Some(blog)) => json_response(&headers, &blog),
+        Ok(None) => not_found("Blog", &id),
+        Err(message) => error(StatusCode::INTERNAL_SERVER_ERROR, message),
+    }
+}
+
+async fn list_posts(
+    State(db): State<SharedDatabase>,
+    Path(id): Path<String>,
+    Query(params): Query<PostsQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let limit = params.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let page = query(&db, |db| match db.blog_row(&id)? {
+        Some(_) => db
+            .posts_page(&id, params.tag.as_deref(), params.cursor.as_deref(), limit)
+            .map(Some),
+        None => Ok(None),
+    });
+    match page {
+        Ok(Some(page)) => json_response(&headers, &page),
+        Ok(None) => not_found("Blog", &id),
+        Err(message) => error(StatusCode::INTERNAL_SERVER_ERROR, message),
+    }
+}
+
+async fn get_post(State(db): State<SharedDatabase>, Path(id): Path<String>, headers: HeaderMap) -> Response {
+    match query(&db, |db| db.post_row(&id)) {
+        Ok(Some(post)) => json_response(&headers, &post),
+        Ok(None) => not_found("Post", &id),
+        Err(message) => error(StatusCode::INTERNAL_SERVER_ERROR, message),
+    }
+}
+
+async fn get_post_body(State(db): State<SharedDatabase>, Path(id): Path<String>, headers: HeaderMap) -> Response {
+    match query(&db, |db| db.post_body(&id)) {
+        // Bodies are content addressed, so the content id is the ETag
+        Ok(Some((body, body_id))) => cached(
+            &headers,
+            format!("\"{}\"", body_id),
+            BODY_CACHE_CONTROL,
+            "text/markdown; charset=utf-8",
+            body.into_bytes(),
+        ),
+        Ok(None) => not_found("Body for post", &id),
+        Err(message) => error(StatusCode::INTERNAL_SERVER_ERROR, message),
+    }
+}
+
+async fn search(State(db): State<SharedDatabase>, Query(search): Query<SearchQuery>, headers: HeaderMap) -> Response {
+    match query(&db, |db| db.search(&search)) {
+        Ok(results) => json_response(&headers, &SearchResponse { results }),
+        Err(message) => error(StatusCode::INTERNAL_SERVER_ERROR, message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::{to_bytes, Body},
+        http::Request,
+    };
+    use solana_blog_program::state::{Blog, BlogPost};
+    use solana_sdk::pubkey::Pubkey;
+    use tower::ServiceExt;
+
+    /// A blog with posts `a` to `e`, newest last, of which `b` and `d` are
+    /// tagged `rust` and `a` has its body fetched
+    fn database() -> (SharedDatabase, Pubkey, Pubkey) {
+        let db = Database::open_in_memory().unwrap();
+        let blog = Pubkey::new_unique();
+        let blog_account = Blog {
+            authority: Pubkey::new_unique(),
+            title: "Blog".to_string(),
+            description: String::new(),
+            post_count: 5,
+            created_at: 1,
+        };
+        db.put_blog(&blog, &blog_account, 1).unwrap();
+        let mut first = None;
+        for (i, title) in ["a", "b", "c", "d", "e"].into_iter().enumerate() {
+            let pubkey = Pubkey::new_unique();
+            let post = BlogPost {
+                author: blog_account.authority,
+                blog,
+                title: title.to_string(),
+                content: String::new(),
+                arweave_hash: "a".repeat(43),
+                created_at: i as i64,
+                updated_at: i as i64,
+            };
+            db.put_post(&pubkey, &post, 1, None).unwrap();
+            let tags = match title {
+                "b" | "d" => vec!["rust".to_string()],
+                _ => Vec::new(),
+            };
+            db.set_post_body(&pubkey, &"a".repeat(43), (title == "a").then_some("# A"), &tags)
+                .unwrap();
+            first.get_or_insert(pubkey);
+        }
+        (Arc::new(Mutex::new(db)), blog, first.unwrap())
+    }
+
+    async fn get(db: &SharedDatabase, uri: &str, if_none_match: Option<&str>) -> Response {
+        let mut request = Request::get(uri);
+        if let Some(etag) = if_none_match {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        router(db.clone())
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn json(response: Response) -> serde_json::Value {
+        serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn responses_revalidate_with_their_etag() {
+        let (db, blog, post) = database();
+
+        for (uri, cache_control) in [
+            (format!("/blogs/{}", blog), JSON_CACHE_CONTROL),
+            (format!("/posts/{}/body", post), BODY_CACHE_CONTROL),
+        ] {
+            let response = get(&db, &uri, None).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()[CACHE_CONTROL], cache_control);
+            let etag = response.headers()[ETAG].to_str().unwrap().to_string();
+
+            let response = get(&db, &uri, Some(&etag)).await;
+            assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+            assert_eq!(response.headers()[ETAG], etag.as_str());
+            assert_eq!(response.headers()[CACHE_CONTROL], cache_control);
+            assert!(to_bytes(response.into_body(), usize::MAX).await.unwrap().is_empty());
+
+            let response = get(&db, &uri, Some("\"stale\", *")).await;
+            assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+            assert_eq!(get(&db, &uri, Some("\"stale\"")).await.status(), StatusCode::OK);
+        }
+
+        let body = get(&db, &format!("/posts/{}/body", post), None).await;
+        assert_eq!(body.headers()[ETAG], format!("\"{}\"", "a".repeat(43)).as_str());
+        assert_eq!(to_bytes(body.into_body(), usize::MAX).await.unwrap(), "# A");
+    }
+
+    #[tokio::test]
+    async fn posts_page_by_tag_and_cursor() {
+        let (db, blog, _) = database();
+
+        let mut titles = Vec::new();
+        let mut uri = format!("/blogs/{}/posts?limit=2", blog);
+        loop {
+            let page = json(get(&db, &uri, None).await).await;
+            let posts = page["posts"].as_array().unwrap();
+            assert!(posts.len() <= 2);
+            titles.extend(posts.iter().map(|post| post["title"].as_str().unwrap().to_string()));
+            match page["next_cursor"].as_str() {
+                Some(cursor) => uri = format!("/blogs/{}/posts?limit=2&cursor={}", blog, cursor),
+                None => break,
+            }
+        }
+        assert_eq!(titles, ["e", "d", "c", "b", "a"]);
+
+        let page = json(get(&db, &format!("/blogs/{}/posts?tag=rust", blog), None).await).await;
+        let tagged: Vec<&str> = page["posts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|post| post["title"].as_str().unwrap())
+            .collect();
+        assert_eq!(tagged, ["d", "b"]);
+    }
+
+    #[tokio::test]
+    async fn unknown_ids_are_not_found() {
+        let (db, blog, post) = database();
+        let missing = Pubkey::new_unique();
+
+        for uri in [
+            format!("/blogs/{}", missing),
+            format!("/blogs/{}/posts", missing),
+            format!("/posts/{}", missing),
+            format!("/posts/{}/body", missing),
+        ] {
+            let response = get(&db, &uri, None).await;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND, "{}", uri);
+            assert!(json(response).await["error"].as_str().unwrap().contains(&missing.to_string()));
+        }
+
+        // Known posts whose body the indexer has not fetched yet
+        let page = json(get(&db, &format!("/blogs/{}/posts?limit=1", blog), None).await).await;
+        let unfetched = page["posts"][0]["pubkey"].as_str().unwrap().to_string();
+        assert_ne!(unfetched, post.to_string());
+        let response = get(&db, &format!("/posts/{}/body", unfetched), None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
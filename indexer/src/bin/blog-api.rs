@@ -0,0 +1,140 @@
+use clap::Parser;
+use solana_blog_client::{BlogClient, LocalStore};
+use solana_blog_indexer::{api, Database, Indexer};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    path::PathBuf,
+    process::ExitCode,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Program id of the devnet deployment
+const DEFAULT_PROGRAM_ID: &str = "BmqUqrFGJA7C9nzv787SrC59PUs2kSUvgdhDru6NQFmQ";
+const DEFAULT_RPC_URL: &str = "https://api.devnet.solana.com";
+
+#[derive(Parser)]
+#[command(name = "blog-api", version, about = "Serve indexed Solana blogs and posts over HTTP")]
+struct Cli {
+    /// Address to listen on
+    #[arg(long, env = "BLOG_API_LISTEN", default_value = "127.0.0.1:8080")]
+    listen: String,
+
+    #[arg(long, short = 'u', env = "BLOG_RPC_URL", default_value = DEFAULT_RPC_URL)]
+    url: String,
+
+    #[arg(long, env = "BLOG_PROGRAM_ID", default_value = DEFAULT_PROGRAM_ID)]
+    program_id: String,
+
+    /// SQLite database file, created if missing
+    #[arg(long, env = "BLOG_INDEX_DB", default_value = "blog-index.sqlite")]
+    db: PathBuf,
+
+    /// Seconds between index refreshes
+    #[arg(long, default_value_t = 10)]
+    interval: u64,
+
+    /// Serve the database as is, for when `blog-indexer` maintains it
+    #[arg(long)]
+    no_refresh: bool,
+
+    /// Read post bodies from this directory instead of Arweave
+    #[arg(long)]
+    local_store: Option<PathBuf>,
+}
+
+/// Runtime the refresh thread drives its passes on
+fn refresh_runtime() -> std::io::Result<tokio::runtime::Runtime> {
+    tokio::runtime::Builder::new_current_thread().enable_all().build()
+}
+
+/// Keep the database fresh on a thread of its own, since the connection
+/// is not shared with the server
+fn spawn_refresh(cli: &Cli, program_id: Pubkey) -> Result<(), String> {
+    let db = Database::open(&cli.db).map_err(|e| e.to_string())?;
+    let mut client = BlogClient::new(&cli.url, &cli.program_id).map_err(|e| e.to_string())?;
+    if let Some(dir) = &cli.local_store {
+        client = client.with_content_store(LocalStore::new(dir));
+    }
+    let rpc_url = cli.url.clone();
+    let interval = Duration::from_secs(cli.interval);
+
+    std::thread::spawn(move || {
+        let runtime = match refresh_runtime() {
+            Ok(runtime) => runtime,
+            Err(e) => return eprintln!("error: failed to start refresh runtime: {}", e),
+        };
+        let indexer = Indexer::new(&rpc_url, program_id, db).with_content(client);
+        runtime.block_on(async {
+            loop {
+                // RPC failures are transient; keep serving what we have
+                if let Err(e) = indexer.catch_up().await {
+                    eprintln!("refresh failed: {}", e);
+                }
+                tokio::time::sleep(interval).await;
+            }
+        })
+    });
+    Ok(())
+}
+
+async fn run(cli: Cli) -> Result<(), String> {
+    let program_id = Pubkey::from_str(&cli.program_id).map_err(|e| format!("Invalid program ID: {}", e))?;
+    // Opening first creates the schema before the refresh thread starts
+    let db = Database::open(&cli.db).map_err(|e| e.to_string())?;
+    if !cli.no_refresh {
+        spawn_refresh(&cli, program_id)?;
+    }
+
+    let listener = tokio::net::TcpListener::bind(&cli.listen)
+        .await
+        .map_err(|e| format!("Failed to listen on {}: {}", cli.listen, e))?;
+    println!("Serving {} on http://{}", cli.db.display(), cli.listen);
+    axum::serve(listener, api::router(Arc::new(Mutex::new(db))))
+        .await
+        .map_err(|e| format!("Server error: {}", e))
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Json, Router};
+    use serde_json::{json, Value};
+
+    /// An RPC node with no program transactions yet
+    async fn rpc(Json(request): Json<Value>) -> Json<Value> {
+        let result = match request["method"].as_str().unwrap_or_default() {
+            "getVersion" => json!({ "solana-core": "1.18.0" }),
+            "getSignaturesForAddress" => json!([]),
+            method => panic!("unexpected RPC method {}", method),
+        };
+        Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }))
+    }
+
+    #[test]
+    fn refresh_passes_run_on_the_refresh_runtime() {
+        let runtime = refresh_runtime().unwrap();
+        let report = runtime.block_on(async {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            tokio::spawn(async move { axum::serve(listener, Router::new().route("/", post(rpc))).await });
+
+            let program_id = Pubkey::new_unique();
+            let indexer = Indexer::new(&url, program_id, Database::open_in_memory().unwrap());
+            indexer.catch_up().await
+        });
+        assert_eq!(report.unwrap(), Default::default());
+    }
+}
@@ -0,0 +1,121 @@
+use clap::{Parser, ValueEnum};
+use solana_blog_client::{subscribe::derive_ws_url, BlogClient, LocalStore};
+use solana_blog_indexer::{Database, Indexer, PassReport};
+use solana_sdk::pubkey::Pubkey;
+use std::{path::PathBuf, process::ExitCode, str::FromStr, time::Duration};
+
+/// Program id of the devnet deployment
+const DEFAULT_PROGRAM_ID: &str = "BmqUqrFGJA7C9nzv787SrC59PUs2kSUvgdhDru6NQFmQ";
+const DEFAULT_RPC_URL: &str = "https://api.devnet.solana.com";
+
+#[derive(Parser)]
+#[command(name = "blog-indexer", version, about = "Index Solana blogs and posts into SQLite")]
+struct Cli {
+    #[arg(long, short = 'u', env = "BLOG_RPC_URL", default_value = DEFAULT_RPC_URL)]
+    url: String,
+
+    /// Websocket URL for `--mode subscribe`; derived from `--url` when omitted
+    #[arg(long, env = "BLOG_WS_URL")]
+    ws_url: Option<String>,
+
+    #[arg(long, env = "BLOG_PROGRAM_ID", default_value = DEFAULT_PROGRAM_ID)]
+    program_id: String,
+
+    /// SQLite database file, created if missing
+    #[arg(long, env = "BLOG_INDEX_DB", default_value = "blog-index.sqlite")]
+    db: PathBuf,
+
+    /// Index from this slot when the database has no checkpoint yet
+    #[arg(long)]
+    from_slot: Option<u64>,
+
+    #[arg(long, value_enum, default_value_t = Mode::Poll)]
+    mode: Mode,
+
+    /// Seconds between catch-up passes in poll mode
+    #[arg(long, default_value_t = 10)]
+    interval: u64,
+
+    /// Catch up once and exit
+    #[arg(long)]
+    once: bool,
+
+    /// Also fetch post bodies and tags from the content store
+    #[arg(long)]
+    fetch_content: bool,
+
+    /// Read content from this directory instead of Arweave
+    #[arg(long)]
+    local_store: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Mode {
+    /// Replay program transactions every `--interval` seconds
+    Poll,
+    /// Follow account updates over a websocket
+    Subscribe,
+}
+
+fn print_pass(report: &PassReport) {
+    if report.transactions + report.bodies_fetched + report.body_errors == 0 {
+        return;
+    }
+    println!(
+        "slot {}: {} transactions, {} bodies fetched, {} body errors",
+        report.slot.map(|slot| slot.to_string()).unwrap_or_else(|| "-".to_string()),
+        report.transactions,
+        report.bodies_fetched,
+        report.body_errors
+    );
+}
+
+async fn run(cli: Cli) -> Result<(), String> {
+    let program_id = Pubkey::from_str(&cli.program_id).map_err(|e| format!("Invalid program ID: {}", e))?;
+    let db = Database::open(&cli.db).map_err(|e| e.to_string())?;
+    if let Some(slot) = cli.from_slot {
+        if db.checkpoint().map_err(|e| e.to_string())?.is_none() {
+            db.set_checkpoint(slot, None).map_err(|e| e.to_string())?;
+        }
+    }
+
+    let mut indexer = Indexer::new(&cli.url, program_id, db);
+    if cli.fetch_content {
+        let mut client = BlogClient::new(&cli.url, &cli.program_id).map_err(|e| e.to_string())?;
+        if let Some(dir) = &cli.local_store {
+            client = client.with_content_store(LocalStore::new(dir));
+        }
+        indexer = indexer.with_content(client);
+    }
+
+    if cli.once {
+        let report = indexer.catch_up().await.map_err(|e| e.to_string())?;
+        print_pass(&report);
+        return Ok(());
+    }
+
+    match cli.mode {
+        Mode::Poll => indexer
+            .run_polling(Duration::from_secs(cli.interval), print_pass)
+            .await
+            .map_err(|e| e.to_string()),
+        Mode::Subscribe => {
+            let ws_url = cli.ws_url.clone().unwrap_or_else(|| derive_ws_url(&cli.url));
+            indexer
+                .run_subscription(&ws_url, print_pass)
+                .await
+                .map_err(|e| e.to_string())
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,805 @@
+use crate::Result;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+use solana_blog_client::{
+    search::{SearchQuery, SearchResult},
+    sync::parse_frontmatter,
+};
+use solana_blog_program::state::{Blog, BlogPost};
+use solana_sdk::pubkey::Pubkey;
+use std::{path::Path, str::FromStr};
+
+/// Schema version stored in `PRAGMA user_version`
+const SCHEMA_VERSION: i64 = 2;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS blogs (
+    pubkey TEXT PRIMARY KEY,
+    authority TEXT NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT NOT NULL,
+    post_count INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    slot INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS posts (
+    pubkey TEXT PRIMARY KEY,
+    blog TEXT NOT NULL,
+    author TEXT NOT NULL,
+    title TEXT NOT NULL,
+    content TEXT NOT NULL,
+    arweave_hash TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    deleted_at INTEGER,
+    slot INTEGER NOT NULL,
+    -- Markdown body fetched from the content store, and the id it came from
+    body TEXT,
+    body_id TEXT
+);
+CREATE INDEX IF NOT EXISTS posts_by_blog ON posts (blog, created_at DESC);
+CREATE INDEX IF NOT EXISTS posts_by_author ON posts (author, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS post_tags (
+    post TEXT NOT NULL REFERENCES posts (pubkey) ON DELETE CASCADE,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (post, tag)
+);
+CREATE INDEX IF NOT EXISTS post_tags_by_tag ON post_tags (tag);
+
+-- Every distinct (title, content, arweave_hash) a post has had, oldest first
+CREATE TABLE IF NOT EXISTS revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    post TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    signature TEXT,
+    title TEXT NOT NULL,
+    content TEXT NOT NULL,
+    arweave_hash TEXT NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS revisions_by_post ON revisions (post, id);
+
+-- Title and body text of live posts; the body is the fetched markdown
+-- without frontmatter, or the on-chain summary until it is fetched
+CREATE VIRTUAL TABLE IF NOT EXISTS posts_fts USING fts5 (
+    pubkey UNINDEXED,
+    title,
+    body,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TABLE IF NOT EXISTS checkpoint (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    slot INTEGER NOT NULL,
+    signature TEXT
+);
+";
+
+/// Where indexing resumes: transactions after `signature`, or from `slot`
+/// on when there is no signature yet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub slot: u64,
+    pub signature: Option<String>,
+}
+
+/// A blog as served by the API
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BlogRow {
+    pub pubkey: String,
+    pub authority: String,
+    pub title: String,
+    pub description: String,
+    pub post_count: u64,
+    pub created_at: i64,
+    /// Slot of the last change the index applied
+    pub slot: u64,
+}
+
+/// A live post as served by the API
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PostRow {
+    pub pubkey: String,
+    pub blog: String,
+    pub author: String,
+    pub title: String,
+    /// On-chain summary; the full body is served separately
+    pub content: String,
+    pub arweave_hash: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub slot: u64,
+    pub tags: Vec<String>,
+}
+
+/// One page of a blog's posts, newest first
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PostPage {
+    pub posts: Vec<PostRow>,
+    /// Pass back as `cursor` for the next page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Search results per query when the query does not say
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+const MAX_SEARCH_LIMIT: usize = 100;
+/// Markers FTS wraps matches in, replaced with `<mark>` after escaping
+const MATCH_START: &str = "\u{1}";
+const MATCH_END: &str = "\u{2}";
+
+const BLOG_COLUMNS: &str = "pubkey, authority, title, description, post_count, created_at, slot";
+const POST_COLUMNS: &str = "pubkey, blog, author, title, content, arweave_hash, created_at, updated_at, slot";
+
+fn blog_row(row: &Row) -> rusqlite::Result<BlogRow> {
+    Ok(BlogRow {
+        pubkey: row.get(0)?,
+        authority: row.get(1)?,
+        title: row.get(2)?,
+        description: row.get(3)?,
+        post_count: row.get::<_, i64>(4)? as u64,
+        created_at: row.get(5)?,
+        slot: row.get::<_, i64>(6)? as u64,
+    })
+}
+
+fn post_row(row: &Row) -> rusqlite::Result<PostRow> {
+    Ok(PostRow {
+        pubkey: row.get(0)?,
+        blog: row.get(1)?,
+        author: row.get(2)?,
+        title: row.get(3)?,
+        content: row.get(4)?,
+        arweave_hash: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+        slot: row.get::<_, i64>(8)? as u64,
+        tags: Vec::new(),
+    })
+}
+
+/// Page cursors are the last post's `created_at` and pubkey
+fn parse_cursor(cursor: &str) -> Option<(i64, String)> {
+    let (created_at, pubkey) = cursor.split_once('.')?;
+    Some((created_at.parse().ok()?, pubkey.to_string()))
+}
+
+/// FTS5 query matching every word of `text` as a prefix, so partial words
+/// and plurals match. Operators in `text` are not interpreted.
+fn fts_query(text: &str) -> Option<String> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+/// Escape FTS output for HTML and turn its match markers into `<mark>`
+fn highlight_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace(MATCH_START, "<mark>")
+        .replace(MATCH_END, "</mark>")
+}
+
+/// SQLite store of blogs, posts, tags and revisions
+pub struct Database {
+    conn: Connection,
+}
+
+impl Database {
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self> {
+        // WAL lets readers such as the API server query while indexing runs
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "foreign_keys", "ON")?;
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        conn.execute_batch(SCHEMA)?;
+        let db = Self { conn };
+        // Version 1 databases predate the search index
+        if version < 2 {
+            db.rebuild_search_index()?;
+        }
+        db.conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        Ok(db)
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Run `f` in a transaction, rolling back if it fails
+    pub fn transaction<T>(&self, f: impl FnOnce(&Self) -> Result<T>) -> Result<T> {
+        self.conn.execute_batch("BEGIN IMMEDIATE")?;
+        match f(self) {
+            Ok(value) => {
+                self.conn.execute_batch("COMMIT")?;
+                Ok(value)
+            }
+            Err(e) => {
+                let _ = self.conn.execute_batch("ROLLBACK");
+                Err(e)
+            }
+        }
+    }
+
+    pub fn checkpoint(&self) -> Result<Option<Checkpoint>> {
+        Ok(self
+            .conn
+            .query_row("SELECT slot, signature FROM checkpoint WHERE id = 1", [], |row| {
+                Ok(Checkpoint {
+                    slot: row.get::<_, i64>(0)? as u64,
+                    signature: row.get(1)?,
+                })
+            })
+            .optional()?)
+    }
+
+    pub fn set_checkpoint(&self, slot: u64, signature: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO checkpoint (id, slot, signature) VALUES (1, ?1, ?2)
+             ON CONFLICT (id) DO UPDATE SET slot = excluded.slot, signature = excluded.signature",
+            params![slot as i64, signature],
+        )?;
+        Ok(())
+    }
+
+    pub fn blog(&self, pubkey: &Pubkey) -> Result<Option<Blog>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT authority, title, description, post_count, created_at FROM blogs WHERE pubkey = ?1",
+                [pubkey.to_string()],
+                |row| {
+                    Ok(Blog {
+                        authority: parse_pubkey(&row.get::<_, String>(0)?),
+                        title: row.get(1)?,
+                        description: row.get(2)?,
+                        post_count: row.get::<_, i64>(3)? as u64,
+                        created_at: row.get(4)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    /// Insert or replace a blog. `post_count` is taken as given; use
+    /// `recount_posts` when it is not known from the account.
+    pub fn put_blog(&self, pubkey: &Pubkey, blog: &Blog, slot: u64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO blogs (pubkey, authority, title, description, post_count, created_at, slot)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT (pubkey) DO UPDATE SET
+                authority = excluded.authority, title = excluded.title,
+                description = excluded.description, post_count = excluded.post_count,
+                created_at = excluded.created_at, slot = excluded.slot",
+            params![
+                pubkey.to_string(),
+                blog.authority.to_string(),
+                blog.title,
+                blog.description,
+                blog.post_count as i64,
+                blog.created_at,
+                slot as i64
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Set a blog's post count to its number of live posts, which is what
+    /// the program's counter tracks
+    pub fn recount_posts(&self, blog: &Pubkey) -> Result<()> {
+        self.conn.execute(
+            "UPDATE blogs SET post_count =
+                (SELECT COUNT(*) FROM posts WHERE posts.blog = blogs.pubkey AND deleted_at IS NULL)
+             WHERE pubkey = ?1",
+            [blog.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// A post, including deleted ones
+    pub fn post(&self, pubkey: &Pubkey) -> Result<Option<BlogPost>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT author, blog, title, content, arweave_hash, created_at, updated_at
+                 FROM posts WHERE pubkey = ?1",
+                [pubkey.to_string()],
+                |row| {
+                    Ok(BlogPost {
+                        author: parse_pubkey(&row.get::<_, String>(0)?),
+                        blog: parse_pubkey(&row.get::<_, String>(1)?),
+                        title: row.get(2)?,
+                        content: row.get(3)?,
+                        arweave_hash: row.get(4)?,
+                        created_at: row.get(5)?,
+                        updated_at: row.get(6)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    /// Insert or replace a live post, recording a revision when its title,
+    /// content or content id changed. Re-applying the same state is a no-op,
+    /// so replaying already indexed transactions is safe.
+    pub fn put_post(&self, pubkey: &Pubkey, post: &BlogPost, slot: u64, signature: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO posts (pubkey, blog, author, title, content, arweave_hash, created_at, updated_at, deleted_at, slot)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, NULL, ?9)
+             ON CONFLICT (pubkey) DO UPDATE SET
+                blog = excluded.blog, author = excluded.author, title = excluded.title,
+                content = excluded.content, arweave_hash = excluded.arweave_hash,
+                created_at = excluded.created_at, updated_at = excluded.updated_at,
+                deleted_at = NULL, slot = excluded.slot",
+            params![
+                pubkey.to_string(),
+                post.blog.to_string(),
+                post.author.to_string(),
+                post.title,
+                post.content,
+                post.arweave_hash,
+                post.created_at,
+                post.updated_at,
+                slot as i64
+            ],
+        )?;
+
+        let latest: Option<(String, String, String)> = self
+            .conn
+            .query_row(
+                "SELECT title, content, arweave_hash FROM revisions WHERE post = ?1 ORDER BY id DESC LIMIT 1",
+                [pubkey.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+        let current = (post.title.clone(), post.content.clone(), post.arweave_hash.clone());
+        if latest.as_ref() != Some(&current) {
+            self.conn.execute(
+                "INSERT INTO revisions (post, slot, signature, title, content, arweave_hash, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    pubkey.to_string(),
+                    slot as i64,
+                    signature,
+                    post.title,
+                    post.content,
+                    post.arweave_hash,
+                    post.updated_at
+                ],
+            )?;
+        }
+        self.index_post(&pubkey.to_string())
+    }
+
+    /// Mark a post deleted, keeping its row and revisions. Returns the blog
+    /// it belonged to, or `None` if the post is unknown.
+    pub fn delete_post(&self, pubkey: &Pubkey, deleted_at: i64, slot: u64) -> Result<Option<Pubkey>> {
+        let blog: Option<String> = self
+            .conn
+            .query_row(
+                "UPDATE posts SET deleted_at = COALESCE(deleted_at, ?2), slot = ?3
+                 WHERE pubkey = ?1 RETURNING blog",
+                params![pubkey.to_string(), deleted_at, slot as i64],
+                |row| row.get(0),
+            )
+            .optional()?;
+        self.index_post(&pubkey.to_string())?;
+        Ok(blog.map(|blog| parse_pubkey(&blog)))
+    }
+
+    /// Live posts whose body has not been fetched for their current content id
+    pub fn posts_missing_body(&self, limit: usize) -> Result<Vec<(Pubkey, String)>> {
+        let mut statement = self.conn.prepare(
+            "SELECT pubkey, arweave_hash FROM posts
+             WHERE deleted_at IS NULL AND (body_id IS NULL OR body_id != arweave_hash)
+             ORDER BY slot LIMIT ?1",
+        )?;
+        let rows = statement
+            .query_map([limit as i64], |row| {
+                Ok((parse_pubkey(&row.get::<_, String>(0)?), row.get(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Store the body fetched for `body_id` and replace the post's tags
+    pub fn set_post_body(&self, pubkey: &Pubkey, body_id: &str, body: Option<&str>, tags: &[String]) -> Result<()> {
+        self.conn.execute(
+            "UPDATE posts SET body = ?2, body_id = ?3 WHERE pubkey = ?1",
+            params![pubkey.to_string(), body, body_id],
+        )?;
+        self.conn
+            .execute("DELETE FROM post_tags WHERE post = ?1", [pubkey.to_string()])?;
+        for tag in tags {
+            self.conn.execute(
+                "INSERT OR IGNORE INTO post_tags (post, tag) VALUES (?1, ?2)",
+                params![pubkey.to_string(), tag],
+            )?;
+        }
+        self.index_post(&pubkey.to_string())
+    }
+
+    /// Bring a post's search entry in line with its row; deleted posts are
+    /// removed from the index
+    fn index_post(&self, pubkey: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM posts_fts WHERE pubkey = ?1", [pubkey])?;
+        let post: Option<(String, String, Option<String>)> = self
+            .conn
+            .query_row(
+                "SELECT title, content, body FROM posts WHERE pubkey = ?1 AND deleted_at IS NULL",
+                [pubkey],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+        let Some((title, summary, body)) = post else { return Ok(()) };
+
+        let text = match body {
+            Some(body) => {
+                let body = body.replace("\r\n", "\n");
+                match parse_frontmatter(&body) {
+                    Some((_, rest)) => rest.to_string(),
+                    None => body,
+                }
+            }
+            None => summary,
+        };
+        self.conn.execute(
+            "INSERT INTO posts_fts (pubkey, title, body) VALUES (?1, ?2, ?3)",
+            params![pubkey, title, text],
+        )?;
+        Ok(())
+    }
+
+    /// Re-index every post, for databases created before search existed
+    pub fn rebuild_search_index(&self) -> Result<()> {
+        let pubkeys = {
+            let mut statement = self.conn.prepare("SELECT pubkey FROM posts")?;
+            let pubkeys = statement
+                .query_map([], |row| row.get::<_, String>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            pubkeys
+        };
+        self.transaction(|db| {
+            db.conn.execute("DELETE FROM posts_fts", [])?;
+            for pubkey in &pubkeys {
+                db.index_post(pubkey)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Live posts matching `query`, best first. Titles weigh more than
+    /// bodies. A query with no words matches nothing.
+    pub fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>> {
+        let Some(fts_query) = fts_query(&query.text) else { return Ok(Vec::new()) };
+        let limit = query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, MAX_SEARCH_LIMIT);
+        let mut statement = self.conn.prepare(
+            "SELECT posts.pubkey, posts.blog, posts.title, posts.created_at,
+                    highlight(posts_fts, 1, ?7, ?8),
+                    snippet(posts_fts, 2, ?7, ?8, '…', 24),
+                    bm25(posts_fts, 0.0, 10.0, 1.0) AS rank
+             FROM posts_fts JOIN posts ON posts.pubkey = posts_fts.pubkey
+             WHERE posts_fts MATCH ?1 AND posts.deleted_at IS NULL
+               AND (?2 IS NULL OR posts.blog = ?2)
+               AND (?3 IS NULL OR EXISTS (SELECT 1 FROM post_tags WHERE post = posts.pubkey AND tag = ?3))
+               AND (?4 IS NULL OR posts.created_at >= ?4)
+               AND (?5 IS NULL OR posts.created_at < ?5)
+             ORDER BY rank LIMIT ?6",
+        )?;
+        let mut results = statement
+            .query_map(
+                params![
+                    fts_query,
+                    query.blog,
+                    query.tag,
+                    query.from,
+                    query.to,
+                    limit as i64,
+                    MATCH_START,
+                    MATCH_END
+                ],
+                |row| {
+                    Ok(SearchResult {
+                        post: row.get(0)?,
+                        blog: row.get(1)?,
+                        title: row.get(2)?,
+                        created_at: row.get(3)?,
+                        title_html: highlight_html(&row.get::<_, String>(4)?),
+                        snippet_html: highlight_html(&row.get::<_, String>(5)?),
+                        // bm25 is lower for better matches
+                        score: -row.get::<_, f64>(6)?,
+                        tags: Vec::new(),
+                    })
+                },
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        for result in &mut results {
+            result.tags = self.tags(&result.post)?;
+        }
+        Ok(results)
+    }
+
+    /// All blogs, newest first
+    pub fn blogs(&self) -> Result<Vec<BlogRow>> {
+        let mut statement = self
+            .conn
+            .prepare(&format!("SELECT {} FROM blogs ORDER BY created_at DESC, pubkey", BLOG_COLUMNS))?;
+        let rows = statement
+            .query_map([], blog_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    pub fn blog_row(&self, pubkey: &str) -> Result<Option<BlogRow>> {
+        Ok(self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM blogs WHERE pubkey = ?1", BLOG_COLUMNS),
+                [pubkey],
+                blog_row,
+            )
+            .optional()?)
+    }
+
+    /// Up to `limit` live posts of `blog` after `cursor`, optionally only
+    /// those tagged `tag`. An unparseable cursor starts from the beginning.
+    pub fn posts_page(&self, blog: &str, tag: Option<&str>, cursor: Option<&str>, limit: usize) -> Result<PostPage> {
+        let (before_time, before_key) = match cursor.and_then(parse_cursor) {
+            Some((created_at, pubkey)) => (Some(created_at), Some(pubkey)),
+            None => (None, None),
+        };
+        let mut statement = self.conn.prepare(&format!(
+            "SELECT {} FROM posts
+             WHERE blog = ?1 AND deleted_at IS NULL
+               AND (?2 IS NULL OR EXISTS (SELECT 1 FROM post_tags WHERE post = posts.pubkey AND tag = ?2))
+               AND (?3 IS NULL OR (created_at, pubkey) < (?3, ?4))
+             ORDER BY created_at DESC, pubkey DESC LIMIT ?5",
+            POST_COLUMNS
+        ))?;
+        // One extra row tells whether there is a next page
+        let mut posts = statement
+            .query_map(
+                params![blog, tag, before_time, before_key, limit as i64 + 1],
+                post_row,
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let next_cursor = if posts.len() > limit {
+            posts.truncate(limit);
+            posts.last().map(|post| format!("{}.{}", post.created_at, post.pubkey))
+        } else {
+            None
+        };
+        for post in &mut posts {
+            post.tags = self.tags(&post.pubkey)?;
+        }
+        Ok(PostPage { posts, next_cursor })
+    }
+
+    /// A live post
+    pub fn post_row(&self, pubkey: &str) -> Result<Option<PostRow>> {
+        let post = self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM posts WHERE pubkey = ?1 AND deleted_at IS NULL", POST_COLUMNS),
+                [pubkey],
+                post_row,
+            )
+            .optional()?;
+        match post {
+            Some(mut post) => {
+                post.tags = self.tags(&post.pubkey)?;
+                Ok(Some(post))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// A live post's fetched body and the content id it came from, or
+    /// `None` if the post is unknown or its body has not been fetched
+    pub fn post_body(&self, pubkey: &str) -> Result<Option<(String, String)>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT body, body_id FROM posts
+                 WHERE pubkey = ?1 AND deleted_at IS NULL AND body IS NOT NULL AND body_id = arweave_hash",
+                [pubkey],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?)
+    }
+
+    pub fn tags(&self, post: &str) -> Result<Vec<String>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT tag FROM post_tags WHERE post = ?1 ORDER BY tag")?;
+        let tags = statement
+            .query_map([post], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(tags)
+    }
+}
+
+/// Pubkeys are only ever written by this module, from valid keys
+fn parse_pubkey(value: &str) -> Pubkey {
+    Pubkey::from_str(value).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blog(title: &str) -> Blog {
+        Blog {
+            authority: Pubkey::new_unique(),
+            title: title.to_string(),
+            description: String::new(),
+            post_count: 0,
+            created_at: 100,
+        }
+    }
+
+    fn post(blog: Pubkey, title: &str, created_at: i64) -> BlogPost {
+        BlogPost {
+            author: Pubkey::new_unique(),
+            blog,
+            title: title.to_string(),
+            content: format!("Summary of {}", title),
+            arweave_hash: "a".repeat(43),
+            created_at,
+            updated_at: created_at,
+        }
+    }
+
+    fn revisions(db: &Database, pubkey: &Pubkey) -> Vec<(String, Option<String>)> {
+        let mut statement = db
+            .connection()
+            .prepare("SELECT title, signature FROM revisions WHERE post = ?1 ORDER BY id")
+            .unwrap();
+        let rows = statement
+            .query_map([pubkey.to_string()], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .unwrap();
+        rows
+    }
+
+    #[test]
+    fn put_post_records_a_revision_per_change() {
+        let db = Database::open_in_memory().unwrap();
+        let pubkey = Pubkey::new_unique();
+        let mut first = post(Pubkey::new_unique(), "First", 10);
+
+        db.put_post(&pubkey, &first, 1, Some("sig-1")).unwrap();
+        // Replaying the same state adds nothing
+        db.put_post(&pubkey, &first, 1, Some("sig-1")).unwrap();
+        first.title = "Second".to_string();
+        first.updated_at = 20;
+        db.put_post(&pubkey, &first, 2, Some("sig-2")).unwrap();
+
+        assert_eq!(
+            revisions(&db, &pubkey),
+            [
+                ("First".to_string(), Some("sig-1".to_string())),
+                ("Second".to_string(), Some("sig-2".to_string())),
+            ]
+        );
+        let stored = db.post(&pubkey).unwrap().unwrap();
+        assert_eq!(stored.title, "Second");
+        assert_eq!(stored.updated_at, 20);
+        assert_eq!(db.post_row(&pubkey.to_string()).unwrap().unwrap().slot, 2);
+    }
+
+    #[test]
+    fn deleted_posts_leave_counts_pages_and_search() {
+        let db = Database::open_in_memory().unwrap();
+        let blog_pubkey = Pubkey::new_unique();
+        db.put_blog(&blog_pubkey, &Blog { post_count: 99, ..blog("Blog") }, 1).unwrap();
+        let kept = Pubkey::new_unique();
+        let deleted = Pubkey::new_unique();
+        db.put_post(&kept, &post(blog_pubkey, "Kept", 10), 2, None).unwrap();
+        db.put_post(&deleted, &post(blog_pubkey, "Deleted", 11), 2, None).unwrap();
+
+        db.recount_posts(&blog_pubkey).unwrap();
+        assert_eq!(db.blog(&blog_pubkey).unwrap().unwrap().post_count, 2);
+
+        assert_eq!(db.delete_post(&deleted, 50, 3).unwrap(), Some(blog_pubkey));
+        assert_eq!(db.delete_post(&Pubkey::new_unique(), 50, 3).unwrap(), None);
+        db.recount_posts(&blog_pubkey).unwrap();
+        assert_eq!(db.blog(&blog_pubkey).unwrap().unwrap().post_count, 1);
+
+        // The row and its history stay, but it is no longer served
+        assert!(db.post(&deleted).unwrap().is_some());
+        assert_eq!(revisions(&db, &deleted).len(), 1);
+        assert_eq!(db.post_row(&deleted.to_string()).unwrap(), None);
+        let page = db.posts_page(&blog_pubkey.to_string(), None, None, 10).unwrap();
+        let titles: Vec<&str> = page.posts.iter().map(|post| post.title.as_str()).collect();
+        assert_eq!(titles, ["Kept"]);
+        assert!(db.search(&SearchQuery::new("deleted")).unwrap().is_empty());
+        assert_eq!(db.search(&SearchQuery::new("kept")).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn posts_page_walks_every_post_once() {
+        let db = Database::open_in_memory().unwrap();
+        let blog_pubkey = Pubkey::new_unique();
+        // Two posts share a timestamp, so the cursor has to break the tie
+        for (title, created_at) in [("a", 1), ("b", 2), ("c", 2), ("d", 3), ("e", 4)] {
+            db.put_post(&Pubkey::new_unique(), &post(blog_pubkey, title, created_at), 1, None)
+                .unwrap();
+        }
+
+        let mut titles = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = db
+                .posts_page(&blog_pubkey.to_string(), None, cursor.as_deref(), 2)
+                .unwrap();
+            assert!(page.posts.len() <= 2);
+            titles.extend(page.posts.into_iter().map(|post| (post.created_at, post.title)));
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        let times: Vec<i64> = titles.iter().map(|(created_at, _)| *created_at).collect();
+        assert_eq!(times, [4, 3, 2, 2, 1]);
+        let mut seen: Vec<&str> = titles.iter().map(|(_, title)| title.as_str()).collect();
+        seen.sort();
+        assert_eq!(seen, ["a", "b", "c", "d", "e"]);
+    }
+
+    #[test]
+    fn posts_page_filters_by_tag() {
+        let db = Database::open_in_memory().unwrap();
+        let blog_pubkey = Pubkey::new_unique();
+        let tagged = Pubkey::new_unique();
+        db.put_post(&tagged, &post(blog_pubkey, "Tagged", 1), 1, None).unwrap();
+        db.put_post(&Pubkey::new_unique(), &post(blog_pubkey, "Plain", 2), 1, None)
+            .unwrap();
+        db.set_post_body(&tagged, &"a".repeat(43), Some("Body"), &["rust".to_string()])
+            .unwrap();
+
+        let page = db.posts_page(&blog_pubkey.to_string(), Some("rust"), None, 10).unwrap();
+        let titles: Vec<&str> = page.posts.iter().map(|post| post.title.as_str()).collect();
+        assert_eq!(titles, ["Tagged"]);
+        assert_eq!(page.posts[0].tags, ["rust"]);
+    }
+
+    #[test]
+    fn search_text_is_never_read_as_fts_syntax() {
+        assert_eq!(fts_query("rust OR \"go\" -c*"), Some("\"rust\"* \"OR\"* \"go\"* \"c\"*".to_string()));
+        assert_eq!(fts_query(" \"*\" - "), None);
+
+        let db = Database::open_in_memory().unwrap();
+        let pubkey = Pubkey::new_unique();
+        db.put_post(&pubkey, &post(Pubkey::new_unique(), "Rust <tips>", 1), 1, None)
+            .unwrap();
+        for text in ["rust AND", "\"unbalanced", "NEAR(rust", "tips)"] {
+            db.search(&SearchQuery::new(text)).unwrap();
+        }
+        let results = db.search(&SearchQuery::new("tip")).unwrap();
+        assert_eq!(results[0].title_html, "Rust &lt;<mark>tips</mark>&gt;");
+    }
+
+    #[test]
+    fn cursors_that_do_not_parse_start_over() {
+        assert_eq!(parse_cursor("12.abc"), Some((12, "abc".to_string())));
+        assert_eq!(parse_cursor("abc"), None);
+        assert_eq!(parse_cursor("x.abc"), None);
+    }
+}
@@ -0,0 +1,594 @@
+use crate::{db::Database, IndexerError, ProgramAccount, Result};
+use borsh::BorshDeserialize;
+use futures_util::StreamExt;
+use solana_account_decoder::UiAccountEncoding;
+use solana_blog_client::{sync::parse_frontmatter, BlogClient};
+use solana_blog_program::{
+    instruction::BlogInstruction,
+    state::{Blog, BlogPost},
+};
+use solana_client::{
+    nonblocking::{pubsub_client::PubsubClient, rpc_client::RpcClient},
+    rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionConfig},
+    rpc_response::RpcConfirmedTransactionStatusWithSignature,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::UiTransactionEncoding;
+use std::{
+    collections::BTreeSet,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Signatures requested per `getSignaturesForAddress` page (the RPC maximum)
+const SIGNATURE_PAGE_SIZE: usize = 1000;
+/// Bodies fetched per pass, so a large backlog does not stall indexing
+const BODY_BATCH_SIZE: usize = 50;
+
+/// What one catch-up pass did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PassReport {
+    /// Program transactions applied, including failed ones that were skipped
+    pub transactions: usize,
+    pub bodies_fetched: usize,
+    pub body_errors: usize,
+    pub slot: Option<u64>,
+}
+
+/// Keeps a `Database` in step with the blog program, by replaying its
+/// transactions from the last checkpoint and optionally following account
+/// updates over a websocket
+pub struct Indexer {
+    rpc_client: RpcClient,
+    program_id: Pubkey,
+    db: Database,
+    content: Option<BlogClient>,
+}
+
+fn rpc_error(context: &str, e: impl std::fmt::Display) -> IndexerError {
+    IndexerError {
+        message: format!("{}: {}", context, e),
+    }
+}
+
+impl Indexer {
+    pub fn new(rpc_url: &str, program_id: Pubkey, db: Database) -> Self {
+        Self {
+            rpc_client: RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed()),
+            program_id,
+            db,
+            content: None,
+        }
+    }
+
+    /// Also fetch each post's body from `client`'s content store, storing
+    /// the markdown and its frontmatter tags
+    pub fn with_content(mut self, client: BlogClient) -> Self {
+        self.content = Some(client);
+        self
+    }
+
+    pub fn database(&self) -> &Database {
+        &self.db
+    }
+
+    /// Apply every program transaction since the checkpoint, oldest first,
+    /// advancing the checkpoint after each one
+    pub async fn catch_up(&self) -> Result<PassReport> {
+        let checkpoint = self.db.checkpoint()?;
+        let until = match checkpoint.as_ref().and_then(|checkpoint| checkpoint.signature.as_deref()) {
+            Some(signature) => Some(
+                Signature::from_str(signature).map_err(|e| rpc_error("Invalid checkpoint signature", e))?,
+            ),
+            None => None,
+        };
+        let from_slot = checkpoint.as_ref().map(|checkpoint| checkpoint.slot).unwrap_or(0);
+
+        // Pages come newest first; collect back to the checkpoint
+        let mut pending: Vec<RpcConfirmedTransactionStatusWithSignature> = Vec::new();
+        let mut before = None;
+        loop {
+            let page = self
+                .rpc_client
+                .get_signatures_for_address_with_config(
+                    &self.program_id,
+                    GetConfirmedSignaturesForAddress2Config {
+                        before,
+                        until,
+                        limit: Some(SIGNATURE_PAGE_SIZE),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                    },
+                )
+                .await
+                .map_err(|e| rpc_error("Failed to get program signatures", e))?;
+
+            let full_page = page.len() == SIGNATURE_PAGE_SIZE;
+            let mut reached_checkpoint = false;
+            for status in page {
+                if until.is_none() && status.slot < from_slot {
+                    reached_checkpoint = true;
+                    break;
+                }
+                pending.push(status);
+            }
+            match pending.last() {
+                Some(last) if full_page && !reached_checkpoint => {
+                    before = Some(
+                        Signature::from_str(&last.signature)
+                            .map_err(|e| rpc_error("Invalid signature from RPC", e))?,
+                    );
+                }
+                _ => break,
+            }
+        }
+
+        let mut report = PassReport::default();
+        for status in pending.iter().rev() {
+            if status.err.is_none() {
+                self.apply_transaction(status).await?;
+            }
+            self.db.set_checkpoint(status.slot, Some(&status.signature))?;
+            report.transactions += 1;
+            report.slot = Some(status.slot);
+        }
+
+        let (fetched, errors) = self.fetch_bodies().await?;
+        report.bodies_fetched = fetched;
+        report.body_errors = errors;
+        Ok(report)
+    }
+
+    /// Decode the program's instructions in one transaction and apply them
+    /// to the database in a single database transaction
+    async fn apply_transaction(&self, status: &RpcConfirmedTransactionStatusWithSignature) -> Result<()> {
+        let signature =
+            Signature::from_str(&status.signature).map_err(|e| rpc_error("Invalid signature from RPC", e))?;
+        let transaction = self
+            .rpc_client
+            .get_transaction_with_config(
+                &signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await
+            .map_err(|e| rpc_error(&format!("Failed to get transaction {}", status.signature), e))?;
+        let Some(decoded) = transaction.transaction.transaction.decode() else {
+            return Err(rpc_error("Failed to decode transaction", &status.signature));
+        };
+
+        let timestamp = status.block_time.or(transaction.block_time).unwrap_or_default();
+        let keys = decoded.message.static_account_keys();
+        let mut instructions = Vec::new();
+        for instruction in decoded.message.instructions() {
+            if keys.get(instruction.program_id_index as usize) != Some(&self.program_id) {
+                continue;
+            }
+            let Ok(decoded) = BlogInstruction::try_from_slice(&instruction.data) else { continue };
+            let accounts: Vec<Pubkey> = instruction
+                .accounts
+                .iter()
+                .filter_map(|index| keys.get(*index as usize).copied())
+                .collect();
+            instructions.push((decoded, accounts));
+        }
+
+        // Posts and blogs touched before the checkpoint window are loaded
+        // from the chain the first time they are seen
+        let mut unknown = BTreeSet::new();
+        self.db.transaction(|db| {
+            for (instruction, accounts) in instructions {
+                let account = |index: usize| accounts.get(index).copied().unwrap_or_default();
+                apply_instruction(
+                    db,
+                    instruction,
+                    &account,
+                    status.slot,
+                    &status.signature,
+                    timestamp,
+                    &mut unknown,
+                )?;
+            }
+            Ok(())
+        })?;
+
+        for pubkey in unknown {
+            self.refresh_account(&pubkey, status.slot).await?;
+        }
+        Ok(())
+    }
+
+    /// Load one account from the chain into the database
+    async fn refresh_account(&self, pubkey: &Pubkey, slot: u64) -> Result<()> {
+        let account = self
+            .rpc_client
+            .get_account_with_commitment(pubkey, CommitmentConfig::confirmed())
+            .await
+            .map_err(|e| rpc_error(&format!("Failed to get account {}", pubkey), e))?
+            .value;
+        match account.and_then(|account| ProgramAccount::decode(&account.data)) {
+            Some(account) => self.apply_account(pubkey, account, slot),
+            None => Ok(()),
+        }
+    }
+
+    fn apply_account(&self, pubkey: &Pubkey, account: ProgramAccount, slot: u64) -> Result<()> {
+        match account {
+            ProgramAccount::Blog(blog) => self.db.put_blog(pubkey, &blog, slot),
+            ProgramAccount::Post(post) => self.db.transaction(|db| {
+                db.put_post(pubkey, &post, slot, None)?;
+                db.recount_posts(&post.blog)
+            }),
+        }
+    }
+
+    /// Fetch missing bodies and their tags; returns (fetched, failed)
+    async fn fetch_bodies(&self) -> Result<(usize, usize)> {
+        let Some(client) = &self.content else { return Ok((0, 0)) };
+        let (mut fetched, mut failed) = (0, 0);
+        for (pubkey, arweave_hash) in self.db.posts_missing_body(BODY_BATCH_SIZE)? {
+            match client.get_post_content(&arweave_hash).await {
+                Ok((body, _)) => {
+                    let body = body.map(|body| String::from_utf8_lossy(&body).into_owned());
+                    let tags = body
+                        .as_deref()
+                        .map(|body| body.replace("\r\n", "\n"))
+                        .and_then(|body| parse_frontmatter(&body).map(|(frontmatter, _)| frontmatter.tags))
+                        .unwrap_or_default();
+                    self.db
+                        .set_post_body(&pubkey, &arweave_hash, body.as_deref(), &tags)?;
+                    fetched += 1;
+                }
+                Err(_) => failed += 1,
+            }
+        }
+        Ok((fetched, failed))
+    }
+
+    /// Catch up every `interval` until an error occurs
+    pub async fn run_polling(&self, interval: Duration, on_pass: impl Fn(&PassReport)) -> Result<()> {
+        loop {
+            let report = self.catch_up().await?;
+            on_pass(&report);
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// Follow `programSubscribe` account updates, catching up on anything
+    /// missed first. The subscription is opened before catching up so no
+    /// update falls between the two.
+    pub async fn run_subscription(&self, ws_url: &str, on_pass: impl Fn(&PassReport)) -> Result<()> {
+        let pubsub = PubsubClient::new(ws_url)
+            .await
+            .map_err(|e| rpc_error(&format!("Failed to connect to {}", ws_url), e))?;
+        let config = RpcProgramAccountsConfig {
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (mut updates, unsubscribe) = pubsub
+            .program_subscribe(&self.program_id, Some(config))
+            .await
+            .map_err(|e| rpc_error("Failed to subscribe to program accounts", e))?;
+
+        on_pass(&self.catch_up().await?);
+
+        while let Some(update) = updates.next().await {
+            let slot = update.context.slot;
+            let Ok(pubkey) = Pubkey::from_str(&update.value.pubkey) else { continue };
+            let data = update
+                .value
+                .account
+                .decode::<solana_sdk::account::Account>()
+                .map(|account| account.data)
+                .unwrap_or_default();
+
+            match ProgramAccount::decode(&data) {
+                Some(account) => self.apply_account(&pubkey, account, slot)?,
+                // A closed post account; notifications carry no block time
+                None => {
+                    let now = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|elapsed| elapsed.as_secs() as i64)
+                        .unwrap_or_default();
+                    if let Some(blog) = self.db.delete_post(&pubkey, now, slot)? {
+                        self.db.recount_posts(&blog)?;
+                    }
+                }
+            }
+
+            // Only the slot is known here; catching up after a restart
+            // replays from it, which re-applies cleanly
+            let checkpoint = self.db.checkpoint()?;
+            if checkpoint.is_none_or(|checkpoint| checkpoint.slot < slot) {
+                self.db.set_checkpoint(slot, None)?;
+            }
+            let (bodies_fetched, body_errors) = self.fetch_bodies().await?;
+            if bodies_fetched + body_errors > 0 {
+                on_pass(&PassReport {
+                    transactions: 0,
+                    bodies_fetched,
+                    body_errors,
+                    slot: Some(slot),
+                });
+            }
+        }
+
+        unsubscribe().await;
+        Err(IndexerError {
+            message: "Program subscription closed".to_string(),
+        })
+    }
+}
+
+/// Apply one decoded instruction. Instructions carry everything the
+/// program stores except timestamps, which come from the block time.
+fn apply_instruction(
+    db: &Database,
+    instruction: BlogInstruction,
+    account: &dyn Fn(usize) -> Pubkey,
+    slot: u64,
+    signature: &str,
+    timestamp: i64,
+    unknown: &mut BTreeSet<Pubkey>,
+) -> Result<()> {
+    match instruction {
+        BlogInstruction::InitializeBlog { title, description } => {
+            let blog = Blog {
+                authority: account(0),
+                title,
+                description,
+                post_count: 0,
+                created_at: timestamp,
+            };
+            db.put_blog(&account(1), &blog, slot)?;
+            db.recount_posts(&account(1))?;
+        }
+        BlogInstruction::UpdateBlog { title, description } => match db.blog(&account(1))? {
+            Some(mut blog) => {
+                if let Some(title) = title {
+                    blog.title = title;
+                }
+                if let Some(description) = description {
+                    blog.description = description;
+                }
+                db.put_blog(&account(1), &blog, slot)?;
+            }
+            None => {
+                unknown.insert(account(1));
+            }
+        },
+        BlogInstruction::CreatePost {
+            title,
+            content,
+            arweave_hash,
+        } => {
+            let post = BlogPost {
+                author: account(0),
+                blog: account(2),
+                title,
+                content,
+                arweave_hash,
+                created_at: timestamp,
+                updated_at: timestamp,
+            };
+            db.put_post(&account(1), &post, slot, Some(signature))?;
+            if db.blog(&post.blog)?.is_some() {
+                db.recount_posts(&post.blog)?;
+            } else {
+                unknown.insert(post.blog);
+            }
+        }
+        BlogInstruction::UpdatePost {
+            title,
+            content,
+            arweave_hash,
+        } => match db.post(&account(1))? {
+            Some(mut post) => {
+                if let Some(title) = title {
+                    post.title = title;
+                }
+                if let Some(content) = content {
+                    post.content = content;
+                }
+                if let Some(arweave_hash) = arweave_hash {
+                    post.arweave_hash = arweave_hash;
+                }
+                post.updated_at = timestamp;
+                db.put_post(&account(1), &post, slot, Some(signature))?;
+            }
+            None => {
+                unknown.insert(account(1));
+            }
+        },
+        BlogInstruction::DeletePost => {
+            if db.delete_post(&account(1), timestamp, slot)?.is_some() {
+                db.recount_posts(&account(2))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, routing::post, Json, Router};
+    use serde_json::{json, Value};
+    use solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        transaction::Transaction,
+    };
+    use solana_transaction_status::Encodable;
+    use std::sync::{Arc, Mutex};
+
+    /// A transaction as the mock RPC serves it
+    struct MockTransaction {
+        signature: Signature,
+        slot: u64,
+        err: Value,
+        transaction: Value,
+    }
+
+    /// Program transactions, oldest first
+    #[derive(Clone, Default)]
+    struct Chain(Arc<Mutex<Vec<MockTransaction>>>);
+
+    impl Chain {
+        fn push(&self, slot: u64, instruction: Instruction, err: Value) -> Signature {
+            let signature = Signature::new_unique();
+            let payer = instruction.accounts[0].pubkey;
+            let transaction = Transaction::new_with_payer(&[instruction], Some(&payer));
+            let encoded = serde_json::to_value(transaction.encode(UiTransactionEncoding::Base64)).unwrap();
+            self.0.lock().unwrap().push(MockTransaction {
+                signature,
+                slot,
+                err,
+                transaction: encoded,
+            });
+            signature
+        }
+    }
+
+    async fn rpc(State(chain): State<Chain>, Json(request): Json<Value>) -> Json<Value> {
+        let params = &request["params"];
+        let chain = chain.0.lock().unwrap();
+        let result = match request["method"].as_str().unwrap_or_default() {
+            "getVersion" => json!({ "solana-core": "1.18.0" }),
+            "getSignaturesForAddress" => {
+                let config = &params[1];
+                let before = config["before"].as_str();
+                let until = config["until"].as_str();
+                let statuses: Vec<Value> = chain
+                    .iter()
+                    .rev()
+                    .skip_while(|tx| before.is_some_and(|before| tx.signature.to_string() != before))
+                    .skip(before.is_some() as usize)
+                    .take_while(|tx| until != Some(tx.signature.to_string().as_str()))
+                    .map(|tx| {
+                        json!({
+                            "signature": tx.signature.to_string(),
+                            "slot": tx.slot,
+                            "err": tx.err,
+                            "memo": null,
+                            "blockTime": 1_000 + tx.slot as i64,
+                            "confirmationStatus": "confirmed",
+                        })
+                    })
+                    .collect();
+                json!(statuses)
+            }
+            "getTransaction" => {
+                let tx = chain
+                    .iter()
+                    .find(|tx| params[0].as_str() == Some(tx.signature.to_string().as_str()))
+                    .unwrap();
+                json!({
+                    "slot": tx.slot,
+                    "blockTime": 1_000 + tx.slot as i64,
+                    "transaction": tx.transaction,
+                    "meta": null,
+                })
+            }
+            // Accounts are only asked for when an instruction touches one
+            // the database has not seen; here those never existed
+            "getAccountInfo" => json!({ "context": { "slot": 1 }, "value": null }),
+            method => panic!("unexpected RPC method {}", method),
+        };
+        Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }))
+    }
+
+    async fn serve(chain: Chain) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let app = Router::new().route("/", post(rpc)).with_state(chain);
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    fn instruction(program_id: Pubkey, data: &BlogInstruction, accounts: &[Pubkey]) -> Instruction {
+        let accounts = accounts.iter().map(|pubkey| AccountMeta::new(*pubkey, false)).collect();
+        Instruction::new_with_bytes(program_id, &borsh::to_vec(data).unwrap(), accounts)
+    }
+
+    fn create_post(title: &str) -> BlogInstruction {
+        BlogInstruction::CreatePost {
+            title: title.to_string(),
+            content: format!("Summary of {}", title),
+            arweave_hash: "a".repeat(43),
+        }
+    }
+
+    fn update_title(title: &str) -> BlogInstruction {
+        BlogInstruction::UpdatePost {
+            title: Some(title.to_string()),
+            content: None,
+            arweave_hash: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn catch_up_replays_transactions_since_the_checkpoint() {
+        let program_id = Pubkey::new_unique();
+        let (authority, blog, post, failed_post, unknown_post) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let system = solana_sdk::system_program::id();
+        let chain = Chain::default();
+        let initialize = BlogInstruction::InitializeBlog {
+            title: "Notes".to_string(),
+            description: "Things".to_string(),
+        };
+        chain.push(10, instruction(program_id, &initialize, &[authority, blog, system]), Value::Null);
+        chain.push(11, instruction(program_id, &create_post("First"), &[authority, post, blog, system]), Value::Null);
+        chain.push(
+            12,
+            instruction(program_id, &create_post("Failed"), &[authority, failed_post, blog, system]),
+            json!({ "InstructionError": [0, { "Custom": 1 }] }),
+        );
+        chain.push(13, instruction(program_id, &update_title("First, edited"), &[authority, post, system]), Value::Null);
+        let last = chain.push(
+            13,
+            instruction(program_id, &update_title("Elsewhere"), &[authority, unknown_post, system]),
+            Value::Null,
+        );
+
+        let indexer = Indexer::new(&serve(chain.clone()).await, program_id, Database::open_in_memory().unwrap());
+        let report = indexer.catch_up().await.unwrap();
+        assert_eq!(
+            report,
+            PassReport {
+                transactions: 5,
+                slot: Some(13),
+                ..PassReport::default()
+            }
+        );
+
+        let db = indexer.database();
+        assert_eq!(db.blog(&blog).unwrap().unwrap().post_count, 1);
+        let indexed = db.post(&post).unwrap().unwrap();
+        assert_eq!(indexed.title, "First, edited");
+        assert_eq!((indexed.created_at, indexed.updated_at), (1_011, 1_013));
+        assert!(db.post(&failed_post).unwrap().is_none());
+        assert!(db.post(&unknown_post).unwrap().is_none());
+        let checkpoint = db.checkpoint().unwrap().unwrap();
+        assert_eq!((checkpoint.slot, checkpoint.signature), (13, Some(last.to_string())));
+
+        // Only what landed after the checkpoint is applied again
+        chain.push(14, instruction(program_id, &BlogInstruction::DeletePost, &[authority, post, blog]), Value::Null);
+        let report = indexer.catch_up().await.unwrap();
+        assert_eq!((report.transactions, report.slot), (1, Some(14)));
+        assert_eq!(db.blog(&blog).unwrap().unwrap().post_count, 0);
+        assert_eq!(indexer.catch_up().await.unwrap().transactions, 0);
+    }
+}
@@ -0,0 +1,36 @@
+pub mod api;
+pub mod db;
+pub mod indexer;
+
+pub use db::{BlogRow, Checkpoint, Database, PostPage, PostRow};
+pub use indexer::{Indexer, PassReport};
+pub use solana_blog_program::state::ProgramAccount;
+
+#[derive(Debug, Clone)]
+pub struct IndexerError {
+    pub message: String,
+}
+
+impl std::fmt::Display for IndexerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for IndexerError {}
+
+impl From<rusqlite::Error> for IndexerError {
+    fn from(e: rusqlite::Error) -> Self {
+        Self {
+            message: format!("Database error: {}", e),
+        }
+    }
+}
+
+impl From<solana_blog_client::BlogClientError> for IndexerError {
+    fn from(e: solana_blog_client::BlogClientError) -> Self {
+        Self { message: e.message }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, IndexerError>;
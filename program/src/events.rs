@@ -0,0 +1,75 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+
+/// Leads every event so it can be told apart from other `Program data:`
+/// logs
+pub const EVENT_DISCRIMINATOR: [u8; 8] = *b"blogevt1";
+
+/// A state change, logged with `sol_log_data` as the discriminator
+/// followed by the borsh-serialized event. Variants are only ever
+/// appended so older events keep decoding.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum BlogEvent {
+    BlogInitialized {
+        blog: Pubkey,
+        authority: Pubkey,
+        title: String,
+        description: String,
+        created_at: i64,
+    },
+
+    PostCreated {
+        post: Pubkey,
+        blog: Pubkey,
+        author: Pubkey,
+        title: String,
+        content: String,
+        arweave_hash: String,
+        created_at: i64,
+    },
+
+    /// Only fields whose value changed are set
+    PostUpdated {
+        post: Pubkey,
+        blog: Pubkey,
+        title: Option<String>,
+        content: Option<String>,
+        arweave_hash: Option<String>,
+        updated_at: i64,
+    },
+
+    /// Only fields whose value changed are set
+    BlogUpdated {
+        blog: Pubkey,
+        title: Option<String>,
+        description: Option<String>,
+    },
+
+    PostDeleted {
+        post: Pubkey,
+        blog: Pubkey,
+        author: Pubkey,
+    },
+}
+
+impl BlogEvent {
+    /// Log the event; it shows in the transaction's logs as
+    /// `Program data: <base64>`
+    pub fn emit(&self) {
+        let mut data = EVENT_DISCRIMINATOR.to_vec();
+        if self.serialize(&mut data).is_ok() {
+            sol_log_data(&[&data]);
+        }
+    }
+
+    /// Decode one `sol_log_data` field, if it holds an event
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let event = data.strip_prefix(&EVENT_DISCRIMINATOR)?;
+        Self::try_from_slice(event).ok()
+    }
+}
+
+/// `new` if it differs from `old`, for the changed fields of update events
+pub(crate) fn changed<T: PartialEq>(old: &T, new: T) -> Option<T> {
+    (*old != new).then_some(new)
+}
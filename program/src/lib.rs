@@ -2,9 +2,10 @@ use solana_program::{
     account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey,
 };
 
-pub mod instruction;
+pub mod events;
 pub mod processor;
-pub mod state;
+
+pub use solana_blog_types::{instruction, state};
 
 use crate::processor::Processor;
 
@@ -1,4 +1,8 @@
-use crate::{instruction::BlogInstruction, state::{Blog, BlogPost}};
+use crate::{
+    events::{changed, BlogEvent},
+    instruction::BlogInstruction,
+    state::{Blog, BlogPost},
+};
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
@@ -33,6 +37,10 @@ impl Processor {
             BlogInstruction::UpdatePost { title, content, arweave_hash } => {
                 Self::process_update_post(program_id, accounts, title, content, arweave_hash)
             }
+            BlogInstruction::UpdateBlog { title, description } => {
+                Self::process_update_blog(program_id, accounts, title, description)
+            }
+            BlogInstruction::DeletePost => Self::process_delete_post(program_id, accounts),
         }
     }
 
@@ -89,6 +97,14 @@ impl Processor {
 
         blog.serialize(&mut &mut blog_info.data.borrow_mut()[..])?;
 
+        BlogEvent::BlogInitialized {
+            blog: *blog_info.key,
+            authority: blog.authority,
+            title: blog.title,
+            description: blog.description,
+            created_at: blog.created_at,
+        }
+        .emit();
         msg!("Blog initialized successfully");
         Ok(())
     }
@@ -162,12 +178,22 @@ impl Processor {
         blog.post_count += 1;
         blog.serialize(&mut &mut blog_data[..])?;
 
+        BlogEvent::PostCreated {
+            post: *post_info.key,
+            blog: blog_post.blog,
+            author: blog_post.author,
+            title: blog_post.title,
+            content: blog_post.content,
+            arweave_hash: blog_post.arweave_hash,
+            created_at: blog_post.created_at,
+        }
+        .emit();
         msg!("Blog post created successfully");
         Ok(())
     }
 
     fn process_update_post(
-        _program_id: &Pubkey,
+        program_id: &Pubkey,
         accounts: &[AccountInfo],
         title: Option<String>,
         content: Option<String>,
@@ -176,17 +202,24 @@ impl Processor {
         let account_info_iter = &mut accounts.iter();
         let author_info = next_account_info(account_info_iter)?;
         let post_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
 
         if !author_info.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
-        let mut post_data = post_info.try_borrow_mut_data()?;
-        let mut blog_post = BlogPost::try_from_slice(&post_data)?;
+        if post_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // Not `try_from_slice`: posts shortened before updates resized the
+        // account have trailing bytes, which this update trims
+        let mut blog_post = BlogPost::deserialize(&mut &post_info.data.borrow()[..])?;
 
         if blog_post.author != *author_info.key {
             return Err(ProgramError::InvalidAccountData);
         }
+        let previous = blog_post.clone();
 
         if let Some(new_title) = title {
             if new_title.len() > BlogPost::MAX_TITLE_LENGTH {
@@ -212,9 +245,502 @@ impl Processor {
         let clock = Clock::get()?;
         blog_post.updated_at = clock.unix_timestamp;
 
-        blog_post.serialize(&mut &mut post_data[..])?;
+        let space = BlogPost::get_size(&blog_post.title, &blog_post.content, &blog_post.arweave_hash);
+        Self::resize(author_info, post_info, system_program_info, space)?;
+        blog_post.serialize(&mut &mut post_info.data.borrow_mut()[..])?;
 
+        BlogEvent::PostUpdated {
+            post: *post_info.key,
+            blog: blog_post.blog,
+            title: changed(&previous.title, blog_post.title),
+            content: changed(&previous.content, blog_post.content),
+            arweave_hash: changed(&previous.arweave_hash, blog_post.arweave_hash),
+            updated_at: blog_post.updated_at,
+        }
+        .emit();
         msg!("Blog post updated successfully");
         Ok(())
     }
-} 
\ No newline at end of file
+
+    fn process_update_blog(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        title: Option<String>,
+        description: Option<String>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let authority_info = next_account_info(account_info_iter)?;
+        let blog_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !authority_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if blog_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let mut blog = Blog::try_from_slice(&blog_info.data.borrow())?;
+
+        if blog.authority != *authority_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let previous = blog.clone();
+
+        if let Some(new_title) = title {
+            if new_title.len() > Blog::MAX_TITLE_LENGTH {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            blog.title = new_title;
+        }
+
+        if let Some(new_description) = description {
+            if new_description.len() > Blog::MAX_DESCRIPTION_LENGTH {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            blog.description = new_description;
+        }
+
+        let space = Blog::get_size(&blog.title, &blog.description);
+        Self::resize(authority_info, blog_info, system_program_info, space)?;
+        blog.serialize(&mut &mut blog_info.data.borrow_mut()[..])?;
+
+        BlogEvent::BlogUpdated {
+            blog: *blog_info.key,
+            title: changed(&previous.title, blog.title),
+            description: changed(&previous.description, blog.description),
+        }
+        .emit();
+        msg!("Blog updated successfully");
+        Ok(())
+    }
+
+    /// Resize `account`, whose size follows its strings, to `space` bytes,
+    /// settling the rent difference with `payer`
+    fn resize<'a>(
+        payer: &AccountInfo<'a>,
+        account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        space: usize,
+    ) -> ProgramResult {
+        let rent_lamports = Rent::get()?.minimum_balance(space);
+        let current_lamports = account.lamports();
+
+        if rent_lamports > current_lamports {
+            invoke(
+                &system_instruction::transfer(
+                    payer.key,
+                    account.key,
+                    rent_lamports - current_lamports,
+                ),
+                &[payer.clone(), account.clone(), system_program.clone()],
+            )?;
+        } else if rent_lamports < current_lamports {
+            let refund = current_lamports - rent_lamports;
+            **account.try_borrow_mut_lamports()? -= refund;
+            **payer.try_borrow_mut_lamports()? += refund;
+        }
+
+        account.realloc(space, false)
+    }
+
+    fn process_delete_post(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let author_info = next_account_info(account_info_iter)?;
+        let post_info = next_account_info(account_info_iter)?;
+        let blog_info = next_account_info(account_info_iter)?;
+
+        if !author_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if post_info.owner != program_id || blog_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let blog_post = BlogPost::try_from_slice(&post_info.data.borrow())?;
+
+        if blog_post.author != *author_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if blog_post.blog != *blog_info.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut blog_data = blog_info.try_borrow_mut_data()?;
+        let mut blog = Blog::try_from_slice(&blog_data)?;
+        blog.post_count = blog.post_count.saturating_sub(1);
+        blog.serialize(&mut &mut blog_data[..])?;
+
+        // Close the post account: refund its rent and clear its data so it
+        // can no longer be read as a post
+        let lamports = post_info.lamports();
+        **post_info.try_borrow_mut_lamports()? = 0;
+        **author_info.try_borrow_mut_lamports()? += lamports;
+        post_info.data.borrow_mut().fill(0);
+        post_info.realloc(0, false)?;
+
+        BlogEvent::PostDeleted {
+            post: *post_info.key,
+            blog: blog_post.blog,
+            author: blog_post.author,
+        }
+        .emit();
+        msg!("Blog post deleted successfully");
+        Ok(())
+    }
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use solana_program::{
+        account_info::AccountInfo,
+        instruction::{AccountMeta, Instruction, InstructionError},
+        program_stubs::{set_syscall_stubs, SyscallStubs},
+        system_program,
+    };
+    use solana_program_test::{processor, BanksClient, BanksClientError, ProgramTest};
+    use solana_sdk::{
+        signature::{Keypair, Signer},
+        transaction::{Transaction, TransactionError},
+    };
+
+    /// Prefix of the log line `LogDataStubs` writes for `sol_log_data`
+    const DATA_LOG: &str = "Program log: data: ";
+
+    /// program-test's stubs for native processors only print
+    /// `sol_log_data` to stdout, so forward it to the transaction log as
+    /// `data: <base64>...` and delegate everything else unchanged
+    struct LogDataStubs(Box<dyn SyscallStubs>);
+
+    impl LogDataStubs {
+        fn install() {
+            static INSTALL: std::sync::Once = std::sync::Once::new();
+            INSTALL.call_once(|| {
+                let inner = set_syscall_stubs(Box::new(LogDataStubs(Box::new(UnsetStubs))));
+                set_syscall_stubs(Box::new(LogDataStubs(inner)));
+            });
+        }
+    }
+
+    /// Placeholder while the real stubs are swapped out
+    struct UnsetStubs;
+    impl SyscallStubs for UnsetStubs {}
+
+    impl SyscallStubs for LogDataStubs {
+        fn sol_log(&self, message: &str) {
+            self.0.sol_log(message)
+        }
+        fn sol_log_data(&self, fields: &[&[u8]]) {
+            let fields: Vec<String> = fields.iter().map(|field| STANDARD.encode(field)).collect();
+            self.0.sol_log(&format!("data: {}", fields.join(" ")))
+        }
+        fn sol_invoke_signed(
+            &self,
+            instruction: &Instruction,
+            account_infos: &[AccountInfo],
+            signers_seeds: &[&[&[u8]]],
+        ) -> ProgramResult {
+            self.0.sol_invoke_signed(instruction, account_infos, signers_seeds)
+        }
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            self.0.sol_get_clock_sysvar(var_addr)
+        }
+        fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+            self.0.sol_get_epoch_schedule_sysvar(var_addr)
+        }
+        fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+            self.0.sol_get_epoch_rewards_sysvar(var_addr)
+        }
+        fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+            self.0.sol_get_fees_sysvar(var_addr)
+        }
+        fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+            self.0.sol_get_rent_sysvar(var_addr)
+        }
+        fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+            self.0.sol_get_last_restart_slot(var_addr)
+        }
+        fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+            self.0.sol_get_return_data()
+        }
+        fn sol_set_return_data(&self, data: &[u8]) {
+            self.0.sol_set_return_data(data)
+        }
+        fn sol_get_stack_height(&self) -> u64 {
+            self.0.sol_get_stack_height()
+        }
+    }
+
+    struct Context {
+        banks: BanksClient,
+        payer: Keypair,
+        blog: Pubkey,
+    }
+
+    impl Context {
+        /// A test validator holding one blog owned by the payer
+        async fn new() -> Self {
+            let program_test = ProgramTest::new(
+                "solana_blog_program",
+                crate::id(),
+                processor!(crate::process_instruction),
+            );
+            let (banks, payer, _) = program_test.start().await;
+            LogDataStubs::install();
+            let blog = Keypair::new();
+            let mut context = Self {
+                banks,
+                payer,
+                blog: blog.pubkey(),
+            };
+            let initialize = context.instruction(
+                BlogInstruction::InitializeBlog {
+                    title: "Blog".to_string(),
+                    description: "About things".to_string(),
+                },
+                vec![
+                    AccountMeta::new(context.payer.pubkey(), true),
+                    AccountMeta::new(blog.pubkey(), true),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            );
+            context.send(initialize, &[&blog]).await.unwrap();
+            context
+        }
+
+        fn instruction(&self, data: BlogInstruction, accounts: Vec<AccountMeta>) -> Instruction {
+            Instruction::new_with_bytes(crate::id(), &borsh::to_vec(&data).unwrap(), accounts)
+        }
+
+        async fn send(
+            &mut self,
+            instruction: Instruction,
+            signers: &[&Keypair],
+        ) -> Result<(), BanksClientError> {
+            let blockhash = self.banks.get_latest_blockhash().await.unwrap();
+            let mut all_signers = vec![&self.payer];
+            all_signers.extend_from_slice(signers);
+            let transaction = Transaction::new_signed_with_payer(
+                &[instruction],
+                Some(&self.payer.pubkey()),
+                &all_signers,
+                blockhash,
+            );
+            self.banks.process_transaction(transaction).await
+        }
+
+        /// Send `instruction` signed by the payer alone and decode the
+        /// events it logged
+        async fn events(&mut self, instruction: Instruction) -> Vec<BlogEvent> {
+            let blockhash = self.banks.get_latest_blockhash().await.unwrap();
+            let transaction = Transaction::new_signed_with_payer(
+                &[instruction],
+                Some(&self.payer.pubkey()),
+                &[&self.payer],
+                blockhash,
+            );
+            let outcome = self.banks.process_transaction_with_metadata(transaction).await.unwrap();
+            outcome.result.unwrap();
+            outcome
+                .metadata
+                .unwrap()
+                .log_messages
+                .iter()
+                .filter_map(|log| log.strip_prefix(DATA_LOG))
+                .filter_map(|data| STANDARD.decode(data).ok())
+                .filter_map(|data| BlogEvent::decode(&data))
+                .collect()
+        }
+
+        async fn create_post(&mut self, title: &str) -> Pubkey {
+            let post = Keypair::new();
+            let create = self.instruction(
+                BlogInstruction::CreatePost {
+                    title: title.to_string(),
+                    content: "Summary".to_string(),
+                    arweave_hash: "a".repeat(43),
+                },
+                vec![
+                    AccountMeta::new(self.payer.pubkey(), true),
+                    AccountMeta::new(post.pubkey(), true),
+                    AccountMeta::new(self.blog, false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            );
+            self.send(create, &[&post]).await.unwrap();
+            post.pubkey()
+        }
+
+        fn update_blog(&self, authority: Pubkey, title: &str) -> Instruction {
+            self.instruction(
+                BlogInstruction::UpdateBlog {
+                    title: Some(title.to_string()),
+                    description: None,
+                },
+                vec![
+                    AccountMeta::new(authority, true),
+                    AccountMeta::new(self.blog, false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            )
+        }
+
+        fn update_post_title(&self, author: Pubkey, post: Pubkey, title: &str) -> Instruction {
+            self.instruction(
+                BlogInstruction::UpdatePost {
+                    title: Some(title.to_string()),
+                    content: None,
+                    arweave_hash: None,
+                },
+                vec![
+                    AccountMeta::new(author, true),
+                    AccountMeta::new(post, false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            )
+        }
+
+        fn delete_post(&self, author: Pubkey, post: Pubkey) -> Instruction {
+            self.instruction(
+                BlogInstruction::DeletePost,
+                vec![
+                    AccountMeta::new(author, true),
+                    AccountMeta::new(post, false),
+                    AccountMeta::new(self.blog, false),
+                ],
+            )
+        }
+
+        /// The account's data, after checking it holds exactly its rent
+        /// exempt minimum
+        async fn rent_exempt_data(&mut self, address: Pubkey) -> Vec<u8> {
+            let account = self.banks.get_account(address).await.unwrap().unwrap();
+            let rent = self.banks.get_rent().await.unwrap();
+            assert_eq!(account.lamports, rent.minimum_balance(account.data.len()));
+            account.data
+        }
+
+        async fn blog(&mut self) -> Blog {
+            let data = self.rent_exempt_data(self.blog).await;
+            Blog::try_from_slice(&data).unwrap()
+        }
+    }
+
+    fn instruction_error(error: BanksClientError) -> InstructionError {
+        match error.unwrap() {
+            TransactionError::InstructionError(0, error) => error,
+            error => panic!("unexpected error: {:?}", error),
+        }
+    }
+
+    #[tokio::test]
+    async fn update_blog_resizes_the_account_both_ways() {
+        let mut context = Context::new().await;
+        let authority = context.payer.pubkey();
+
+        let longer = "A much longer title than the blog started with";
+        context.send(context.update_blog(authority, longer), &[]).await.unwrap();
+        let blog = context.blog().await;
+        assert_eq!(blog.title, longer);
+        assert_eq!(blog.description, "About things");
+
+        context.send(context.update_blog(authority, "B"), &[]).await.unwrap();
+        let blog = context.blog().await;
+        assert_eq!(blog.title, "B");
+        assert_eq!(blog.description, "About things");
+    }
+
+    #[tokio::test]
+    async fn update_blog_requires_the_authority() {
+        let mut context = Context::new().await;
+        let stranger = Keypair::new();
+
+        let error = context
+            .send(context.update_blog(stranger.pubkey(), "Taken over"), &[&stranger])
+            .await
+            .unwrap_err();
+        assert_eq!(instruction_error(error), InstructionError::InvalidAccountData);
+        assert_eq!(context.blog().await.title, "Blog");
+    }
+
+    #[tokio::test]
+    async fn update_post_resizes_the_account_both_ways() {
+        let mut context = Context::new().await;
+        let post = context.create_post("Short").await;
+        let author = context.payer.pubkey();
+
+        for title in ["A title several times longer than the first one", "S"] {
+            context.send(context.update_post_title(author, post, title), &[]).await.unwrap();
+            let data = context.rent_exempt_data(post).await;
+            let updated = BlogPost::try_from_slice(&data).unwrap();
+            assert_eq!(updated.title, title);
+            assert_eq!(updated.content, "Summary");
+        }
+    }
+
+    #[tokio::test]
+    async fn update_post_event_carries_only_changed_fields() {
+        let mut context = Context::new().await;
+        let post = context.create_post("Before").await;
+        let author = context.payer.pubkey();
+
+        let events = context.events(context.update_post_title(author, post, "After")).await;
+        match events.as_slice() {
+            [BlogEvent::PostUpdated { post: updated, blog, title, content, arweave_hash, .. }] => {
+                assert_eq!(*updated, post);
+                assert_eq!(*blog, context.blog);
+                assert_eq!(title.as_deref(), Some("After"));
+                assert_eq!(*content, None);
+                assert_eq!(*arweave_hash, None);
+            }
+            events => panic!("unexpected events: {:?}", events),
+        }
+    }
+
+    #[tokio::test]
+    async fn update_post_requires_the_author() {
+        let mut context = Context::new().await;
+        let post = context.create_post("Mine").await;
+        let stranger = Keypair::new();
+
+        let error = context
+            .send(context.update_post_title(stranger.pubkey(), post, "Theirs"), &[&stranger])
+            .await
+            .unwrap_err();
+        assert_eq!(instruction_error(error), InstructionError::InvalidAccountData);
+    }
+
+    #[tokio::test]
+    async fn delete_post_closes_the_account() {
+        let mut context = Context::new().await;
+        let post = context.create_post("Doomed").await;
+        assert_eq!(context.blog().await.post_count, 1);
+
+        let author = context.payer.pubkey();
+        context.send(context.delete_post(author, post), &[]).await.unwrap();
+
+        assert!(context.banks.get_account(post).await.unwrap().is_none());
+        assert_eq!(context.blog().await.post_count, 0);
+    }
+
+    #[tokio::test]
+    async fn delete_post_requires_the_author() {
+        let mut context = Context::new().await;
+        let post = context.create_post("Kept").await;
+        let stranger = Keypair::new();
+
+        let error = context
+            .send(context.delete_post(stranger.pubkey(), post), &[&stranger])
+            .await
+            .unwrap_err();
+        assert_eq!(instruction_error(error), InstructionError::InvalidAccountData);
+        let data = context.rent_exempt_data(post).await;
+        assert_eq!(BlogPost::try_from_slice(&data).unwrap().title, "Kept");
+        assert_eq!(context.blog().await.post_count, 1);
+    }
+}
@@ -33,9 +33,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // For now, this will fail unless the authority has SOL
     
     match client.initialize_blog(&authority, title, description).await {
-        Ok(blog_pubkey) => {
+        Ok((blog_pubkey, send_result)) => {
             println!("✅ Blog created successfully!");
             println!("📝 Blog Pubkey: {}", blog_pubkey);
+            println!("🧾 Signature: {} (slot {}, fee {} lamports)", send_result.signature, send_result.slot, send_result.fee_lamports);
             println!("🔗 Update frontend/src/config.rs with:");
             println!("   pub const DEMO_BLOG_PUBKEY: &str = \"{}\";", blog_pubkey);
         }
@@ -0,0 +1,76 @@
+use crate::state::{Blog, BlogPost};
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey::Pubkey;
+
+/// A blog as shown to readers
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BlogInfo {
+    #[serde(with = "pubkey_string")]
+    pub pubkey: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub authority: Pubkey,
+    pub title: String,
+    pub description: String,
+    pub post_count: u64,
+    pub created_at: i64,
+}
+
+impl BlogInfo {
+    pub fn new(pubkey: Pubkey, blog: Blog) -> Self {
+        Self {
+            pubkey,
+            authority: blog.authority,
+            title: blog.title,
+            description: blog.description,
+            post_count: blog.post_count,
+            created_at: blog.created_at,
+        }
+    }
+}
+
+/// A post as shown to readers; `content` is the on-chain summary and the
+/// body lives at `arweave_hash`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PostInfo {
+    #[serde(with = "pubkey_string")]
+    pub pubkey: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub author: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub blog: Pubkey,
+    pub title: String,
+    pub content: String,
+    pub arweave_hash: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl PostInfo {
+    pub fn new(pubkey: Pubkey, post: BlogPost) -> Self {
+        Self {
+            pubkey,
+            author: post.author,
+            blog: post.blog,
+            title: post.title,
+            content: post.content,
+            arweave_hash: post.arweave_hash,
+            created_at: post.created_at,
+            updated_at: post.updated_at,
+        }
+    }
+}
+
+/// Pubkeys as base58 strings, matching the RPC and the indexer API
+mod pubkey_string {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use solana_program::pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(pubkey)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(|e| D::Error::custom(format!("Invalid pubkey {}: {:?}", text, e)))
+    }
+}
@@ -22,15 +22,35 @@ pub enum BlogInstruction {
         arweave_hash: String,
     },
 
-    /// Update an existing blog post
+    /// Update an existing blog post, resizing its account to fit. The
+    /// system program account is new; deployments older than it only take
+    /// the first two accounts and must be redeployed.
     /// Accounts:
-    /// 0. `[signer]` Post author
+    /// 0. `[signer, writable]` Post author, pays for any extra rent
     /// 1. `[writable]` Post account
+    /// 2. `[]` System program
     UpdatePost {
         title: Option<String>,
         content: Option<String>,
         arweave_hash: Option<String>,
     },
+
+    /// Update a blog's title or description
+    /// Accounts:
+    /// 0. `[signer, writable]` Blog authority, pays for any extra rent
+    /// 1. `[writable]` Blog account
+    /// 2. `[]` System program
+    UpdateBlog {
+        title: Option<String>,
+        description: Option<String>,
+    },
+
+    /// Delete a blog post, returning its rent to the author
+    /// Accounts:
+    /// 0. `[signer, writable]` Post author
+    /// 1. `[writable]` Post account
+    /// 2. `[writable]` Blog account
+    DeletePost,
 }
 
 impl BlogInstruction {
@@ -0,0 +1,10 @@
+pub mod arweave;
+pub mod info;
+pub mod instruction;
+pub mod state;
+
+pub use info::{BlogInfo, PostInfo};
+pub use solana_program::pubkey::Pubkey;
+
+/// Winston are the smallest AR unit
+pub const WINSTON_PER_AR: u64 = 1_000_000_000_000;
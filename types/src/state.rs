@@ -37,7 +37,9 @@ pub struct BlogPost {
 impl BlogPost {
     pub const MAX_TITLE_LENGTH: usize = 200;
     pub const MAX_CONTENT_LENGTH: usize = 1000;
-    pub const MAX_ARWEAVE_HASH_LENGTH: usize = 43; // Standard Arweave hash length
+    pub const MAX_ARWEAVE_HASH_LENGTH: usize = 64; // Arweave ids are 43 chars, IPFS CIDv1 up to 59
+    /// Offset of `blog`, after the author; filter on it to list a blog's posts
+    pub const BLOG_OFFSET: usize = 32;
     
     pub fn get_size(title: &str, content: &str, arweave_hash: &str) -> usize {
         32 + // author
@@ -48,4 +50,25 @@ impl BlogPost {
         8 + // created_at
         8   // updated_at
     }
-} 
\ No newline at end of file
+}
+
+/// A decoded account owned by the blog program
+#[derive(Debug, Clone)]
+pub enum ProgramAccount {
+    Blog(Blog),
+    Post(BlogPost),
+}
+
+impl ProgramAccount {
+    /// Accounts are sized exactly to their contents, so only one of the two
+    /// layouts consumes all of `data`. Closed accounts decode to `None`.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.is_empty() {
+            return None;
+        }
+        BlogPost::try_from_slice(data)
+            .map(Self::Post)
+            .or_else(|_| Blog::try_from_slice(data).map(Self::Blog))
+            .ok()
+    }
+}